@@ -0,0 +1,782 @@
+// Backends d'archivage : FreeArc, 7-Zip et Sharky.
+// Toute la construction des lignes de commande passe par ici, pour que l'interface
+// graphique, la ligne de commande et les presets choisissent l'outil de la même façon.
+
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::encryption::{EncryptionAlgorithm, EncryptionSettings};
+use crate::listing::{self, ArchiveEntry};
+use crate::method;
+use crate::overwrite::OverwritePolicy;
+use crate::queue::UpdateOp;
+use crate::recovery::RecoverySize;
+use crate::sfx;
+use crate::volumes;
+use crate::verify::TestLine;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BackendKind {
+    FreeArc,
+    SevenZip,
+    Sharky,
+}
+
+/// Ce que chaque outil sait faire (utilisé pour griser les options de l'interface)
+#[derive(Debug, Clone, Copy)]
+pub struct BackendCapabilities {
+    pub list: bool,
+    pub test: bool,
+    pub multiple_sources: bool,
+    pub sfx: bool,
+    pub encryption: bool,
+    pub volumes: bool, // Découpage en volumes natif
+    pub update: bool,  // Ajout, actualisation et suppression dans une archive existante
+    pub rename: bool,
+    pub recovery: bool, // Enregistrement de récupération et réparation
+}
+
+/// Informations de progression extraites d'une ligne de sortie
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ProgressInfo {
+    pub fraction: Option<f32>,   // 0.0 à 1.0
+    pub total_bytes: Option<u64>, // Taille totale annoncée par l'outil
+}
+
+pub trait ArchiveBackend: Sync {
+    fn kind(&self) -> BackendKind;
+    fn name(&self) -> &'static str;
+    fn executable(&self) -> PathBuf;
+    fn capabilities(&self) -> BackendCapabilities;
+    /// Extensions proposées pour la sortie (sans le point), la première est celle par défaut
+    fn extensions(&self) -> &'static [&'static str];
+
+    fn compress_command(&self, archive: &Path, sources: &[PathBuf], flags: &[&str]) -> Command;
+    fn extract_command(&self, archive: &Path, dest: &Path) -> Command;
+    /// Extraction sélective : `list_file` contient un chemin d'entrée par ligne
+    fn extract_files_command(&self, archive: &Path, dest: &Path, list_file: &Path) -> Option<Command>;
+    fn list_command(&self, archive: &Path) -> Option<Command>;
+    fn test_command(&self, archive: &Path) -> Option<Command>;
+    /// Source de compression désignant un fichier liste (un chemin par ligne) ;
+    /// None si l'outil ne lit pas de liste
+    fn list_file_source(&self, list_file: &Path) -> Option<PathBuf>;
+    /// Options d'extraction appliquant la politique d'écrasement (les commandes d'extraction
+    /// remplacent par défaut) ; None si l'outil ne sait pas l'appliquer seul
+    fn overwrite_args(&self, policy: OverwritePolicy) -> Option<Vec<String>>;
+
+    /// Modification d'une archive existante. `list_file` contient les entrées visées
+    /// (actualisation, suppression), une par ligne ; None si l'opération n'est pas supportée
+    fn update_command(&self, archive: &Path, op: &UpdateOp, sources: &[PathBuf], list_file: Option<&Path>, flags: &[&str]) -> Option<Command>;
+
+    /// Options intégrant le module SFX à la compression ; None si l'archive doit être
+    /// accolée au module après coup (module + configuration + archive)
+    fn sfx_args(&self, module: &Path, config: &Path) -> Option<Vec<String>>;
+    /// Auto-test d'un exécutable auto-extractible produit
+    fn sfx_test_command(&self, sfx: &Path) -> Option<Command>;
+
+    /// Modules SFX installés avec l'outil (fichiers .sfx à côté de l'exécutable)
+    fn sfx_modules(&self) -> Vec<PathBuf> {
+        let exe = self.executable();
+        sfx::find_modules(&[exe.parent().map(Path::to_path_buf).unwrap_or_default()])
+    }
+
+    /// Options de chiffrement ajoutées à la compression ; erreur si la combinaison
+    /// n'est pas supportée par l'outil ou le format
+    fn encryption_args(&self, archive: &Path, settings: &EncryptionSettings, password: &str) -> Result<Vec<String>, String>;
+    /// Mot de passe pour extraire, tester ou lister une archive chiffrée
+    fn password_args(&self, password: &str) -> Vec<String>;
+
+    /// Options de compression avec le nombre de threads imposé
+    fn thread_flags(&self, flags: &[&str], threads: usize) -> Vec<String>;
+    /// Besoins en mémoire (compression, décompression) estimés pour ces options ;
+    /// None si l'outil ou la méthode ne sont pas estimables
+    fn estimate_memory(&self, flags: &[&str], cpus: usize) -> Option<(u64, u64)>;
+
+    /// Options de découpage en volumes (vide si `capabilities().volumes` est faux)
+    fn volume_args(&self, volume_size: u64) -> Vec<String>;
+
+    /// Options ajoutant un enregistrement de récupération (vide si `capabilities().recovery` est faux)
+    fn recovery_args(&self, size: &RecoverySize) -> Vec<String>;
+    /// Réparation d'une archive endommagée grâce à son enregistrement de récupération
+    fn repair_command(&self, archive: &Path) -> Option<Command>;
+    /// Présence d'un enregistrement de récupération d'après la sortie de `list_command` ;
+    /// None si l'outil ne l'indique pas
+    fn parse_recovery_record(&self, output: &str) -> Option<bool>;
+
+    /// Options désignant le fichier des groupes de fichiers ($text, $exe...) ;
+    /// None si l'outil ne connaît pas les groupes
+    fn groups_args(&self, groups_file: &Path) -> Option<Vec<String>>;
+
+    /// Extrait la progression d'une ligne de sortie de l'outil
+    fn parse_progress(&self, line: &str) -> ProgressInfo;
+
+    /// Analyse la sortie de `list_command`
+    fn parse_listing(&self, output: &str) -> Vec<ArchiveEntry>;
+
+    /// Résultat d'un fichier dans la sortie de `test_command`
+    fn parse_test_line(&self, line: &str) -> Option<TestLine>;
+
+    fn is_available(&self) -> bool {
+        let exe = self.executable();
+        if exe.components().count() > 1 {
+            return exe.exists();
+        }
+        // Nom nu : chercher dans le PATH
+        std::env::var_os("PATH")
+            .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(&exe).is_file()))
+            .unwrap_or(false)
+    }
+}
+
+impl BackendKind {
+    pub fn all() -> &'static [BackendKind] {
+        static ALL: [BackendKind; 3] = [BackendKind::FreeArc, BackendKind::SevenZip, BackendKind::Sharky];
+        &ALL
+    }
+
+    pub fn backend(self) -> &'static dyn ArchiveBackend {
+        match self {
+            BackendKind::FreeArc => &FreeArcBackend,
+            BackendKind::SevenZip => &SevenZipBackend,
+            BackendKind::Sharky => &SharkyBackend,
+        }
+    }
+
+    /// Choix de l'outil d'après l'extension de l'archive : 7-Zip pour les formats courants
+    /// (7z, zip, rar, iso, bz2, gz, xz, tar, tgz), Sharky pour les siens (stel, zstd, lzma,
+    /// br, lz4), FreeArc pour le reste. Contrairement à extract.bat, qui confie la plupart
+    /// des formats courants à Sharky, 7-Zip est préféré : il sait lister et tester l'archive
+    pub fn for_archive(path: &Path) -> BackendKind {
+        // archive.7z.001 : l'outil dépend de l'extension qui précède le numéro
+        if volumes::is_volume(path) {
+            return Self::for_archive(&volumes::base_archive(path));
+        }
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+        Self::for_extension(&ext).unwrap_or(BackendKind::FreeArc)
+    }
+
+    /// Outil d'une extension d'archive connue (en minuscules, sans le point)
+    fn for_extension(ext: &str) -> Option<BackendKind> {
+        match ext {
+            "7z" | "zip" | "rar" | "iso" | "bz2" | "gz" | "xz" | "tar" | "tgz" => Some(BackendKind::SevenZip),
+            "stel" | "zstd" | "lzma" | "br" | "lz4" => Some(BackendKind::Sharky),
+            ext if FreeArcBackend.extensions().contains(&ext) => Some(BackendKind::FreeArc),
+            _ => None,
+        }
+    }
+
+    /// Extension d'un format d'archive connu, quelle que soit la casse
+    pub fn is_archive_extension(ext: &str) -> bool {
+        Self::for_extension(&ext.to_ascii_lowercase()).is_some()
+    }
+}
+
+/// Lit la sortie d'un outil en découpant aussi sur '\r' et '\x08' : FreeArc et 7-Zip
+/// réécrivent leur ligne de progression sans jamais envoyer de '\n'.
+/// Le booléen indique si le segment est une vraie ligne (terminée par '\n').
+pub fn read_output<R: Read>(mut reader: R, mut on_segment: impl FnMut(&str, bool)) {
+    let mut buf = [0u8; 4096];
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        for &b in &buf[..n] {
+            match b {
+                b'\n' | b'\r' | 0x08 => {
+                    let text = String::from_utf8_lossy(&pending);
+                    let text = text.trim_end();
+                    if !text.trim().is_empty() {
+                        on_segment(text, b == b'\n');
+                    }
+                    pending.clear();
+                }
+                _ => pending.push(b),
+            }
+        }
+    }
+    let text = String::from_utf8_lossy(&pending);
+    if !text.trim().is_empty() {
+        on_segment(text.trim_end(), true);
+    }
+}
+
+/// Mot de la forme "42%" ou "42.5%"
+fn percent_token(token: &str) -> Option<f32> {
+    let value: f32 = token.strip_suffix('%')?.parse().ok()?;
+    (0.0..=100.0).contains(&value).then_some(value / 100.0)
+}
+
+/// Progression 7-Zip (-bsp1) : " 42% 12 + dossier/fichier.txt", pourcentage en tête de
+/// ligne ; un nom de fichier comme "50% off.txt" vient après et n'est pas lu
+fn parse_leading_percent(line: &str) -> Option<f32> {
+    percent_token(line.split_whitespace().next()?)
+}
+
+/// Progression FreeArc ou Sharky : pourcentage en fin de ligne, après le nom du fichier
+fn parse_trailing_percent(line: &str) -> Option<f32> {
+    percent_token(line.split_whitespace().next_back()?)
+}
+
+/// Taille totale annoncée en début d'opération, ex : "Compressing 12 files, 1,234,567 bytes"
+/// (FreeArc) ou "Add new data to archive: 3 files, 123456 bytes (121 KiB)" (7-Zip), au
+/// singulier pour un seul fichier ("1 file, ...")
+fn parse_total_bytes(line: &str) -> Option<u64> {
+    if !(line.contains(" files, ") || line.contains(" file, ")) || line.contains("=>") {
+        return None;
+    }
+    let head = &line[..line.find(" bytes")?];
+    let number = head.rsplit(' ').next()?;
+    number.replace([',', '.', '\''], "").parse().ok()
+}
+
+pub struct FreeArcBackend;
+
+impl ArchiveBackend for FreeArcBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::FreeArc
+    }
+
+    fn name(&self) -> &'static str {
+        "FreeArc"
+    }
+
+    fn executable(&self) -> PathBuf {
+        PathBuf::from(if cfg!(windows) { ".\\FreeArc\\arc.exe" } else { "./FreeArc/bin/arc" })
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities { list: true, test: true, multiple_sources: true, sfx: true, encryption: true, volumes: false, update: true, rename: false, recovery: true }
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["arc", "bin", "doi", "bbv", "pak", "sfx", "pixel"]
+    }
+
+    fn compress_command(&self, archive: &Path, sources: &[PathBuf], flags: &[&str]) -> Command {
+        let mut cmd = Command::new(self.executable());
+        cmd.arg("a");
+        cmd.arg(archive);
+        for item in sources {
+            cmd.arg(item);
+        }
+        for flag in flags {
+            cmd.arg(flag);
+        }
+        if archive.extension().and_then(|ext| ext.to_str()) == Some("sfx") {
+            cmd.arg("-sfx");
+        }
+        cmd
+    }
+
+    fn extract_command(&self, archive: &Path, dest: &Path) -> Command {
+        let mut cmd = Command::new(self.executable());
+        cmd.arg("x");
+        cmd.arg(archive);
+        cmd.arg(format!("-dp{}", dest.display()));
+        cmd.args(["-o+", "-y"]);
+        cmd
+    }
+
+    fn extract_files_command(&self, archive: &Path, dest: &Path, list_file: &Path) -> Option<Command> {
+        let mut cmd = self.extract_command(archive, dest);
+        cmd.arg(format!("@{}", list_file.display()));
+        Some(cmd)
+    }
+
+    fn list_command(&self, archive: &Path) -> Option<Command> {
+        // "v" plutôt que "l" : liste détaillée avec taille compressée et CRC
+        let mut cmd = Command::new(self.executable());
+        cmd.arg("v").arg(archive);
+        Some(cmd)
+    }
+
+    fn test_command(&self, archive: &Path) -> Option<Command> {
+        let mut cmd = Command::new(self.executable());
+        cmd.arg("t").arg(archive);
+        Some(cmd)
+    }
+
+    fn list_file_source(&self, list_file: &Path) -> Option<PathBuf> {
+        Some(PathBuf::from(format!("@{}", list_file.display())))
+    }
+
+    // -o+ -y déjà passés par extract_command ; le reste passe par une extraction sélective
+    fn overwrite_args(&self, policy: OverwritePolicy) -> Option<Vec<String>> {
+        (policy == OverwritePolicy::Overwrite).then(Vec::new)
+    }
+
+    fn update_command(&self, archive: &Path, op: &UpdateOp, sources: &[PathBuf], list_file: Option<&Path>, flags: &[&str]) -> Option<Command> {
+        let mut cmd = Command::new(self.executable());
+        match op {
+            UpdateOp::Add => return Some(self.compress_command(archive, sources, flags)),
+            // f : remplace uniquement les fichiers déjà présents et plus récents sur le disque
+            UpdateOp::Freshen => {
+                cmd.arg("f").arg(archive).arg(format!("@{}", list_file?.display())).args(flags);
+            }
+            UpdateOp::Delete => {
+                cmd.arg("d").arg(archive).arg(format!("@{}", list_file?.display()));
+            }
+            // FreeArc n'a pas de commande de renommage
+            UpdateOp::Rename { .. } => return None,
+        }
+        Some(cmd)
+    }
+
+    // Commentaire d'archive (-z) : configuration lue par les modules installateurs
+    fn sfx_args(&self, module: &Path, config: &Path) -> Option<Vec<String>> {
+        Some(vec![format!("-sfx={}", module.display()), format!("-z{}", config.display())])
+    }
+
+    // Les modules FreeArc acceptent les commandes d'unarc : "t" teste l'archive intégrée
+    fn sfx_test_command(&self, sfx: &Path) -> Option<Command> {
+        let mut cmd = Command::new(sfx);
+        cmd.arg("t");
+        Some(cmd)
+    }
+
+    fn encryption_args(&self, _archive: &Path, settings: &EncryptionSettings, password: &str) -> Result<Vec<String>, String> {
+        let method = settings.method();
+        if method.is_empty() {
+            return Err("Aucune méthode de chiffrement indiquée".to_string());
+        }
+        // -hp chiffre les en-têtes en plus des données
+        let password_arg = if settings.encrypt_headers { format!("-hp{}", password) } else { format!("--password={}", password) };
+        Ok(vec![format!("--encryption={}", method), password_arg])
+    }
+
+    fn password_args(&self, password: &str) -> Vec<String> {
+        // -hp sert aussi de mot de passe pour les données
+        vec![format!("-hp{}", password)]
+    }
+
+    fn thread_flags(&self, flags: &[&str], threads: usize) -> Vec<String> {
+        method::set_threads(flags, threads)
+    }
+
+    fn estimate_memory(&self, flags: &[&str], cpus: usize) -> Option<(u64, u64)> {
+        method::estimate_flags(flags, cpus)
+    }
+
+    fn volume_args(&self, _volume_size: u64) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn recovery_args(&self, size: &RecoverySize) -> Vec<String> {
+        vec![size.freearc_arg()]
+    }
+
+    fn repair_command(&self, archive: &Path) -> Option<Command> {
+        let mut cmd = Command::new(self.executable());
+        cmd.arg("r").arg(archive);
+        Some(cmd)
+    }
+
+    fn groups_args(&self, groups_file: &Path) -> Option<Vec<String>> {
+        Some(vec![format!("--groups={}", groups_file.display())])
+    }
+
+    // Le résumé de `arc v` mentionne l'enregistrement de récupération quand il existe ;
+    // les lignes des entrées sont écartées, un nom de fichier pouvant contenir le mot
+    fn parse_recovery_record(&self, output: &str) -> Option<bool> {
+        Some(output.lines().any(|line| {
+            listing::parse_freearc_listing(line).is_empty() && line.to_lowercase().contains("recovery")
+        }))
+    }
+
+    fn parse_progress(&self, line: &str) -> ProgressInfo {
+        // Le résumé final ("Ratio 37.0%") n'est pas une progression
+        let fraction = if line.contains("Ratio") { None } else { parse_trailing_percent(line) };
+        ProgressInfo { fraction, total_bytes: parse_total_bytes(line) }
+    }
+
+    fn parse_listing(&self, output: &str) -> Vec<ArchiveEntry> {
+        listing::parse_freearc_listing(output)
+    }
+
+    fn parse_test_line(&self, line: &str) -> Option<TestLine> {
+        // FreeArc ne détaille pas les fichiers testés : seules les erreurs sont signalées,
+        // parfois avec le fichier concerné ("ERROR: ... in dossier\fichier.txt")
+        let message = line.trim().strip_prefix("ERROR:")?.trim();
+        let path = message
+            .rsplit_once(" in ")
+            .map(|(_, path)| path.trim().trim_end_matches(')').replace('\\', "/"))
+            .unwrap_or_default();
+        Some(TestLine::Failed(path, message.to_string()))
+    }
+}
+
+pub struct SevenZipBackend;
+
+impl ArchiveBackend for SevenZipBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::SevenZip
+    }
+
+    fn name(&self) -> &'static str {
+        "7-Zip"
+    }
+
+    fn executable(&self) -> PathBuf {
+        PathBuf::from(if cfg!(windows) { ".\\FreeArc\\7z.exe" } else { "7z" })
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities { list: true, test: true, multiple_sources: true, sfx: true, encryption: true, volumes: true, update: true, rename: true, recovery: false }
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["7z", "zip"]
+    }
+
+    fn compress_command(&self, archive: &Path, sources: &[PathBuf], flags: &[&str]) -> Command {
+        let mut cmd = Command::new(self.executable());
+        cmd.arg("a");
+        // -bsp1 : progression sur stdout
+        cmd.arg("-bsp1");
+        for flag in flags {
+            cmd.arg(flag);
+        }
+        cmd.arg(archive);
+        for item in sources {
+            cmd.arg(item);
+        }
+        cmd
+    }
+
+    fn extract_command(&self, archive: &Path, dest: &Path) -> Command {
+        let mut cmd = Command::new(self.executable());
+        cmd.args(["x", "-bsp1"]);
+        cmd.arg(archive);
+        cmd.arg(format!("-o{}", dest.display()));
+        cmd.arg("-y");
+        cmd
+    }
+
+    fn extract_files_command(&self, archive: &Path, dest: &Path, list_file: &Path) -> Option<Command> {
+        let mut cmd = self.extract_command(archive, dest);
+        cmd.arg(format!("@{}", list_file.display()));
+        Some(cmd)
+    }
+
+    fn list_command(&self, archive: &Path) -> Option<Command> {
+        let mut cmd = Command::new(self.executable());
+        cmd.args(["l", "-slt"]).arg(archive);
+        Some(cmd)
+    }
+
+    fn test_command(&self, archive: &Path) -> Option<Command> {
+        let mut cmd = Command::new(self.executable());
+        // -bb1 : une ligne "T fichier" par fichier testé
+        cmd.args(["t", "-bsp1", "-bb1"]).arg(archive);
+        Some(cmd)
+    }
+
+    fn list_file_source(&self, list_file: &Path) -> Option<PathBuf> {
+        Some(PathBuf::from(format!("@{}", list_file.display())))
+    }
+
+    fn overwrite_args(&self, policy: OverwritePolicy) -> Option<Vec<String>> {
+        let arg = match policy {
+            OverwritePolicy::Overwrite => "-aoa",
+            OverwritePolicy::Skip => "-aos",
+            OverwritePolicy::Rename => "-aou",
+            OverwritePolicy::Ask | OverwritePolicy::Newer => return None,
+        };
+        Some(vec![arg.to_string()])
+    }
+
+    fn update_command(&self, archive: &Path, op: &UpdateOp, sources: &[PathBuf], list_file: Option<&Path>, flags: &[&str]) -> Option<Command> {
+        let mut cmd = Command::new(self.executable());
+        match op {
+            UpdateOp::Add => return Some(self.compress_command(archive, sources, flags)),
+            // Équivalent de "freshen" : fichiers absents de l'archive ignorés (r0), plus récents remplacés (y2)
+            UpdateOp::Freshen => {
+                cmd.args(["u", "-bsp1", "-up1q1r0x1y2z1w2"]).args(flags).arg(archive).arg(format!("@{}", list_file?.display()));
+            }
+            UpdateOp::Delete => {
+                cmd.args(["d", "-bsp1"]).arg(archive).arg(format!("@{}", list_file?.display()));
+            }
+            UpdateOp::Rename { from, to } => {
+                cmd.args(["rn", "-bsp1"]).arg(archive).arg(from).arg(to);
+            }
+        }
+        Some(cmd)
+    }
+
+    fn sfx_args(&self, _module: &Path, _config: &Path) -> Option<Vec<String>> {
+        None
+    }
+
+    // Les modules 7-Zip n'ont pas de commande de test : 7-Zip teste l'exécutable produit
+    fn sfx_test_command(&self, sfx: &Path) -> Option<Command> {
+        self.test_command(sfx)
+    }
+
+    fn sfx_modules(&self) -> Vec<PathBuf> {
+        let exe = self.executable();
+        let mut dirs = vec![exe.parent().map(Path::to_path_buf).unwrap_or_default()];
+        // p7zip installé par le système
+        if cfg!(unix) {
+            dirs.extend(["/usr/lib/p7zip", "/usr/lib/7zip", "/usr/libexec/p7zip"].map(PathBuf::from));
+        }
+        sfx::find_modules(&dirs)
+    }
+
+    fn encryption_args(&self, archive: &Path, settings: &EncryptionSettings, password: &str) -> Result<Vec<String>, String> {
+        if settings.algorithm != EncryptionAlgorithm::Aes256 {
+            return Err("7-Zip ne propose que le chiffrement AES-256".to_string());
+        }
+        let mut args = vec![format!("-p{}", password)];
+        let is_zip = archive.extension().and_then(|ext| ext.to_str()) == Some("zip");
+        if is_zip {
+            if settings.encrypt_headers {
+                return Err("Le chiffrement des en-têtes n'est possible qu'avec le format 7z".to_string());
+            }
+            // ZipCrypto par défaut, trop faible
+            args.push("-mem=AES256".to_string());
+        } else if settings.encrypt_headers {
+            args.push("-mhe=on".to_string());
+        }
+        Ok(args)
+    }
+
+    fn password_args(&self, password: &str) -> Vec<String> {
+        vec![format!("-p{}", password)]
+    }
+
+    fn thread_flags(&self, flags: &[&str], threads: usize) -> Vec<String> {
+        let mut flags: Vec<String> = flags.iter().filter(|f| !f.starts_with("-mmt")).map(|f| f.to_string()).collect();
+        flags.push(format!("-mmt{}", threads));
+        flags
+    }
+
+    fn estimate_memory(&self, flags: &[&str], cpus: usize) -> Option<(u64, u64)> {
+        method::estimate_7z_flags(flags, cpus)
+    }
+
+    // -v produit archive.7z.001, .002... ; 7-Zip relit ensuite les volumes depuis le premier
+    fn volume_args(&self, volume_size: u64) -> Vec<String> {
+        vec![format!("-v{}b", volume_size)]
+    }
+
+    // Le format 7z n'a pas d'enregistrement de récupération
+    fn recovery_args(&self, _size: &RecoverySize) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn repair_command(&self, _archive: &Path) -> Option<Command> {
+        None
+    }
+
+    fn groups_args(&self, _groups_file: &Path) -> Option<Vec<String>> {
+        None
+    }
+
+    // 7-Zip l'indique seulement pour les archives qui en ont un (RAR) : "Recovery = +"
+    fn parse_recovery_record(&self, output: &str) -> Option<bool> {
+        output.lines().find_map(|line| line.trim().strip_prefix("Recovery = ").map(|value| value.trim() == "+"))
+    }
+
+    fn parse_progress(&self, line: &str) -> ProgressInfo {
+        ProgressInfo { fraction: parse_leading_percent(line), total_bytes: parse_total_bytes(line) }
+    }
+
+    fn parse_listing(&self, output: &str) -> Vec<ArchiveEntry> {
+        listing::parse_7z_listing(output)
+    }
+
+    fn parse_test_line(&self, line: &str) -> Option<TestLine> {
+        // "T dossier/fichier.txt" (testé) ou "ERROR: CRC Failed : dossier/fichier.txt"
+        if let Some(message) = line.trim().strip_prefix("ERROR:") {
+            return Some(match message.rsplit_once(" : ") {
+                Some((error, path)) => TestLine::Failed(path.trim().replace('\\', "/"), error.trim().to_string()),
+                None => TestLine::Failed(String::new(), message.trim().to_string()),
+            });
+        }
+        line.strip_prefix("T ").map(|path| TestLine::Passed(path.trim().replace('\\', "/")))
+    }
+}
+
+pub struct SharkyBackend;
+
+impl ArchiveBackend for SharkyBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Sharky
+    }
+
+    fn name(&self) -> &'static str {
+        "Sharky"
+    }
+
+    fn executable(&self) -> PathBuf {
+        PathBuf::from(if cfg!(windows) { ".\\sharky\\sharky.exe" } else { "./sharky/sharky" })
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities { list: false, test: false, multiple_sources: false, sfx: false, encryption: false, volumes: false, update: false, rename: false, recovery: false }
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["stel"]
+    }
+
+    fn compress_command(&self, archive: &Path, sources: &[PathBuf], flags: &[&str]) -> Command {
+        let mut cmd = Command::new(self.executable());
+        cmd.arg("-c");
+        for flag in flags {
+            cmd.arg(flag);
+        }
+        if let Some(source) = sources.first() {
+            cmd.arg("-i").arg(source);
+        }
+        cmd.arg("-o").arg(archive);
+        cmd
+    }
+
+    fn extract_command(&self, archive: &Path, dest: &Path) -> Command {
+        let mut cmd = Command::new(self.executable());
+        cmd.arg("-d");
+        cmd.arg("-i").arg(archive);
+        cmd.arg("-o").arg(dest);
+        cmd
+    }
+
+    fn extract_files_command(&self, _archive: &Path, _dest: &Path, _list_file: &Path) -> Option<Command> {
+        None
+    }
+
+    fn list_command(&self, _archive: &Path) -> Option<Command> {
+        None
+    }
+
+    fn test_command(&self, _archive: &Path) -> Option<Command> {
+        None
+    }
+
+    fn list_file_source(&self, _list_file: &Path) -> Option<PathBuf> {
+        None
+    }
+
+    fn overwrite_args(&self, policy: OverwritePolicy) -> Option<Vec<String>> {
+        (policy == OverwritePolicy::Overwrite).then(Vec::new)
+    }
+
+    fn update_command(&self, _archive: &Path, _op: &UpdateOp, _sources: &[PathBuf], _list_file: Option<&Path>, _flags: &[&str]) -> Option<Command> {
+        None
+    }
+
+    fn sfx_args(&self, _module: &Path, _config: &Path) -> Option<Vec<String>> {
+        None
+    }
+
+    fn sfx_test_command(&self, _sfx: &Path) -> Option<Command> {
+        None
+    }
+
+    fn encryption_args(&self, _archive: &Path, _settings: &EncryptionSettings, _password: &str) -> Result<Vec<String>, String> {
+        Err("Sharky ne prend pas en charge le chiffrement".to_string())
+    }
+
+    fn password_args(&self, _password: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    // Sharky ne propose pas de réglage du nombre de threads
+    fn thread_flags(&self, flags: &[&str], _threads: usize) -> Vec<String> {
+        flags.iter().map(|f| f.to_string()).collect()
+    }
+
+    // Sharky n'indique pas la mémoire demandée par ses niveaux
+    fn estimate_memory(&self, _flags: &[&str], _cpus: usize) -> Option<(u64, u64)> {
+        None
+    }
+
+    fn volume_args(&self, _volume_size: u64) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn recovery_args(&self, _size: &RecoverySize) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn repair_command(&self, _archive: &Path) -> Option<Command> {
+        None
+    }
+
+    fn groups_args(&self, _groups_file: &Path) -> Option<Vec<String>> {
+        None
+    }
+
+    fn parse_recovery_record(&self, _output: &str) -> Option<bool> {
+        None
+    }
+
+    fn parse_progress(&self, line: &str) -> ProgressInfo {
+        ProgressInfo { fraction: parse_trailing_percent(line), total_bytes: None }
+    }
+
+    fn parse_listing(&self, _output: &str) -> Vec<ArchiveEntry> {
+        Vec::new()
+    }
+
+    fn parse_test_line(&self, _line: &str) -> Option<TestLine> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(value: Option<f32>, expected: f32) -> bool {
+        value.is_some_and(|v| (v - expected).abs() < 1e-6)
+    }
+
+    #[test]
+    fn total_bytes_from_tool_headers() {
+        assert_eq!(parse_total_bytes("Compressing 12 files, 1,234,567 bytes. Processed   0%"), Some(1_234_567));
+        assert_eq!(parse_total_bytes("Compressing 1 file, 2,048 bytes. Processed   0%"), Some(2048));
+        assert_eq!(parse_total_bytes("Add new data to archive: 3 files, 123456 bytes (121 KiB)"), Some(123_456));
+        assert_eq!(parse_total_bytes("Add new data to archive: 1 file, 5000 bytes (5 KiB)"), Some(5000));
+        // Bilan final de FreeArc : taille compressée, pas un total à traiter
+        assert_eq!(parse_total_bytes("Compressed 3 files, 1,234,567 => 456,789 bytes. Ratio 37.0%"), None);
+        assert_eq!(parse_total_bytes("Extracting archive: photos.7z"), None);
+    }
+
+    #[test]
+    fn leading_percent_from_7z_progress() {
+        assert!(close(parse_leading_percent(" 45% 3 + dossier/50% off.txt"), 0.45));
+        assert!(close(parse_leading_percent("  0%"), 0.0));
+        assert!(close(parse_leading_percent("100% 12 - photos/été.jpg"), 1.0));
+        assert_eq!(parse_leading_percent("- 50% off.txt"), None);
+        assert_eq!(parse_leading_percent("T 50% off.txt"), None);
+    }
+
+    #[test]
+    fn trailing_percent_from_freearc_progress() {
+        assert!(close(parse_trailing_percent("Compressing 12 files, 1,234,567 bytes. Processed  45.3%"), 0.453));
+        assert_eq!(parse_trailing_percent("Extracting 50% off.txt"), None);
+        assert_eq!(parse_trailing_percent("Processed 250%"), None);
+        // Le taux de compression final n'est pas une progression
+        assert_eq!(FreeArcBackend.parse_progress("Compressed 3 files, 1,234,567 => 456,789 bytes. Ratio 37.0%").fraction, None);
+    }
+
+    #[test]
+    fn sevenzip_memory_from_dictionary_and_threads() {
+        const MB: u64 = 1024 * 1024;
+        // -mx9 : 64 Mo de dictionnaire, un encodeur pour deux threads
+        assert_eq!(SevenZipBackend.estimate_memory(&["-mx9", "-mmt4"], 8), Some((2 * (736 + 6) * MB, 66 * MB)));
+        assert_eq!(SevenZipBackend.estimate_memory(&["-mx=9", "-md26", "-mmt=off"], 8), Some(((736 + 6) * MB, 66 * MB)));
+        assert_eq!(SevenZipBackend.estimate_memory(&["-m0=LZMA2:d=256m", "-mmt2"], 8), Some(((2944 + 6) * MB, 258 * MB)));
+        assert_eq!(SevenZipBackend.estimate_memory(&["-m0=PPMd", "-mx9"], 8), None);
+        assert_eq!(SharkyBackend.estimate_memory(&["-mx9"], 8), None);
+    }
+}
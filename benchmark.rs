@@ -0,0 +1,493 @@
+// Banc d'essai des presets : compresse la sélection (ou un échantillon) avec chaque preset
+// choisi et compare taux de compression, temps et mémoire consommée.
+
+use eframe::egui;
+use egui::{Color32, RichText};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{format_size, process_tree, CompressionPreset};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkResult {
+    pub preset: String,
+    pub backend: String,
+    pub original_size: u64,
+    pub compressed_size: u64,
+    pub compress_secs: f64,
+    pub peak_memory: u64,
+    pub decompress_secs: Option<f64>,
+    pub error: Option<String>,
+}
+
+impl BenchmarkResult {
+    pub fn ratio(&self) -> f64 {
+        if self.original_size == 0 {
+            return 0.0;
+        }
+        self.compressed_size as f64 / self.original_size as f64 * 100.0
+    }
+
+    /// Vitesse de compression en Mo/s
+    pub fn speed(&self) -> f64 {
+        if self.compress_secs <= 0.0 {
+            return 0.0;
+        }
+        self.original_size as f64 / (1024.0 * 1024.0) / self.compress_secs
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchmarkConfig {
+    pub presets: Vec<CompressionPreset>,
+    pub sources: Vec<PathBuf>,
+    pub sample_limit: Option<u64>, // Octets copiés dans l'échantillon, None = toute la sélection
+    pub with_decompression: bool,
+}
+
+pub enum BenchmarkEvent {
+    Started(usize, String), // Index et nom du preset en cours
+    Finished(BenchmarkResult),
+    Done(Result<(), String>),
+}
+
+fn total_size(path: &Path) -> u64 {
+    if path.is_file() {
+        return fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    }
+    fs::read_dir(path)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| total_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Copie des fichiers de la sélection jusqu'à `limit` octets, arborescence conservée
+fn copy_sample(source: &Path, dest: &Path, remaining: &mut u64) -> std::io::Result<()> {
+    if *remaining == 0 {
+        return Ok(());
+    }
+    if source.is_file() {
+        let size = fs::metadata(source)?.len();
+        if size <= *remaining {
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(source, dest)?;
+            *remaining -= size;
+        }
+        return Ok(());
+    }
+    let mut entries: Vec<_> = fs::read_dir(source)?.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        copy_sample(&entry.path(), &dest.join(entry.file_name()), remaining)?;
+    }
+    Ok(())
+}
+
+fn build_sample(sources: &[PathBuf], limit: u64, dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let root = dir.join("echantillon");
+    let mut remaining = limit;
+    for source in sources {
+        let name = source.file_name().map(PathBuf::from).unwrap_or_else(|| PathBuf::from("source"));
+        copy_sample(source, &root.join(name), &mut remaining)
+            .map_err(|e| format!("Impossible de créer l'échantillon : {}", e))?;
+    }
+    if remaining == limit {
+        return Err("Échantillon vide : aucun fichier ne tient dans la limite".to_string());
+    }
+    Ok(vec![root])
+}
+
+/// Lance la commande sans afficher sa sortie ; retourne (durée, pic mémoire)
+fn run_measured(mut cmd: Command, cancel: &AtomicBool) -> Result<(Duration, u64), String> {
+    cmd.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    let start = Instant::now();
+    let mut child = cmd.spawn().map_err(|e| format!("Erreur lors de l'exécution de la commande : {}", e))?;
+    let mut peak = 0;
+    loop {
+        if cancel.load(Ordering::SeqCst) {
+            process_tree::kill_tree(child.id());
+            let _ = child.wait();
+            return Err(crate::CANCELLED.to_string());
+        }
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok((start.elapsed(), peak)),
+            Ok(Some(status)) => return Err(format!("Échec de l'outil ({})", status)),
+            Ok(None) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+        peak = peak.max(process_tree::tree_memory(child.id()));
+        thread::sleep(Duration::from_millis(200));
+    }
+}
+
+fn bench_preset(preset: &CompressionPreset, index: usize, sources: &[PathBuf], original_size: u64, dir: &Path, with_decompression: bool, cancel: &AtomicBool) -> BenchmarkResult {
+    let backend = preset.backend().backend();
+    let mut result = BenchmarkResult {
+        preset: preset.label().trim().to_string(),
+        backend: backend.name().to_string(),
+        original_size,
+        compressed_size: 0,
+        compress_secs: 0.0,
+        peak_memory: 0,
+        decompress_secs: None,
+        error: None,
+    };
+    if !backend.is_available() {
+        result.error = Some(format!("{} n'est pas installé correctement", backend.name()));
+        return result;
+    }
+    if sources.len() > 1 && !backend.capabilities().multiple_sources {
+        result.error = Some(format!("{} ne compresse qu'une seule source à la fois", backend.name()));
+        return result;
+    }
+
+    let archive = dir.join(format!("bench_{}.{}", index, preset.extension()));
+    let cmd = backend.compress_command(&archive, sources, &preset.flags());
+    match run_measured(cmd, cancel) {
+        Ok((elapsed, peak)) => {
+            result.compress_secs = elapsed.as_secs_f64();
+            result.peak_memory = peak;
+            result.compressed_size = fs::metadata(&archive).map(|m| m.len()).unwrap_or(0);
+        }
+        Err(e) => {
+            result.error = Some(e);
+            let _ = fs::remove_file(&archive);
+            return result;
+        }
+    }
+
+    if with_decompression {
+        let out = dir.join(format!("bench_{}_extrait", index));
+        match run_measured(backend.extract_command(&archive, &out), cancel) {
+            Ok((elapsed, peak)) => {
+                result.decompress_secs = Some(elapsed.as_secs_f64());
+                result.peak_memory = result.peak_memory.max(peak);
+            }
+            Err(e) => result.error = Some(format!("Décompression : {}", e)),
+        }
+        let _ = fs::remove_dir_all(&out);
+    }
+    let _ = fs::remove_file(&archive);
+    result
+}
+
+/// Exécute le banc d'essai preset par preset ; les fichiers temporaires sont supprimés à la fin
+pub fn run_benchmark(config: &BenchmarkConfig, cancel: &AtomicBool, mut on_event: impl FnMut(BenchmarkEvent)) {
+    let dir = std::env::temp_dir().join(format!("stelarc_bench_{}", std::process::id()));
+    if let Err(e) = fs::create_dir_all(&dir) {
+        on_event(BenchmarkEvent::Done(Err(format!("Impossible de créer le dossier temporaire : {}", e))));
+        return;
+    }
+
+    let sources = match config.sample_limit {
+        Some(limit) => match build_sample(&config.sources, limit, &dir) {
+            Ok(sample) => sample,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&dir);
+                on_event(BenchmarkEvent::Done(Err(e)));
+                return;
+            }
+        },
+        None => config.sources.clone(),
+    };
+    let original_size = sources.iter().map(|p| total_size(p)).sum();
+
+    let mut outcome = Ok(());
+    for (index, preset) in config.presets.iter().enumerate() {
+        if cancel.load(Ordering::SeqCst) {
+            outcome = Err(crate::CANCELLED.to_string());
+            break;
+        }
+        on_event(BenchmarkEvent::Started(index, preset.label().trim().to_string()));
+        let result = bench_preset(preset, index, &sources, original_size, &dir, config.with_decompression, cancel);
+        if result.error.as_deref() == Some(crate::CANCELLED) {
+            outcome = Err(crate::CANCELLED.to_string());
+            break;
+        }
+        on_event(BenchmarkEvent::Finished(result));
+    }
+    let _ = fs::remove_dir_all(&dir);
+    on_event(BenchmarkEvent::Done(outcome));
+}
+
+pub fn to_csv(results: &[BenchmarkResult]) -> String {
+    let escape = |text: &str| format!("\"{}\"", text.replace('"', "\"\""));
+    let mut csv = String::from("preset;outil;taille_originale;taille_compressee;ratio_pct;compression_s;vitesse_mo_s;memoire_pic;decompression_s;erreur\n");
+    for r in results {
+        csv.push_str(&format!(
+            "{};{};{};{};{:.2};{:.3};{:.2};{};{};{}\n",
+            escape(&r.preset),
+            escape(&r.backend),
+            r.original_size,
+            r.compressed_size,
+            r.ratio(),
+            r.compress_secs,
+            r.speed(),
+            r.peak_memory,
+            r.decompress_secs.map(|s| format!("{:.3}", s)).unwrap_or_default(),
+            escape(r.error.as_deref().unwrap_or("")),
+        ));
+    }
+    csv
+}
+
+pub fn to_json(results: &[BenchmarkResult]) -> Result<String, String> {
+    serde_json::to_string_pretty(results).map_err(|e| e.to_string())
+}
+
+/// Écrit le fichier d'export ; le format dépend de l'extension (.json, sinon CSV)
+pub fn export(results: &[BenchmarkResult], path: &Path) -> Result<(), String> {
+    let content = if path.extension().and_then(|e| e.to_str()) == Some("json") {
+        to_json(results)?
+    } else {
+        to_csv(results)
+    };
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortColumn {
+    Preset,
+    Ratio,
+    Speed,
+    Memory,
+    Decompression,
+}
+
+/// Fenêtre du banc d'essai
+pub struct BenchmarkWindow {
+    selected: Vec<bool>, // Presets cochés, même ordre que la liste passée à `show`
+    use_sample: bool,
+    sample_mb: u64,
+    with_decompression: bool,
+    results: Vec<BenchmarkResult>,
+    rx: Option<mpsc::Receiver<BenchmarkEvent>>,
+    cancel: Arc<AtomicBool>,
+    current: Option<(usize, String)>,
+    total: usize,
+    status: Option<String>,
+    sort: SortColumn,
+    ascending: bool,
+}
+
+impl Default for BenchmarkWindow {
+    fn default() -> Self {
+        Self {
+            selected: Vec::new(),
+            use_sample: true,
+            sample_mb: 100,
+            with_decompression: false,
+            results: Vec::new(),
+            rx: None,
+            cancel: Arc::new(AtomicBool::new(false)),
+            current: None,
+            total: 0,
+            status: None,
+            sort: SortColumn::Ratio,
+            ascending: true,
+        }
+    }
+}
+
+impl BenchmarkWindow {
+    fn start(&mut self, presets: &[CompressionPreset], sources: &[PathBuf], ctx: &egui::Context) {
+        let config = BenchmarkConfig {
+            presets: presets.iter().zip(&self.selected).filter(|(_, s)| **s).map(|(p, _)| p.clone()).collect(),
+            sources: sources.to_vec(),
+            sample_limit: self.use_sample.then_some(self.sample_mb * 1024 * 1024),
+            with_decompression: self.with_decompression,
+        };
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.cancel = cancel.clone();
+        self.total = config.presets.len();
+        self.results.clear();
+        self.status = None;
+        self.rx = Some(rx);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            run_benchmark(&config, &cancel, |event| {
+                tx.send(event).ok();
+                ctx.request_repaint();
+            });
+        });
+    }
+
+    fn poll(&mut self) {
+        let Some(rx) = &self.rx else { return };
+        let mut done = false;
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                BenchmarkEvent::Started(index, name) => self.current = Some((index, name)),
+                BenchmarkEvent::Finished(result) => self.results.push(result),
+                BenchmarkEvent::Done(outcome) => {
+                    self.status = Some(match outcome {
+                        Ok(()) => format!("Banc d'essai terminé : {} preset(s)", self.results.len()),
+                        Err(e) if e == crate::CANCELLED => "Banc d'essai annulé".to_string(),
+                        Err(e) => format!("Erreur : {}", e),
+                    });
+                    done = true;
+                }
+            }
+        }
+        if done {
+            self.rx = None;
+            self.current = None;
+        }
+    }
+
+    fn sorted_results(&self) -> Vec<&BenchmarkResult> {
+        let mut rows: Vec<&BenchmarkResult> = self.results.iter().collect();
+        rows.sort_by(|a, b| {
+            // Les presets en erreur restent en bas
+            let ordering = match self.sort {
+                SortColumn::Preset => a.preset.cmp(&b.preset),
+                SortColumn::Ratio => a.ratio().total_cmp(&b.ratio()),
+                SortColumn::Speed => a.speed().total_cmp(&b.speed()),
+                SortColumn::Memory => a.peak_memory.cmp(&b.peak_memory),
+                SortColumn::Decompression => a.decompress_secs.unwrap_or(f64::MAX).total_cmp(&b.decompress_secs.unwrap_or(f64::MAX)),
+            };
+            a.error.is_some().cmp(&b.error.is_some())
+                .then(if self.ascending { ordering } else { ordering.reverse() })
+        });
+        rows
+    }
+
+    fn header(&mut self, ui: &mut egui::Ui, label: &str, column: SortColumn) {
+        let text = if self.sort == column {
+            format!("{} {}", label, if self.ascending { "⏶" } else { "⏷" })
+        } else {
+            label.to_string()
+        };
+        if ui.button(RichText::new(text).strong()).clicked() {
+            if self.sort == column {
+                self.ascending = !self.ascending;
+            } else {
+                self.sort = column;
+                self.ascending = true;
+            }
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool, presets: &[CompressionPreset], sources: &[PathBuf]) {
+        self.poll();
+        self.selected.resize(presets.len(), false);
+        let running = self.rx.is_some();
+
+        egui::Window::new("⏱ Banc d'essai des presets")
+            .open(open)
+            .resizable(true)
+            .default_width(760.0)
+            .show(ctx, |ui| {
+                ui.add_enabled_ui(!running, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Sélection : {} élément(s)", sources.len()));
+                        ui.checkbox(&mut self.use_sample, "Échantillon limité à");
+                        ui.add_enabled(self.use_sample, egui::DragValue::new(&mut self.sample_mb).range(1..=100_000).suffix(" Mo"));
+                        ui.checkbox(&mut self.with_decompression, "Mesurer la décompression");
+                    });
+                    egui::CollapsingHeader::new(format!("Presets ({} coché(s))", self.selected.iter().filter(|s| **s).count()))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("Tout cocher").clicked() {
+                                    self.selected.iter_mut().for_each(|s| *s = true);
+                                }
+                                if ui.small_button("Tout décocher").clicked() {
+                                    self.selected.iter_mut().for_each(|s| *s = false);
+                                }
+                            });
+                            egui::ScrollArea::vertical().id_salt("bench_presets").max_height(160.0).show(ui, |ui| {
+                                egui::Grid::new("bench_presets_grid").num_columns(3).show(ui, |ui| {
+                                    for (i, preset) in presets.iter().enumerate() {
+                                        ui.checkbox(&mut self.selected[i], preset.label().trim());
+                                        if i % 3 == 2 {
+                                            ui.end_row();
+                                        }
+                                    }
+                                });
+                            });
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    if running {
+                        let (index, name) = self.current.clone().unwrap_or((0, String::new()));
+                        ui.add(egui::ProgressBar::new(index as f32 / self.total.max(1) as f32)
+                            .desired_width(240.0)
+                            .text(format!("{}/{} : {}", index + 1, self.total, name)));
+                        if ui.button("⏹ Annuler").clicked() {
+                            self.cancel.store(true, Ordering::SeqCst);
+                        }
+                    } else {
+                        let ready = !sources.is_empty() && self.selected.iter().any(|s| *s);
+                        if ui.add_enabled(ready, egui::Button::new("▶ Lancer"))
+                            .on_disabled_hover_text("Sélectionnez des fichiers et au moins un preset")
+                            .clicked() {
+                            self.start(presets, sources, ctx);
+                        }
+                    }
+                    if let Some(status) = &self.status {
+                        ui.label(status);
+                    }
+                });
+                ui.separator();
+
+                if self.results.is_empty() {
+                    return;
+                }
+                egui::ScrollArea::vertical().id_salt("bench_results").max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("bench_results_grid").striped(true).show(ui, |ui| {
+                        self.header(ui, "Preset", SortColumn::Preset);
+                        ui.label(RichText::new("Taille").strong());
+                        self.header(ui, "Ratio", SortColumn::Ratio);
+                        ui.label(RichText::new("Temps").strong());
+                        self.header(ui, "Vitesse", SortColumn::Speed);
+                        self.header(ui, "Mémoire", SortColumn::Memory);
+                        self.header(ui, "Décompression", SortColumn::Decompression);
+                        ui.end_row();
+
+                        for r in self.sorted_results() {
+                            ui.label(&r.preset).on_hover_text(&r.backend);
+                            if let Some(error) = &r.error {
+                                ui.colored_label(Color32::from_rgb(234, 67, 53), error);
+                                ui.end_row();
+                                continue;
+                            }
+                            ui.label(format_size(r.compressed_size));
+                            ui.label(format!("{:.1} %", r.ratio()));
+                            ui.label(format!("{:.1} s", r.compress_secs));
+                            ui.label(format!("{:.1} Mo/s", r.speed()));
+                            ui.label(format_size(r.peak_memory));
+                            ui.label(r.decompress_secs.map(|s| format!("{:.1} s", s)).unwrap_or_else(|| "-".to_string()));
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                ui.horizontal(|ui| {
+                    for (label, ext) in [("💾 Exporter CSV", "csv"), ("💾 Exporter JSON", "json")] {
+                        if ui.button(label).clicked() {
+                            if let Some(path) = rfd::FileDialog::new()
+                                .set_file_name(format!("benchmark.{}", ext))
+                                .add_filter(ext, &[ext])
+                                .save_file() {
+                                self.status = Some(match export(&self.results, &path) {
+                                    Ok(()) => format!("Résultats exportés : {}", path.display()),
+                                    Err(e) => format!("Erreur d'export : {}", e),
+                                });
+                            }
+                        }
+                    }
+                });
+            });
+    }
+}
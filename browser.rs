@@ -0,0 +1,486 @@
+// Fenêtre de consultation du contenu d'une archive avant extraction, et de modification
+// (ajout, actualisation, suppression, renommage d'entrées) quand l'outil le permet.
+
+use eframe::egui;
+use egui::{Color32, RichText};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::backend::BackendKind;
+use crate::format_size;
+use crate::listing::{self, ArchiveContents, ArchiveEntry, TreeNode};
+use crate::queue::UpdateOp;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortColumn {
+    Name,
+    Size,
+    Packed,
+    Modified,
+}
+
+/// Demande transmise à l'application depuis la fenêtre
+pub enum BrowserAction {
+    /// Extraire uniquement ces entrées (chemins dans l'archive)
+    ExtractSelection(Vec<String>),
+    /// Modifier l'archive : fichiers ou dossier source, entrées visées
+    Update { op: UpdateOp, sources: Vec<PathBuf>, selection: Vec<String> },
+}
+
+/// Ligne affichée : dossier ou fichier à une profondeur donnée
+struct Row {
+    depth: usize,
+    name: String,
+    path: String,
+    is_dir: bool,
+    size: u64,
+    packed: u64,
+    entry: Option<usize>,
+}
+
+const CHECKBOX_WIDTH: f32 = 24.0;
+const COL_NAME: f32 = 300.0;
+const COL_SIZE: f32 = 90.0;
+const COL_DATE: f32 = 140.0;
+const COL_CRC: f32 = 80.0;
+const COL_METHOD: f32 = 110.0;
+
+pub struct ArchiveBrowser {
+    pub archive: PathBuf,
+    pub entries: Vec<ArchiveEntry>,
+    tree: TreeNode,
+    recovery_record: Option<bool>,
+    loading: Option<mpsc::Receiver<Result<ArchiveContents, String>>>,
+    error: Option<String>,
+    search: String,
+    sort: SortColumn,
+    ascending: bool,
+    expanded: HashSet<String>,
+    checked: HashSet<usize>, // Index des fichiers cochés pour l'extraction sélective
+    confirm_delete: bool,
+    rename_to: Option<String>, // Nouveau nom en cours de saisie
+    needs_reload: bool,        // Archive modifiée : contenu à relire
+}
+
+impl ArchiveBrowser {
+    /// Ouvre la fenêtre et lance le listage en arrière-plan
+    pub fn open(archive: PathBuf, ctx: &egui::Context) -> Self {
+        let loading = Some(Self::start_listing(archive.clone(), ctx));
+        Self {
+            archive,
+            entries: Vec::new(),
+            tree: TreeNode::default(),
+            recovery_record: None,
+            loading,
+            error: None,
+            search: String::new(),
+            sort: SortColumn::Name,
+            ascending: true,
+            expanded: HashSet::new(),
+            checked: HashSet::new(),
+            confirm_delete: false,
+            rename_to: None,
+            needs_reload: false,
+        }
+    }
+
+    fn start_listing(archive: PathBuf, ctx: &egui::Context) -> mpsc::Receiver<Result<ArchiveContents, String>> {
+        let (tx, rx) = mpsc::channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            tx.send(listing::read_archive(&archive)).ok();
+            ctx.request_repaint();
+        });
+        rx
+    }
+
+    /// Relit le contenu à la prochaine image (archive modifiée par une tâche)
+    pub fn refresh(&mut self) {
+        self.needs_reload = true;
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading.is_some()
+    }
+
+    fn poll(&mut self) {
+        let Some(rx) = &self.loading else { return };
+        match rx.try_recv() {
+            Ok(Ok(ArchiveContents { entries, recovery_record })) => {
+                // Les index cochés ne correspondent plus après une modification
+                self.checked.clear();
+                self.recovery_record = recovery_record;
+                self.tree = listing::build_tree(&entries);
+                // Un seul dossier racine : le déplier directement
+                if self.tree.children.len() == 1 {
+                    self.expanded.extend(self.tree.children.keys().cloned());
+                }
+                self.entries = entries;
+                self.loading = None;
+            }
+            Ok(Err(e)) => {
+                self.error = Some(e);
+                self.loading = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.error = Some("Le listage de l'archive a échoué".to_string());
+                self.loading = None;
+            }
+        }
+    }
+
+    fn compare_nodes(&self, a: &TreeNode, b: &TreeNode) -> std::cmp::Ordering {
+        // Dossiers toujours en premier
+        let dirs_first = b.is_dir().cmp(&a.is_dir());
+        let order = match self.sort {
+            SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortColumn::Size => a.size.cmp(&b.size),
+            SortColumn::Packed => a.packed.cmp(&b.packed),
+            SortColumn::Modified => self.modified_of(a).cmp(self.modified_of(b)),
+        };
+        dirs_first.then(if self.ascending { order } else { order.reverse() })
+    }
+
+    fn modified_of(&self, node: &TreeNode) -> &str {
+        node.entry.map(|i| self.entries[i].modified.as_str()).unwrap_or("")
+    }
+
+    fn collect_rows(&self, node: &TreeNode, depth: usize, rows: &mut Vec<Row>) {
+        let mut children: Vec<&TreeNode> = node.children.values().collect();
+        children.sort_by(|a, b| self.compare_nodes(a, b));
+        for child in children {
+            rows.push(Row {
+                depth,
+                name: child.name.clone(),
+                path: child.path.clone(),
+                is_dir: child.is_dir(),
+                size: child.size,
+                packed: child.packed,
+                entry: child.entry,
+            });
+            if child.is_dir() && self.expanded.contains(&child.path) {
+                self.collect_rows(child, depth + 1, rows);
+            }
+        }
+    }
+
+    /// Recherche : liste à plat des entrées dont le chemin contient le texte saisi
+    fn search_rows(&self) -> Vec<Row> {
+        let needle = self.search.to_lowercase();
+        let mut rows: Vec<Row> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.path.to_lowercase().contains(&needle))
+            .map(|(i, e)| Row {
+                depth: 0,
+                name: e.path.clone(),
+                path: e.path.clone(),
+                is_dir: e.is_dir,
+                size: e.size,
+                packed: e.packed.unwrap_or(0),
+                entry: Some(i),
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            let order = match self.sort {
+                SortColumn::Name => a.path.to_lowercase().cmp(&b.path.to_lowercase()),
+                SortColumn::Size => a.size.cmp(&b.size),
+                SortColumn::Packed => a.packed.cmp(&b.packed),
+                SortColumn::Modified => {
+                    let date = |r: &Row| r.entry.map(|i| self.entries[i].modified.clone()).unwrap_or_default();
+                    date(a).cmp(&date(b))
+                }
+            };
+            if self.ascending { order } else { order.reverse() }
+        });
+        rows
+    }
+
+    /// Fichiers situés sous un chemin (le chemin lui-même s'il s'agit d'un fichier)
+    fn files_under(&self, path: &str) -> Vec<usize> {
+        let prefix = format!("{}/", path);
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| !e.is_dir && (e.path == path || e.path.starts_with(&prefix)))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Chemins à extraire : fichiers cochés, dans l'ordre de l'archive
+    fn selection(&self) -> Vec<String> {
+        let mut indices: Vec<usize> = self.checked.iter().copied().collect();
+        indices.sort_unstable();
+        indices.into_iter().map(|i| self.entries[i].path.clone()).collect()
+    }
+
+    fn sort_header(&mut self, ui: &mut egui::Ui, label: &str, column: SortColumn, width: f32) {
+        let arrow = match (self.sort == column, self.ascending) {
+            (true, true) => " ⏶",
+            (true, false) => " ⏷",
+            _ => "",
+        };
+        let button = egui::Button::new(RichText::new(format!("{}{}", label, arrow)).strong()).frame(false);
+        if ui.add_sized([width, 18.0], button).clicked() {
+            if self.sort == column {
+                self.ascending = !self.ascending;
+            } else {
+                self.sort = column;
+                self.ascending = true;
+            }
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) -> Option<BrowserAction> {
+        if self.needs_reload && self.loading.is_none() {
+            self.needs_reload = false;
+            self.error = None;
+            self.loading = Some(Self::start_listing(self.archive.clone(), ctx));
+        }
+        self.poll();
+        let mut action = None;
+        let capabilities = BackendKind::for_archive(&self.archive).backend().capabilities();
+
+        let title = format!(
+            "Contenu de {}",
+            self.archive.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+        );
+        egui::Window::new(title)
+            .id(egui::Id::new("archive_browser"))
+            .open(open)
+            .resizable(true)
+            .default_size([860.0, 520.0])
+            .show(ctx, |ui| {
+                if self.is_loading() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Lecture du contenu de l'archive...");
+                    });
+                    return;
+                }
+                if let Some(error) = &self.error {
+                    ui.label(RichText::new(error).color(Color32::from_rgb(234, 67, 53)));
+                    return;
+                }
+
+                let files = self.entries.iter().filter(|e| !e.is_dir).count();
+                let total: u64 = self.entries.iter().filter(|e| !e.is_dir).map(|e| e.size).sum();
+                let packed: u64 = self.entries.iter().filter_map(|e| e.packed).sum();
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} fichier(s)", files));
+                    ui.separator();
+                    ui.label(format!("Taille : {}", format_size(total)));
+                    if packed > 0 {
+                        ui.separator();
+                        ui.label(format!("Compressé : {}", format_size(packed)));
+                        if total > 0 {
+                            ui.separator();
+                            ui.label(format!("Ratio : {:.1}%", packed as f64 * 100.0 / total as f64));
+                        }
+                    }
+                    match self.recovery_record {
+                        Some(true) => {
+                            ui.separator();
+                            ui.colored_label(Color32::from_rgb(52, 168, 83), "🛡 Enregistrement de récupération")
+                                .on_hover_text("L'archive pourra être réparée si elle est endommagée");
+                        }
+                        Some(false) => {
+                            ui.separator();
+                            ui.colored_label(Color32::GRAY, "Sans enregistrement de récupération");
+                        }
+                        None => {}
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("🔎");
+                    ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("Rechercher...").desired_width(260.0));
+                    if !self.search.is_empty() && ui.small_button("✖").clicked() {
+                        self.search.clear();
+                    }
+                    if self.search.is_empty() {
+                        if ui.small_button("Tout déplier").clicked() {
+                            self.expanded = self.entries.iter().flat_map(|e| {
+                                let parts: Vec<&str> = e.path.split('/').collect();
+                                (1..parts.len()).map(move |n| parts[..n].join("/"))
+                            }).collect();
+                            self.expanded.extend(self.entries.iter().filter(|e| e.is_dir).map(|e| e.path.clone()));
+                        }
+                        if ui.small_button("Tout replier").clicked() {
+                            self.expanded.clear();
+                        }
+                    }
+                });
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.add_space(CHECKBOX_WIDTH);
+                    self.sort_header(ui, "Nom", SortColumn::Name, COL_NAME);
+                    self.sort_header(ui, "Taille", SortColumn::Size, COL_SIZE);
+                    self.sort_header(ui, "Compressé", SortColumn::Packed, COL_SIZE);
+                    self.sort_header(ui, "Modifié", SortColumn::Modified, COL_DATE);
+                    ui.add_sized([COL_CRC, 18.0], egui::Label::new(RichText::new("CRC").strong()));
+                    ui.add_sized([COL_METHOD, 18.0], egui::Label::new(RichText::new("Méthode").strong()));
+                });
+
+                let rows = if self.search.is_empty() {
+                    let mut rows = Vec::new();
+                    self.collect_rows(&self.tree, 0, &mut rows);
+                    rows
+                } else {
+                    self.search_rows()
+                };
+
+                let mut toggled = None;
+                let mut check_toggled = None;
+                let row_height = ui.text_style_height(&egui::TextStyle::Body) + 4.0;
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show_rows(ui, row_height, rows.len(), |ui, range| {
+                        for row in &rows[range] {
+                            ui.horizontal(|ui| {
+                                // Dossier coché si tous ses fichiers le sont
+                                let mut checked = if row.is_dir {
+                                    let files = self.files_under(&row.path);
+                                    !files.is_empty() && files.iter().all(|i| self.checked.contains(i))
+                                } else {
+                                    row.entry.is_some_and(|i| self.checked.contains(&i))
+                                };
+                                if ui.add_sized([CHECKBOX_WIDTH, row_height], egui::Checkbox::without_text(&mut checked)).changed() {
+                                    check_toggled = Some((row.path.clone(), checked));
+                                }
+
+                                let indent = row.depth as f32 * 14.0;
+                                let icon = if row.is_dir {
+                                    if self.expanded.contains(&row.path) { "📂" } else { "📁" }
+                                } else {
+                                    "📄"
+                                };
+                                let label = egui::Label::new(format!("{} {}", icon, row.name)).truncate().sense(egui::Sense::click());
+                                ui.add_space(indent);
+                                let response = ui.add_sized([COL_NAME - indent, row_height], label).on_hover_text(&row.path);
+                                if row.is_dir && self.search.is_empty() && response.clicked() {
+                                    toggled = Some(row.path.clone());
+                                }
+
+                                let entry = row.entry.map(|i| &self.entries[i]);
+                                ui.add_sized([COL_SIZE, row_height], egui::Label::new(format_size(row.size)));
+                                let packed = if row.packed > 0 { format_size(row.packed) } else { "-".to_string() };
+                                ui.add_sized([COL_SIZE, row_height], egui::Label::new(packed));
+                                ui.add_sized([COL_DATE, row_height], egui::Label::new(entry.map(|e| e.modified.as_str()).unwrap_or("")));
+                                ui.add_sized([COL_CRC, row_height], egui::Label::new(
+                                    RichText::new(entry.and_then(|e| e.crc.as_deref()).unwrap_or("")).monospace()));
+                                ui.add_sized([COL_METHOD, row_height], egui::Label::new(entry.and_then(|e| e.method.as_deref()).unwrap_or("")).truncate());
+                            });
+                        }
+                    });
+
+                if let Some(path) = toggled {
+                    if !self.expanded.remove(&path) {
+                        self.expanded.insert(path);
+                    }
+                }
+                if let Some((path, check)) = check_toggled {
+                    for index in self.files_under(&path) {
+                        if check {
+                            self.checked.insert(index);
+                        } else {
+                            self.checked.remove(&index);
+                        }
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Tout cocher").clicked() {
+                        self.checked = (0..self.entries.len()).filter(|&i| !self.entries[i].is_dir).collect();
+                    }
+                    if ui.button("Tout décocher").clicked() {
+                        self.checked.clear();
+                    }
+                    let checked_size: u64 = self.checked.iter().map(|&i| self.entries[i].size).sum();
+                    let label = format!("📤 Extraire la sélection ({} fichier(s), {})", self.checked.len(), format_size(checked_size));
+                    if ui.add_enabled(!self.checked.is_empty(), egui::Button::new(label).fill(Color32::from_rgb(52, 168, 83))).clicked() {
+                        action = Some(BrowserAction::ExtractSelection(self.selection()));
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(capabilities.update, egui::Button::new("➕ Ajouter...")).clicked() {
+                        if let Some(files) = rfd::FileDialog::new().set_title("Fichiers à ajouter").pick_files() {
+                            action = Some(BrowserAction::Update { op: UpdateOp::Add, sources: files, selection: Vec::new() });
+                        }
+                    }
+                    // Entrées cochées (toutes à défaut) remplacées par leur version plus récente sur le disque
+                    if ui
+                        .add_enabled(capabilities.update, egui::Button::new("🔄 Actualiser..."))
+                        .on_hover_text("Remplacer les fichiers modifiés depuis le dossier d'origine")
+                        .clicked()
+                    {
+                        if let Some(dir) = rfd::FileDialog::new().set_title("Dossier des fichiers d'origine").pick_folder() {
+                            let selection = if self.checked.is_empty() {
+                                self.entries.iter().filter(|e| !e.is_dir).map(|e| e.path.clone()).collect()
+                            } else {
+                                self.selection()
+                            };
+                            action = Some(BrowserAction::Update { op: UpdateOp::Freshen, sources: vec![dir], selection });
+                        }
+                    }
+                    let has_checked = !self.checked.is_empty();
+                    if ui.add_enabled(capabilities.update && has_checked, egui::Button::new("🗑 Supprimer")).clicked() {
+                        self.confirm_delete = true;
+                    }
+                    if ui
+                        .add_enabled(capabilities.rename && self.checked.len() == 1, egui::Button::new("✏ Renommer"))
+                        .on_disabled_hover_text("Cochez un seul fichier (7-Zip uniquement)")
+                        .clicked()
+                    {
+                        self.rename_to = self.selection().into_iter().next();
+                    }
+                });
+
+                if self.confirm_delete {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(format!("Supprimer {} fichier(s) de l'archive ?", self.checked.len()))
+                            .color(Color32::from_rgb(234, 67, 53)));
+                        if ui.button("Confirmer").clicked() {
+                            action = Some(BrowserAction::Update { op: UpdateOp::Delete, sources: Vec::new(), selection: self.selection() });
+                            self.confirm_delete = false;
+                        }
+                        if ui.button("Annuler").clicked() {
+                            self.confirm_delete = false;
+                        }
+                    });
+                }
+
+                if let Some(from) = self.selection().into_iter().next().filter(|_| self.checked.len() == 1) {
+                    if let Some(to) = &mut self.rename_to {
+                        let mut done = false;
+                        ui.horizontal(|ui| {
+                            ui.label("Nouveau nom :");
+                            ui.add(egui::TextEdit::singleline(to).desired_width(320.0));
+                            let valid = !to.trim().is_empty() && to.trim() != from;
+                            if ui.add_enabled(valid, egui::Button::new("OK")).clicked() {
+                                let op = UpdateOp::Rename { from: from.clone(), to: to.trim().to_string() };
+                                action = Some(BrowserAction::Update { op, sources: Vec::new(), selection: vec![from.clone()] });
+                                done = true;
+                            }
+                            if ui.button("Annuler").clicked() {
+                                done = true;
+                            }
+                        });
+                        if done {
+                            self.rename_to = None;
+                        }
+                    }
+                } else {
+                    self.rename_to = None;
+                }
+            });
+        action
+    }
+}
@@ -0,0 +1,257 @@
+// Conversion d'une archive vers un autre format (.7z ou .zip → .arc, .arc → .7z...) :
+// extraction dans un dossier de travail, recompression avec l'outil et le preset choisis,
+// test de l'archive produite et comparaison des tailles, en une seule tâche de la file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::AtomicBool;
+
+use crate::CompressionPreset;
+use crate::backend::BackendKind;
+use crate::diskspace::{self, SpaceCheck, SpaceNeed};
+use crate::format_size;
+use crate::listing;
+use crate::method;
+use crate::safety::SafetyReport;
+use crate::volumes::{self, Reassembly};
+
+/// Fichier que l'on peut convertir : archive d'un format connu ou premier volume
+pub fn is_archive(path: &Path) -> bool {
+    if !path.is_file() || (volumes::is_volume(path) && !volumes::is_first_volume(path)) {
+        return false;
+    }
+    let kind = BackendKind::for_archive(path);
+    let base = if volumes::is_volume(path) { volumes::base_archive(path) } else { path.to_path_buf() };
+    let ext = base.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+    kind != BackendKind::FreeArc || kind.backend().extensions().contains(&ext.as_str())
+}
+
+/// Taille de l'archive d'origine, volumes compris
+fn archive_size(archive: &Path) -> u64 {
+    let size = |p: &Path| fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+    if volumes::is_first_volume(archive) {
+        if let Ok(parts) = volumes::find_volumes(archive) {
+            return parts.iter().map(|p| size(p)).sum();
+        }
+    }
+    size(archive)
+}
+
+/// Conversion en cours. Le dossier de travail est supprimé avec la structure, ainsi que
+/// l'archive produite si la conversion n'est pas allée jusqu'au test
+pub struct Conversion {
+    pub source: PathBuf,
+    pub output: PathBuf,
+    pub work_dir: PathBuf,
+    pub source_backend: BackendKind,
+    pub target_backend: BackendKind,
+    /// Volumes d'origine à réassembler avant l'extraction
+    pub reassembly: Option<Reassembly>,
+    source_size: u64,
+    done: bool,
+}
+
+impl Conversion {
+    /// Vérifie source et destination puis crée le dossier de travail à côté de l'archive
+    /// produite : l'espace nécessaire est pris sur le même disque qu'elle
+    pub fn prepare(source: &Path, output: &Path, target_backend: BackendKind, tag: u64) -> Result<Self, String> {
+        let source = fs::canonicalize(source).map_err(|_| format!("L'archive {} n'existe pas", source.display()))?;
+        if output.exists() {
+            return Err(format!("L'archive {} existe déjà", output.display()));
+        }
+        let output = std::path::absolute(output).map_err(|e| format!("{} : {}", output.display(), e))?;
+        let source_backend = BackendKind::for_archive(&source);
+        let reassembly = volumes::prepare_reassembly(&source, source_backend.backend().capabilities().volumes, tag)?;
+
+        let work_dir = output.parent().ok_or("Destination invalide")?.join(format!("stelarc_convert_{}", tag));
+        if work_dir.exists() {
+            fs::remove_dir_all(&work_dir).map_err(|e| format!("Impossible de vider {} : {}", work_dir.display(), e))?;
+        }
+        fs::create_dir_all(&work_dir).map_err(|e| format!("Impossible de créer {} : {}", work_dir.display(), e))?;
+
+        let source_size = archive_size(&source);
+        Ok(Self { source, output, work_dir, source_backend, target_backend, reassembly, source_size, done: false })
+    }
+
+    /// Archive à lister : le fichier réassemblé s'il y a des volumes
+    pub fn listed_archive(&self) -> &Path {
+        self.reassembly.as_ref().map_or(&self.source, |r| &r.joined)
+    }
+
+    pub fn source_size(&self) -> u64 {
+        self.source_size
+    }
+
+    /// Besoins à vérifier avant de réassembler les volumes : le fichier réassemblé, puis
+    /// l'extraction et l'archive produite estimées d'après la taille des volumes
+    pub fn reassembly_needs(&self, flags: &[String]) -> Vec<SpaceNeed> {
+        let Some(reassembly) = &self.reassembly else { return Vec::new() };
+        let mut needs = reassembly.space_needs(&self.work_dir, "l'extraction");
+        needs.extend(diskspace::compression_needs(&self.output, self.source_size, flags, false));
+        needs
+    }
+
+    /// Vérifications avant l'extraction dans le dossier de travail : entrées dangereuses, et
+    /// espace pour les données extraites puis l'archive produite. None si l'outil ne sait
+    /// pas lister l'archive ; un listage en échec fait échouer la conversion. Appelé après le
+    /// réassemblage des volumes, dont l'espace libre mesuré tient déjà compte
+    pub fn preflight(&self, flags: &[String]) -> Result<Option<(SafetyReport, SpaceCheck)>, String> {
+        if !self.source_backend.backend().capabilities().list {
+            return Ok(None);
+        }
+        let entries = listing::list_archive(self.listed_archive())?;
+        let unpacked = entries.iter().filter(|e| !e.is_dir).map(|e| e.size).sum();
+        let mut needs = vec![SpaceNeed::exact(&self.work_dir, unpacked, "l'extraction")];
+        needs.extend(diskspace::compression_needs(&self.output, unpacked, flags, false));
+        Ok(Some((SafetyReport::check(&entries), diskspace::check(&needs))))
+    }
+
+    pub fn extract_command(&self, password: Option<&str>) -> Command {
+        let backend = self.source_backend.backend();
+        let archive = self.reassembly.as_ref().map_or(&self.source, |r| &r.joined);
+        let mut cmd = backend.extract_command(archive, &self.work_dir);
+        if let Some(password) = password {
+            cmd.args(backend.password_args(password));
+        }
+        cmd
+    }
+
+    /// Recompression de ce qui a été extrait, chemins relatifs au dossier de travail
+    pub fn compress_command(&self, flags: &[String]) -> Result<Command, String> {
+        let mut sources: Vec<PathBuf> = fs::read_dir(&self.work_dir)
+            .map_err(|e| format!("{} : {}", self.work_dir.display(), e))?
+            .filter_map(|e| e.ok())
+            .map(|e| PathBuf::from(e.file_name()))
+            .collect();
+        sources.sort();
+        let backend = self.target_backend.backend();
+        if sources.is_empty() {
+            return Err("L'extraction n'a produit aucun fichier".to_string());
+        }
+        if sources.len() > 1 && !backend.capabilities().multiple_sources {
+            return Err(format!("{} ne compresse qu'une seule source à la fois", backend.name()));
+        }
+        let flags: Vec<&str> = flags.iter().map(String::as_str).collect();
+        let mut cmd = backend.compress_command(&self.output, &sources, &flags);
+        cmd.current_dir(&self.work_dir);
+        Ok(cmd)
+    }
+
+    pub fn test_command(&self) -> Option<Command> {
+        let backend = self.target_backend.backend();
+        backend.capabilities().test.then(|| backend.test_command(&self.output)).flatten()
+    }
+
+    /// Conversion réussie : l'archive produite est conservée. Retourne le bilan des tailles
+    pub fn finish(&mut self) -> String {
+        self.done = true;
+        let output_size = fs::metadata(&self.output).map(|m| m.len()).unwrap_or(0);
+        let change = if self.source_size > 0 { (output_size as f64 / self.source_size as f64 - 1.0) * 100.0 } else { 0.0 };
+        format!(
+            "Conversion terminée : {} → {} ({:+.1} %)",
+            format_size(self.source_size),
+            format_size(output_size),
+            change
+        )
+    }
+}
+
+/// Conversion en mode console : chaque étape affiche directement la sortie de l'outil
+pub fn run_convert_cli(archive: &Path, output: &Path, preset: &CompressionPreset) -> bool {
+    let target = preset.backend();
+    for backend in [BackendKind::for_archive(archive).backend(), target.backend()] {
+        if !backend.is_available() {
+            eprintln!("Erreur : {} n'est pas installé correctement", backend.name());
+            return false;
+        }
+    }
+    let mut conversion = match Conversion::prepare(archive, output, target, std::process::id() as u64) {
+        Ok(conversion) => conversion,
+        Err(e) => {
+            eprintln!("Erreur : {}", e);
+            return false;
+        }
+    };
+    let step = |label: &str, cmd: Result<Command, String>| -> bool {
+        println!("{}", label);
+        match cmd.and_then(|mut cmd| cmd.status().map_err(|e| format!("Erreur lors de l'exécution de la commande : {}", e))) {
+            Ok(status) if status.success() => true,
+            Ok(status) => {
+                eprintln!("Erreur : échec de l'étape \"{}\" (code {:?})", label, status.code());
+                false
+            }
+            Err(e) => {
+                eprintln!("Erreur : {}", e);
+                false
+            }
+        }
+    };
+    let flags: Vec<String> = preset.flags().iter().map(|f| f.to_string()).collect();
+    match method::check_memory(target.backend(), &flags, method::cpu_count(), method::system_memory()) {
+        Ok(warnings) => {
+            for warning in warnings {
+                println!("Attention : {}", warning);
+            }
+        }
+        Err(e) => {
+            eprintln!("Erreur : {}", e);
+            return false;
+        }
+    }
+    let needs = conversion.reassembly_needs(&flags);
+    if let Some(reassembly) = &mut conversion.reassembly {
+        let joined = diskspace::check(&needs)
+            .into_result(|warning| println!("{}", warning))
+            .and_then(|_| reassembly.join(&AtomicBool::new(false)).map_err(|e| format!("Erreur lors du réassemblage des volumes : {}", e)));
+        if let Err(e) = joined {
+            eprintln!("Erreur : {}", e);
+            return false;
+        }
+    }
+    match conversion.preflight(&flags) {
+        Ok(Some((report, space))) => {
+            for line in report.log_lines() {
+                eprintln!("{}", line);
+            }
+            println!("{}", report.summary());
+            if !report.is_clean() {
+                eprintln!("Erreur : conversion refusée, l'archive contient des entrées dangereuses");
+                return false;
+            }
+            if let Err(e) = space.into_result(|warning| println!("{}", warning)) {
+                eprintln!("Erreur : {}", e);
+                return false;
+            }
+        }
+        Ok(None) => println!(
+            "Attention : {} ne permet pas de vérifier le contenu de l'archive avant conversion",
+            conversion.source_backend.backend().name()
+        ),
+        Err(e) => {
+            eprintln!("Erreur : contenu de l'archive illisible ({})", e);
+            return false;
+        }
+    }
+    if !step("Extraction", Ok(conversion.extract_command(None)))
+        || !step("Compression", conversion.compress_command(&flags))
+    {
+        return false;
+    }
+    if let Some(cmd) = conversion.test_command() {
+        if !step("Test", Ok(cmd)) {
+            return false;
+        }
+    }
+    println!("{}", conversion.finish());
+    true
+}
+
+impl Drop for Conversion {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.work_dir);
+        if !self.done && self.output.exists() {
+            let _ = fs::remove_file(&self.output);
+        }
+    }
+}
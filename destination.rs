@@ -0,0 +1,96 @@
+// Dossier d'extraction en un clic, comme "Extraire ici" dans l'explorateur : à côté de
+// l'archive, dans un sous-dossier portant son nom, ou en mode intelligent (sous-dossier
+// seulement si l'archive a plusieurs éléments à la racine).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::backend::BackendKind;
+use crate::listing::{self, ArchiveEntry};
+use crate::volumes;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ExtractMode {
+    #[default]
+    Choose, // Sélecteur de dossier
+    Here,
+    Subfolder,
+    Smart,
+}
+
+impl ExtractMode {
+    pub fn all() -> &'static [ExtractMode] {
+        &[ExtractMode::Choose, ExtractMode::Here, ExtractMode::Subfolder, ExtractMode::Smart]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExtractMode::Choose => "Choisir le dossier...",
+            ExtractMode::Here => "Extraire ici",
+            ExtractMode::Subfolder => "Dans un dossier au nom de l'archive",
+            ExtractMode::Smart => "Intelligent (dossier si plusieurs éléments)",
+        }
+    }
+
+    /// Mot-clé de la ligne de commande (`--mode smart`) ; le sélecteur n'en a pas
+    pub fn keyword(&self) -> Option<&'static str> {
+        match self {
+            ExtractMode::Choose => None,
+            ExtractMode::Here => Some("here"),
+            ExtractMode::Subfolder => Some("folder"),
+            ExtractMode::Smart => Some("smart"),
+        }
+    }
+
+    pub fn parse(keyword: &str) -> Option<ExtractMode> {
+        ExtractMode::all().iter().copied().find(|m| m.keyword().is_some_and(|k| k.eq_ignore_ascii_case(keyword.trim())))
+    }
+}
+
+/// Nom de l'archive sans extension(s) : `photos.tar.gz` → `photos`, `backup.7z.001` → `backup`
+pub fn archive_stem(archive: &Path) -> String {
+    let base = if volumes::is_volume(archive) { volumes::base_archive(archive) } else { archive.to_path_buf() };
+    let mut stem = base.file_stem().and_then(|s| s.to_str()).unwrap_or("archive").to_string();
+    if stem.to_ascii_lowercase().ends_with(".tar") {
+        stem.truncate(stem.len() - 4);
+    }
+    if stem.is_empty() { "archive".to_string() } else { stem }
+}
+
+/// Éléments distincts à la racine des entrées retenues (sélection vide = toutes)
+pub fn top_level_entries(entries: &[ArchiveEntry], selection: &[String]) -> BTreeSet<String> {
+    entries
+        .iter()
+        .map(|e| e.path.trim_start_matches('/'))
+        .filter(|path| selection.is_empty() || selection.iter().any(|s| path == s || path.starts_with(&format!("{}/", s))))
+        .filter_map(|path| path.split('/').next().filter(|first| !first.is_empty()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Dossier de l'archive : destination de "Extraire ici"
+pub fn archive_dir(archive: &Path) -> PathBuf {
+    archive.parent().filter(|p| !p.as_os_str().is_empty()).map_or_else(|| PathBuf::from("."), Path::to_path_buf)
+}
+
+/// Dossier d'extraction dans `base` (dossier de l'archive ou destination donnée) pour un
+/// mode autre que `Choose`, d'après le contenu déjà listé. Sans contenu, le mode
+/// intelligent crée le sous-dossier
+pub fn resolve_listed(archive: &Path, base: &Path, selection: &[String], mode: ExtractMode, entries: Option<&[ArchiveEntry]>) -> PathBuf {
+    let subfolder = base.join(archive_stem(archive));
+    match (mode, entries) {
+        (ExtractMode::Choose | ExtractMode::Here, _) => base.to_path_buf(),
+        (ExtractMode::Smart, Some(entries)) if top_level_entries(entries, selection).len() <= 1 => base.to_path_buf(),
+        _ => subfolder,
+    }
+}
+
+/// Comme `resolve_listed`, en listant `source` en mode intelligent : `archive` donne le nom
+/// du sous-dossier, `source` est l'archive lue (volumes réassemblés le cas échéant)
+pub fn resolve(archive: &Path, source: &Path, base: &Path, selection: &[String], mode: ExtractMode) -> PathBuf {
+    let entries = (mode == ExtractMode::Smart && BackendKind::for_archive(archive).backend().capabilities().list)
+        .then(|| listing::list_archive(source).ok())
+        .flatten();
+    resolve_listed(archive, base, selection, mode, entries.as_deref())
+}
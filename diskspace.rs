@@ -0,0 +1,120 @@
+// Vérification de l'espace libre avant de lancer une opération : disque de destination,
+// et dossier temporaire quand la méthode y écrit (precomp, srep, xtool). Un besoin connu
+// exactement (taille décompressée, volumes à réassembler) bloque le lancement ; une
+// estimation (taille de l'archive à produire) donne seulement un avertissement.
+
+use std::path::{Path, PathBuf};
+use sysinfo::Disks;
+
+use crate::format_size;
+
+/// Méthodes FreeArc qui écrivent des fichiers temporaires de la taille des données
+const TEMP_METHODS: &[&str] = &["precomp", "srep", "xtool"];
+
+/// Espace demandé sur le disque contenant `path`
+#[derive(Debug, Clone)]
+pub struct SpaceNeed {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub label: &'static str, // "l'extraction", "les fichiers temporaires"...
+    pub exact: bool,         // Taille connue : bloquant si elle ne tient pas
+}
+
+impl SpaceNeed {
+    pub fn exact(path: &Path, bytes: u64, label: &'static str) -> SpaceNeed {
+        SpaceNeed { path: path.to_path_buf(), bytes, label, exact: true }
+    }
+
+    pub fn estimated(path: &Path, bytes: u64, label: &'static str) -> SpaceNeed {
+        SpaceNeed { path: path.to_path_buf(), bytes, label, exact: false }
+    }
+}
+
+/// Besoins d'une compression de `source_size` octets vers `archive` : l'archive (au plus
+/// la taille des données), les volumes si elle est découpée ensuite, puis les fichiers
+/// temporaires des méthodes qui en écrivent (dossier TEMP) ; la taille finale n'étant
+/// qu'une estimation, rien n'est bloquant
+pub fn compression_needs(archive: &Path, source_size: u64, flags: &[impl AsRef<str>], split: bool) -> Vec<SpaceNeed> {
+    let dir = archive.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let mut needs = vec![SpaceNeed::estimated(dir, source_size, "l'archive")];
+    if split {
+        needs.push(SpaceNeed::estimated(dir, source_size, "les volumes"));
+    }
+    let temp = flags.iter().any(|flag| {
+        let flag = flag.as_ref().to_lowercase();
+        flag.starts_with("-m") && TEMP_METHODS.iter().any(|method| flag.contains(method))
+    });
+    if temp {
+        needs.push(SpaceNeed::estimated(&std::env::temp_dir(), source_size, "les fichiers temporaires"));
+    }
+    needs
+}
+
+/// Premier dossier existant en remontant : la destination n'est pas toujours encore créée
+fn existing_ancestor(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    absolute
+        .ancestors()
+        .find(|p| p.exists())
+        .and_then(|p| p.canonicalize().ok())
+        .unwrap_or(absolute)
+}
+
+/// Disque contenant le chemin : point de montage le plus long qui le préfixe
+fn disk_for<'a>(disks: &'a Disks, path: &Path) -> Option<&'a sysinfo::Disk> {
+    let path = existing_ancestor(path);
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+}
+
+/// Résultat de la vérification : avertissements à journaliser, ou refus de lancer
+#[derive(Debug, Clone, Default)]
+pub struct SpaceCheck {
+    pub warnings: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl SpaceCheck {
+    /// Transmet les avertissements à `log` ; Err si l'opération ne doit pas être lancée
+    pub fn into_result(self, mut log: impl FnMut(String)) -> Result<(), String> {
+        self.warnings.into_iter().for_each(&mut log);
+        self.error.map_or(Ok(()), Err)
+    }
+}
+
+/// Regroupe les besoins par disque (destination et dossier temporaire peuvent coïncider)
+/// et les compare à l'espace libre. Un disque introuvable n'est pas vérifié
+pub fn check(needs: &[SpaceNeed]) -> SpaceCheck {
+    let disks = Disks::new_with_refreshed_list();
+    let mut result = SpaceCheck::default();
+    let mut grouped: Vec<(&sysinfo::Disk, Vec<&SpaceNeed>)> = Vec::new();
+    for need in needs.iter().filter(|n| n.bytes > 0) {
+        let Some(disk) = disk_for(&disks, &need.path) else { continue };
+        match grouped.iter_mut().find(|(d, _)| d.mount_point() == disk.mount_point()) {
+            Some((_, list)) => list.push(need),
+            None => grouped.push((disk, vec![need])),
+        }
+    }
+    for (disk, list) in grouped {
+        let available = disk.available_space();
+        let exact: u64 = list.iter().filter(|n| n.exact).map(|n| n.bytes).sum();
+        let total: u64 = list.iter().map(|n| n.bytes).sum();
+        let details: Vec<String> = list.iter().map(|n| format!("{} pour {}", format_size(n.bytes), n.label)).collect();
+        let message = format!(
+            "{} libre(s) sur {}, {} nécessaire(s) ({})",
+            format_size(available),
+            disk.mount_point().display(),
+            format_size(total),
+            details.join(", ")
+        );
+        if exact > available {
+            result.error.get_or_insert(format!("Espace disque insuffisant : {}", message));
+        } else if total > available {
+            result.warnings.push(format!("Attention : l'espace disque risque de manquer : {}", message));
+        }
+    }
+    result
+}
@@ -0,0 +1,111 @@
+// Chiffrement des archives : algorithmes proposés (comme compress.bat) et
+// masquage du mot de passe dans tout ce qui est affiché ou journalisé.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum EncryptionAlgorithm {
+    Aes256,
+    Blowfish,
+    Twofish,
+    Serpent,
+    Cascade, // Méthode libre, ex. "aes+serpent/cfb+twofish"
+}
+
+impl EncryptionAlgorithm {
+    pub fn all() -> &'static [EncryptionAlgorithm] {
+        &[
+            EncryptionAlgorithm::Aes256,
+            EncryptionAlgorithm::Blowfish,
+            EncryptionAlgorithm::Twofish,
+            EncryptionAlgorithm::Serpent,
+            EncryptionAlgorithm::Cascade,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EncryptionAlgorithm::Aes256 => "AES-256",
+            EncryptionAlgorithm::Blowfish => "Blowfish",
+            EncryptionAlgorithm::Twofish => "Twofish",
+            EncryptionAlgorithm::Serpent => "Serpent",
+            EncryptionAlgorithm::Cascade => "Cascade personnalisée",
+        }
+    }
+}
+
+/// Options de chiffrement d'une compression. Le mot de passe n'en fait pas partie :
+/// il n'est jamais sauvegardé sur disque.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncryptionSettings {
+    pub algorithm: EncryptionAlgorithm,
+    pub cascade: String,
+    pub encrypt_headers: bool, // Masque aussi la liste des fichiers
+}
+
+impl Default for EncryptionSettings {
+    fn default() -> Self {
+        Self { algorithm: EncryptionAlgorithm::Aes256, cascade: String::new(), encrypt_headers: false }
+    }
+}
+
+impl EncryptionSettings {
+    /// Méthode au format FreeArc (`--encryption=...`)
+    pub fn method(&self) -> String {
+        match self.algorithm {
+            EncryptionAlgorithm::Aes256 => "aes-256".to_string(),
+            EncryptionAlgorithm::Blowfish => "blowfish".to_string(),
+            EncryptionAlgorithm::Twofish => "twofish".to_string(),
+            EncryptionAlgorithm::Serpent => "serpent".to_string(),
+            EncryptionAlgorithm::Cascade => self.cascade.trim().to_string(),
+        }
+    }
+}
+
+/// Vérifie le mot de passe saisi et sa confirmation
+pub fn validate_password(password: &str, confirmation: &str) -> Result<(), String> {
+    if password.is_empty() {
+        return Err("Le mot de passe est vide".to_string());
+    }
+    if password != confirmation {
+        return Err("Les mots de passe ne correspondent pas".to_string());
+    }
+    Ok(())
+}
+
+/// Ligne de commande affichable : chaque occurrence du mot de passe est remplacée.
+/// Construite argument par argument, car l'échappement de `{:?}` pourrait empêcher
+/// de retrouver le mot de passe dans la chaîne formatée.
+pub fn describe_command(cmd: &Command, password: Option<&str>) -> String {
+    let hide = |text: &str| match password {
+        Some(p) if !p.is_empty() => text.replace(p, "********"),
+        _ => text.to_string(),
+    };
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| format!("\"{}\"", hide(&arg.to_string_lossy())))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Messages des outils indiquant un mot de passe manquant ou incorrect ; les noms de
+/// fichiers de la sortie ("passwords.txt") ne doivent pas suffire
+const PASSWORD_ERRORS: &[&str] = &[
+    // 7-Zip
+    "wrong password",
+    "can not open encrypted archive",
+    "cannot open encrypted archive",
+    "data error in encrypted file",
+    "enter password (will not be echoed)",
+    // FreeArc
+    "bad password",
+    "incorrect password",
+    "password required",
+    "enter decryption password",
+];
+
+pub fn is_password_error(line: &str) -> bool {
+    let line = line.to_lowercase();
+    PASSWORD_ERRORS.iter().any(|message| line.contains(message))
+}
@@ -0,0 +1,232 @@
+// Filtres d'inclusion et d'exclusion appliqués aux sources d'une compression : motifs,
+// extensions, taille, date de modification et fichiers cachés. Les fichiers retenus sont
+// passés à l'outil par fichier liste (@liste).
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::format_size;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SourceFilter {
+    /// Motifs séparés par des ';' (`*.rs; docs/**`) ; vide = tous les fichiers
+    pub include: String,
+    /// Motifs exclus ; un dossier exclu n'est pas parcouru (`target; .git; *.tmp`)
+    pub exclude: String,
+    /// Extensions retenues, séparées par des ';' ou des ',' (`jpg, png`) ; vide = toutes
+    pub extensions: String,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    /// Dates "AAAA-MM-JJ" ; vides = pas de limite
+    pub modified_after: String,
+    pub modified_before: String,
+    pub exclude_hidden: bool,
+}
+
+/// Fichiers retenus par les filtres
+#[derive(Debug, Clone, Default)]
+pub struct FilteredFiles {
+    pub files: Vec<PathBuf>,
+    pub total_size: u64,
+    pub excluded: usize, // Fichiers écartés (hors dossiers exclus, non parcourus)
+}
+
+fn split_list(text: &str) -> impl Iterator<Item = &str> {
+    text.split([';', ',']).map(str::trim).filter(|p| !p.is_empty())
+}
+
+impl SourceFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == SourceFilter::default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (label, date) in [("après", &self.modified_after), ("avant", &self.modified_before)] {
+            if !date.trim().is_empty() && parse_date(date).is_none() {
+                return Err(format!("Date \"modifié {}\" invalide : {} (format AAAA-MM-JJ)", label, date.trim()));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_size, self.max_size) {
+            if min > max {
+                return Err("La taille minimale dépasse la taille maximale".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Résumé d'une ligne, pour les presets et la file d'attente
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.include.trim().is_empty() {
+            parts.push(format!("inclure {}", self.include.trim()));
+        }
+        if !self.exclude.trim().is_empty() {
+            parts.push(format!("exclure {}", self.exclude.trim()));
+        }
+        if !self.extensions.trim().is_empty() {
+            parts.push(format!("extensions {}", self.extensions.trim()));
+        }
+        if let Some(min) = self.min_size {
+            parts.push(format!("≥ {}", format_size(min)));
+        }
+        if let Some(max) = self.max_size {
+            parts.push(format!("≤ {}", format_size(max)));
+        }
+        if !self.modified_after.trim().is_empty() {
+            parts.push(format!("modifié après le {}", self.modified_after.trim()));
+        }
+        if !self.modified_before.trim().is_empty() {
+            parts.push(format!("modifié avant le {}", self.modified_before.trim()));
+        }
+        if self.exclude_hidden {
+            parts.push("sans fichiers cachés".to_string());
+        }
+        if parts.is_empty() { "Aucun filtre".to_string() } else { parts.join(", ") }
+    }
+
+    /// Motif sans '/' : comparé au nom ; avec '/' : au chemin relatif à la sélection
+    fn pattern_matches(pattern: &str, relative: &str, name: &str) -> bool {
+        let pattern = pattern.replace('\\', "/");
+        if pattern.contains('/') {
+            glob_match(pattern.trim_start_matches("./"), relative)
+        } else {
+            glob_match(&pattern, name)
+        }
+    }
+
+    fn is_excluded(&self, relative: &str, name: &str, hidden: bool) -> bool {
+        (self.exclude_hidden && hidden) || split_list(&self.exclude).any(|p| Self::pattern_matches(p, relative, name))
+    }
+
+    fn accepts_file(&self, relative: &str, name: &str, metadata: &fs::Metadata) -> bool {
+        let mut includes = split_list(&self.include).peekable();
+        if includes.peek().is_some() && !includes.any(|p| Self::pattern_matches(p, relative, name)) {
+            return false;
+        }
+        let mut extensions = split_list(&self.extensions).peekable();
+        if extensions.peek().is_some() {
+            let ext = Path::new(name).extension().and_then(|e| e.to_str()).unwrap_or("");
+            if !extensions.any(|e| e.trim_start_matches("*.").trim_start_matches('.').eq_ignore_ascii_case(ext)) {
+                return false;
+            }
+        }
+        let size = metadata.len();
+        if self.min_size.is_some_and(|min| size < min) || self.max_size.is_some_and(|max| size > max) {
+            return false;
+        }
+        if let Ok(modified) = metadata.modified() {
+            if parse_date(&self.modified_after).is_some_and(|after| modified < after) {
+                return false;
+            }
+            if parse_date(&self.modified_before).is_some_and(|before| modified >= before) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn walk(&self, path: &Path, relative: &str, result: &mut FilteredFiles) {
+        let Ok(metadata) = fs::symlink_metadata(path) else { return };
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or(relative);
+        if self.is_excluded(relative, name, is_hidden(name, &metadata)) {
+            if metadata.is_file() {
+                result.excluded += 1;
+            }
+            return;
+        }
+        if metadata.is_dir() {
+            let Ok(entries) = fs::read_dir(path) else { return };
+            let mut children: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+            children.sort();
+            for child in children {
+                let child_name = child.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+                self.walk(&child, &format!("{}/{}", relative, child_name), result);
+            }
+        } else if self.accepts_file(relative, name, &metadata) {
+            result.total_size += metadata.len();
+            result.files.push(path.to_path_buf());
+        } else {
+            result.excluded += 1;
+        }
+    }
+
+    /// Parcourt les sources et retient les fichiers qui passent les filtres
+    pub fn collect(&self, sources: &[PathBuf]) -> FilteredFiles {
+        let mut result = FilteredFiles::default();
+        for source in sources {
+            let name = source.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+            self.walk(source, &name, &mut result);
+        }
+        result
+    }
+}
+
+#[cfg(windows)]
+fn is_hidden(name: &str, metadata: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+    name.starts_with('.') || metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0
+}
+
+#[cfg(not(windows))]
+fn is_hidden(name: &str, _metadata: &fs::Metadata) -> bool {
+    name.starts_with('.')
+}
+
+/// `*` : tout sauf '/', `**` : tout y compris '/', `?` : un caractère ; sans tenir compte de la casse
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    glob_from(&pattern, &text)
+}
+
+fn glob_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', '/', rest @ ..] => {
+            // "**/" peut aussi ne rien représenter : "src/**/a.rs" couvre "src/a.rs"
+            glob_from(rest, text) || (0..text.len()).any(|i| text[i] == '/' && glob_from(rest, &text[i + 1..]))
+        }
+        ['*', '*', rest @ ..] => (0..=text.len()).any(|i| glob_from(rest, &text[i..])),
+        ['*', rest @ ..] => {
+            let limit = text.iter().position(|&c| c == '/').unwrap_or(text.len());
+            (0..=limit).any(|i| glob_from(rest, &text[i..]))
+        }
+        ['?', rest @ ..] => text.first().is_some_and(|&c| c != '/') && glob_from(rest, &text[1..]),
+        [c, rest @ ..] => text.first() == Some(c) && glob_from(rest, &text[1..]),
+    }
+}
+
+/// "AAAA-MM-JJ" → minuit UTC de ce jour
+pub fn parse_date(text: &str) -> Option<SystemTime> {
+    let mut parts = text.trim().splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // Jours depuis le 1970-01-01 (algorithme "days from civil")
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    let seconds = u64::try_from(days * 86400).ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+/// Écrit la liste des fichiers retenus pour l'outil, chemins relatifs à `base` quand c'est possible
+pub fn write_list_file(files: &[PathBuf], base: &Path, tag: u64) -> Result<PathBuf, String> {
+    let list_file = std::env::temp_dir().join(format!("stelarc_filter_{}.txt", tag));
+    let lines: Vec<String> = files
+        .iter()
+        .map(|f| f.strip_prefix(base).unwrap_or(f).display().to_string())
+        .collect();
+    fs::write(&list_file, lines.join("\n")).map_err(|e| format!("Impossible d'écrire la liste des fichiers : {}", e))?;
+    Ok(list_file)
+}
@@ -0,0 +1,344 @@
+// Groupes de fichiers FreeArc ($text, $exe, $obj...) : motifs de noms rattachés à chaque
+// groupe et, au besoin, une chaîne de méthodes propre au groupe (`-m4/$text=ppmd`).
+// Les groupes sont passés à FreeArc dans un fichier (--groups=...), pour que les presets
+// qui s'en servent (`-mc$default,$obj:+preshark`) voient les mêmes définitions.
+
+use eframe::egui;
+use egui::{Color32, RichText};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::backend::BackendKind;
+use crate::method::{self, Chain};
+
+pub const GROUPS_FILE: &str = "stelarc_groups.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileGroup {
+    pub name: String, // Avec le '$' : "$text"
+    pub patterns: Vec<String>,
+    /// Chaîne de méthodes propre au groupe (`ppmd:o10`) ; vide = méthode du preset
+    #[serde(default)]
+    pub method: String,
+}
+
+impl FileGroup {
+    fn new(name: &str, patterns: &[&str]) -> Self {
+        Self { name: name.to_string(), patterns: patterns.iter().map(|p| p.to_string()).collect(), method: String::new() }
+    }
+}
+
+/// Groupes dans l'ordre de recherche : un fichier appartient au premier groupe qui le reconnaît
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileGroups {
+    pub groups: Vec<FileGroup>,
+}
+
+impl Default for FileGroups {
+    fn default() -> Self {
+        Self {
+            groups: vec![
+                FileGroup::new("$text", &[
+                    "*.txt", "*.htm", "*.html", "*.xml", "*.css", "*.js", "*.json", "*.csv", "*.ini", "*.cfg",
+                    "*.log", "*.md", "*.rtf", "*.c", "*.h", "*.cpp", "*.hpp", "*.rs", "*.py", "*.java", "*.cs",
+                    "*.pas", "*.sql", "*.srt",
+                ]),
+                FileGroup::new("$exe", &["*.exe", "*.dll", "*.sys", "*.ocx", "*.scr", "*.cpl", "*.com"]),
+                FileGroup::new("$obj", &["*.obj", "*.o", "*.lib", "*.a", "*.so", "*.pdb", "*.class"]),
+                FileGroup::new("$wav", &["*.wav", "*.aif", "*.aiff"]),
+                FileGroup::new("$bmp", &["*.bmp", "*.tga", "*.pcx", "*.ppm", "*.pgm", "*.pnm"]),
+                FileGroup::new("$compressed", &[
+                    "*.zip", "*.rar", "*.7z", "*.arc", "*.gz", "*.bz2", "*.xz", "*.cab", "*.jpg", "*.jpeg",
+                    "*.png", "*.gif", "*.mp3", "*.ogg", "*.flac", "*.mp4", "*.mkv", "*.avi", "*.mov", "*.webm",
+                ]),
+            ],
+        }
+    }
+}
+
+/// Fichier de groupes livré avec FreeArc, à côté de son exécutable
+pub fn arc_groups_path() -> PathBuf {
+    BackendKind::FreeArc.backend().executable().with_file_name("arc.groups")
+}
+
+impl FileGroups {
+    /// Fichier absent ou illisible : groupes par défaut
+    pub fn load(path: &Path) -> FileGroups {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, content).map_err(|e| e.to_string())
+    }
+
+    /// Format arc.groups : une ligne `$groupe` suivie de ses motifs, `;` pour les commentaires
+    pub fn parse_arc_groups(text: &str) -> Result<FileGroups, String> {
+        let mut groups: Vec<FileGroup> = Vec::new();
+        for line in text.lines() {
+            let line = line.split(';').next().unwrap_or_default().trim();
+            if line.is_empty() || line.starts_with('[') {
+                continue;
+            }
+            if line.starts_with('$') {
+                groups.push(FileGroup::new(line, &[]));
+            } else {
+                let group = groups.last_mut().ok_or_else(|| format!("Motif hors de tout groupe : {}", line))?;
+                group.patterns.extend(line.split_whitespace().map(str::to_string));
+            }
+        }
+        if groups.is_empty() {
+            return Err("Aucun groupe trouvé".to_string());
+        }
+        Ok(FileGroups { groups })
+    }
+
+    pub fn load_arc_groups(path: &Path) -> Result<FileGroups, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{} : {}", path.display(), e))?;
+        Self::parse_arc_groups(&text)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, group) in self.groups.iter().enumerate() {
+            if group.name.len() < 2 || !group.name.starts_with('$') || group.name.contains(char::is_whitespace) {
+                return Err(format!("Nom de groupe invalide : \"{}\" (format $nom)", group.name));
+            }
+            if self.groups[..i].iter().any(|g| g.name.eq_ignore_ascii_case(&group.name)) {
+                return Err(format!("Le groupe {} est défini deux fois", group.name));
+            }
+        }
+        self.methods().map(|_| ())
+    }
+
+    /// Chaînes de méthodes des groupes qui en précisent une
+    pub fn methods(&self) -> Result<Vec<(String, Chain)>, String> {
+        self.groups
+            .iter()
+            .filter(|g| !g.method.trim().is_empty())
+            .map(|g| {
+                let chain = method::parse_chain(g.method.trim()).map_err(|e| format!("Méthode de {} : {}", g.name, e))?;
+                Ok((g.name.clone(), chain))
+            })
+            .collect()
+    }
+
+    /// Options du preset complétées des méthodes propres aux groupes
+    pub fn compression_flags<S: AsRef<str>>(&self, flags: &[S]) -> Result<Vec<String>, String> {
+        Ok(method::with_group_methods(flags, &self.methods()?))
+    }
+
+    /// Contenu au format arc.groups ; les fichiers qui ne correspondent à aucun groupe vont dans $default
+    pub fn to_arc_groups(&self) -> String {
+        let mut lines = vec!["; Groupes de fichiers écrits par Stelarc".to_string()];
+        for group in &self.groups {
+            lines.push(group.name.clone());
+            lines.extend(group.patterns.iter().cloned());
+        }
+        if !self.groups.iter().any(|g| g.name.eq_ignore_ascii_case("$default")) {
+            lines.push("$default".to_string());
+            lines.push("*".to_string());
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// Écrit le fichier de groupes passé à l'outil pour une tâche
+    pub fn write_groups_file(&self, tag: u64) -> Result<PathBuf, String> {
+        let path = std::env::temp_dir().join(format!("stelarc_groups_{}.groups", tag));
+        fs::write(&path, self.to_arc_groups()).map_err(|e| format!("Impossible d'écrire les groupes de fichiers : {}", e))?;
+        Ok(path)
+    }
+}
+
+/// Fenêtre de gestion des groupes de fichiers
+#[derive(Default)]
+pub struct GroupEditor {
+    draft: Option<FileGroups>, // Copie en cours de modification, reprise des groupes à l'ouverture
+    selected: usize,
+    patterns_text: String, // Motifs du groupe sélectionné, un par ligne
+    error: Option<String>,
+}
+
+impl GroupEditor {
+    fn select(&mut self, index: usize) {
+        self.selected = index;
+        self.patterns_text = self
+            .draft
+            .as_ref()
+            .and_then(|d| d.groups.get(index))
+            .map_or_else(String::new, |g| g.patterns.join("\n"));
+    }
+
+    fn reset(&mut self, groups: FileGroups) {
+        self.draft = Some(groups);
+        self.error = None;
+        self.select(0);
+    }
+
+    /// Retourne true si les groupes ont été enregistrés.
+    /// `preset_flags` : options du preset FreeArc courant, pour l'aperçu
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        groups: &mut FileGroups,
+        enabled: &mut bool,
+        preset_flags: Option<&[String]>,
+    ) -> bool {
+        if self.draft.is_none() {
+            self.reset(groups.clone());
+        }
+        let mut changed = false;
+        egui::Window::new("🗂 Groupes de fichiers")
+            .open(open)
+            .resizable(true)
+            .default_width(680.0)
+            .show(ctx, |ui| {
+                ui.checkbox(enabled, "Appliquer aux compressions FreeArc")
+                    .on_hover_text("Les groupes et leurs méthodes complètent le preset choisi, intégré ou utilisateur");
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    // Liste des groupes, dans l'ordre de recherche
+                    ui.vertical(|ui| {
+                        ui.set_width(200.0);
+                        let Some(draft) = self.draft.as_mut() else { return };
+                        if ui.button("➕ Nouveau").clicked() {
+                            draft.groups.push(FileGroup::new("$nouveau", &[]));
+                            let last = draft.groups.len() - 1;
+                            self.select(last);
+                            return;
+                        }
+                        ui.separator();
+                        let mut action = None;
+                        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                            for (index, group) in draft.groups.iter().enumerate() {
+                                ui.horizontal(|ui| {
+                                    let label = if group.method.trim().is_empty() {
+                                        group.name.clone()
+                                    } else {
+                                        format!("{} = {}", group.name, group.method.trim())
+                                    };
+                                    if ui.selectable_label(self.selected == index, label).clicked() {
+                                        action = Some((index, 0));
+                                    }
+                                    if index > 0 && ui.small_button("⬆").on_hover_text("Chercher avant le groupe précédent").clicked() {
+                                        action = Some((index, -1));
+                                    }
+                                });
+                            }
+                        });
+                        match action {
+                            Some((index, 0)) => self.select(index),
+                            Some((index, _)) => {
+                                draft.groups.swap(index, index - 1);
+                                self.select(index - 1);
+                            }
+                            None => {}
+                        }
+                    });
+
+                    ui.separator();
+
+                    // Groupe sélectionné
+                    ui.vertical(|ui| {
+                        let Some(group) = self.draft.as_mut().and_then(|d| d.groups.get_mut(self.selected)) else {
+                            ui.label(RichText::new("Aucun groupe").color(Color32::GRAY));
+                            return;
+                        };
+                        egui::Grid::new("group_editor_grid").num_columns(2).show(ui, |ui| {
+                            ui.label("Nom :");
+                            ui.text_edit_singleline(&mut group.name);
+                            ui.end_row();
+
+                            ui.label("Motifs :");
+                            if ui
+                                .add(egui::TextEdit::multiline(&mut self.patterns_text)
+                                    .hint_text("*.txt\n*.html")
+                                    .desired_rows(6)
+                                    .desired_width(320.0)
+                                    .font(egui::TextStyle::Monospace))
+                                .changed()
+                            {
+                                group.patterns = self.patterns_text.split_whitespace().map(str::to_string).collect();
+                            }
+                            ui.end_row();
+
+                            ui.label("Méthode :");
+                            ui.add(egui::TextEdit::singleline(&mut group.method)
+                                .hint_text("vide = méthode du preset")
+                                .desired_width(320.0)
+                                .font(egui::TextStyle::Monospace));
+                            ui.end_row();
+                        });
+
+                        if !group.method.trim().is_empty() {
+                            ui.separator();
+                            method::show_analysis(ui, &[format!("-m={}", group.method.trim())]);
+                        }
+
+                        if ui.button("🗑 Supprimer le groupe").clicked() {
+                            if let Some(draft) = self.draft.as_mut() {
+                                draft.groups.remove(self.selected);
+                            }
+                            self.select(self.selected.saturating_sub(1));
+                        }
+                    });
+                });
+
+                // Options transmises à FreeArc avec le preset courant
+                if let (Some(flags), Some(draft)) = (preset_flags, &self.draft) {
+                    ui.separator();
+                    ui.label("Options obtenues avec le preset choisi :");
+                    match draft.compression_flags(flags) {
+                        Ok(flags) => {
+                            ui.label(RichText::new(flags.join(" ")).monospace().color(Color32::GRAY));
+                        }
+                        Err(e) => {
+                            ui.colored_label(Color32::from_rgb(234, 67, 53), format!("❌ {}", e));
+                        }
+                    }
+                }
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(Color32::from_rgb(234, 67, 53), error);
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("💾 Enregistrer").clicked() {
+                        if let Some(draft) = &self.draft {
+                            match draft.validate() {
+                                Ok(()) => {
+                                    *groups = draft.clone();
+                                    self.error = None;
+                                    changed = true;
+                                }
+                                Err(e) => self.error = Some(e),
+                            }
+                        }
+                    }
+                    let arc_groups = arc_groups_path();
+                    if ui
+                        .add_enabled(arc_groups.is_file(), egui::Button::new("📥 Reprendre arc.groups"))
+                        .on_hover_text(arc_groups.display().to_string())
+                        .clicked()
+                    {
+                        match FileGroups::load_arc_groups(&arc_groups) {
+                            Ok(loaded) => self.reset(loaded),
+                            Err(e) => self.error = Some(e),
+                        }
+                    }
+                    if ui.button("↺ Groupes par défaut").clicked() {
+                        self.reset(FileGroups::default());
+                    }
+                    if ui.button("Annuler les modifications").clicked() {
+                        self.reset(groups.clone());
+                    }
+                });
+            });
+        changed
+    }
+}
@@ -0,0 +1,249 @@
+// Contenu d'une archive : lecture de la sortie des commandes de listage
+// (`arc v`, `7z l -slt`) et construction de l'arborescence.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::backend::BackendKind;
+
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveEntry {
+    pub path: String, // Toujours avec des '/'
+    pub size: u64,
+    pub packed: Option<u64>,
+    pub modified: String, // "AAAA-MM-JJ HH:MM:SS", triable tel quel
+    pub crc: Option<String>,
+    pub method: Option<String>,
+    pub is_dir: bool,
+    pub encrypted: bool,
+    /// Lien symbolique : sa cible, vide si l'outil ne l'indique pas
+    pub symlink: Option<String>,
+}
+
+/// Contenu et propriétés d'une archive
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveContents {
+    pub entries: Vec<ArchiveEntry>,
+    pub recovery_record: Option<bool>, // None : l'outil ne l'indique pas
+}
+
+/// Lance la commande de listage de l'outil associé à l'archive et analyse sa sortie
+pub fn list_archive(archive: &Path) -> Result<Vec<ArchiveEntry>, String> {
+    read_archive(archive).map(|contents| contents.entries)
+}
+
+pub fn read_archive(archive: &Path) -> Result<ArchiveContents, String> {
+    let backend = BackendKind::for_archive(archive).backend();
+    if !backend.is_available() {
+        return Err(format!("{} n'est pas installé correctement", backend.name()));
+    }
+    let mut cmd = backend
+        .list_command(archive)
+        .ok_or_else(|| format!("{} ne sait pas lister le contenu d'une archive", backend.name()))?;
+
+    let output = cmd.output().map_err(|e| format!("Erreur lors de l'exécution de la commande : {}", e))?;
+    if !output.status.success() {
+        // Certains outils écrivent leurs erreurs sur stdout
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = if stderr.trim().is_empty() { String::from_utf8_lossy(&output.stdout) } else { stderr };
+        return Err(format!("Impossible de lister l'archive : {}", message.trim()));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(ArchiveContents { entries: backend.parse_listing(&stdout), recovery_record: backend.parse_recovery_record(&stdout) })
+}
+
+/// Archive protégée par mot de passe : entrée chiffrée, ou listage refusé faute de
+/// mot de passe (en-têtes chiffrés). Sans listage possible, on ne peut pas savoir.
+pub fn is_encrypted(archive: &Path) -> bool {
+    match list_archive(archive) {
+        Ok(entries) => entries.iter().any(|e| e.encrypted),
+        Err(e) => crate::encryption::is_password_error(&e),
+    }
+}
+
+/// Entrées passées aux outils par fichier (@liste) : la ligne de commande serait trop longue
+pub fn write_list_file(entries: &[String], tag: u64) -> Result<PathBuf, String> {
+    let list_file = std::env::temp_dir().join(format!("stelarc_selection_{}.txt", tag));
+    let separator = std::path::MAIN_SEPARATOR.to_string();
+    let content: Vec<String> = entries.iter().map(|e| e.replace('/', &separator)).collect();
+    fs::write(&list_file, content.join("\n")).map_err(|e| format!("Impossible d'écrire la liste des fichiers : {}", e))?;
+    Ok(list_file)
+}
+
+fn parse_number(token: &str) -> Option<u64> {
+    token.replace(',', "").parse().ok()
+}
+
+fn is_crc(token: &str) -> bool {
+    token.len() == 8 && token.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Découpe les `n` premiers champs séparés par des espaces et renvoie le reste de la ligne
+/// (les noms de fichiers peuvent contenir des espaces)
+fn split_fields(line: &str, n: usize) -> Option<(Vec<&str>, &str)> {
+    let mut fields = Vec::with_capacity(n);
+    let mut rest = line.trim_start();
+    for _ in 0..n {
+        let end = rest.find(char::is_whitespace)?;
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    Some((fields, rest))
+}
+
+/// Sortie de `arc v` :
+/// `2009-11-25 18:45:00 .A.....     1,052     501 61F85E3B dossier\fichier.txt`
+pub fn parse_freearc_listing(output: &str) -> Vec<ArchiveEntry> {
+    let mut entries = Vec::new();
+    for line in output.lines() {
+        let trimmed = line.trim_start();
+        // Les lignes d'entrées commencent par une date AAAA-MM-JJ
+        let bytes = trimmed.as_bytes();
+        if bytes.len() < 11 || bytes[4] != b'-' || bytes[7] != b'-' || !bytes[..4].iter().all(u8::is_ascii_digit) {
+            continue;
+        }
+
+        let Some((head, after_time)) = split_fields(trimmed, 2) else { continue };
+        let modified = format!("{} {}", head[0], head[1]);
+
+        // Attributs optionnels, puis taille, taille compressée et CRC
+        let mut rest = after_time;
+        let mut attributes = "";
+        if let Some((f, r)) = split_fields(rest, 1) {
+            if parse_number(f[0]).is_none() {
+                attributes = f[0];
+                rest = r;
+            }
+        }
+        let Some((f, r)) = split_fields(rest, 1) else { continue };
+        let Some(size) = parse_number(f[0]) else { continue };
+        rest = r;
+
+        let mut packed = None;
+        if let Some((f, r)) = split_fields(rest, 1) {
+            if let Some(p) = parse_number(f[0]) {
+                packed = Some(p);
+                rest = r;
+            }
+        }
+        let mut crc = None;
+        if let Some((f, r)) = split_fields(rest, 1) {
+            if is_crc(f[0]) {
+                crc = Some(f[0].to_uppercase());
+                rest = r;
+            }
+        }
+
+        let path = rest.trim().replace('\\', "/");
+        if path.is_empty() {
+            continue;
+        }
+        entries.push(ArchiveEntry {
+            is_dir: attributes.contains('D') || path.ends_with('/'),
+            path: path.trim_end_matches('/').to_string(),
+            size,
+            packed,
+            modified,
+            crc,
+            method: None,
+            encrypted: false,
+            symlink: None,
+        });
+    }
+    entries
+}
+
+/// Sortie de `7z l -slt` : blocs "Clé = Valeur" séparés par des lignes vides,
+/// après la ligne "----------" qui termine la description de l'archive
+pub fn parse_7z_listing(output: &str) -> Vec<ArchiveEntry> {
+    let mut entries = Vec::new();
+    let mut in_entries = false;
+    let mut current: Option<ArchiveEntry> = None;
+
+    for line in output.lines().chain(std::iter::once("")) {
+        if !in_entries {
+            in_entries = line.starts_with("----------");
+            continue;
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            if let Some(entry) = current.take() {
+                if !entry.path.is_empty() {
+                    entries.push(entry);
+                }
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once(" = ").or_else(|| line.strip_suffix(" =").map(|k| (k, ""))) else {
+            continue;
+        };
+        let entry = current.get_or_insert_with(ArchiveEntry::default);
+        match key {
+            "Path" => entry.path = value.replace('\\', "/"),
+            "Size" => entry.size = parse_number(value).unwrap_or(0),
+            "Packed Size" => entry.packed = parse_number(value),
+            "Modified" => entry.modified = value.split('.').next().unwrap_or(value).to_string(),
+            "CRC" if !value.is_empty() => entry.crc = Some(value.to_uppercase()),
+            "Method" if !value.is_empty() => entry.method = Some(value.to_string()),
+            "Folder" => entry.is_dir |= value == "+",
+            "Attributes" => {
+                entry.is_dir |= value.starts_with('D');
+                // Mode Unix après les attributs Windows : "A_ lrwxrwxrwx"
+                if value.split_whitespace().skip(1).any(|mode| mode.starts_with('l')) && entry.symlink.is_none() {
+                    entry.symlink = Some(String::new());
+                }
+            }
+            "Symbolic Link" if !value.is_empty() => entry.symlink = Some(value.replace('\\', "/")),
+            "Encrypted" => entry.encrypted = value == "+",
+            _ => {}
+        }
+    }
+    entries
+}
+
+/// Noeud de l'arborescence : dossier (avec enfants) ou fichier (avec `entry`)
+#[derive(Debug, Default)]
+pub struct TreeNode {
+    pub name: String,
+    pub path: String,
+    pub entry: Option<usize>, // Index dans la liste des entrées
+    pub children: BTreeMap<String, TreeNode>,
+    pub size: u64,
+    pub packed: u64,
+}
+
+impl TreeNode {
+    pub fn is_dir(&self) -> bool {
+        !self.children.is_empty() || self.entry.is_none()
+    }
+}
+
+/// Construit l'arborescence ; les dossiers implicites sont créés au besoin
+/// et les tailles des dossiers sont cumulées
+pub fn build_tree(entries: &[ArchiveEntry]) -> TreeNode {
+    let mut root = TreeNode::default();
+    for (index, entry) in entries.iter().enumerate() {
+        let mut node = &mut root;
+        let mut path = String::new();
+        for part in entry.path.split('/').filter(|p| !p.is_empty() && *p != ".") {
+            if !path.is_empty() {
+                path.push('/');
+            }
+            path.push_str(part);
+            node.size += if entry.is_dir { 0 } else { entry.size };
+            node.packed += entry.packed.unwrap_or(0);
+            node = node.children.entry(part.to_string()).or_insert_with(|| TreeNode {
+                name: part.to_string(),
+                path: path.clone(),
+                ..Default::default()
+            });
+        }
+        if !entry.is_dir {
+            node.entry = Some(index);
+            node.size = entry.size;
+            node.packed = entry.packed.unwrap_or(0);
+        }
+    }
+    root
+}
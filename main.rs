@@ -5,13 +5,60 @@ use std::process::Command;
 use std::path::{Path, PathBuf};
 use std::fs;
 use rfd;
-use std::io::BufRead;
 use std::thread;
 use std::collections::HashMap;
 use std::sync::mpsc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use rodio;
 use rodio::Source;
 use sysinfo::System; // <-- Correction de l'import
+use serde::{Deserialize, Serialize};
+
+mod backend;
+mod benchmark;
+mod browser;
+mod convert;
+mod diskspace;
+mod destination;
+mod encryption;
+mod filters;
+mod groups;
+mod listing;
+mod method;
+mod overwrite;
+mod presets;
+mod process_tree;
+mod queue;
+mod recommend;
+mod recovery;
+mod safety;
+mod sfx;
+mod verify;
+mod volumes;
+use backend::BackendKind;
+use benchmark::BenchmarkWindow;
+use browser::{ArchiveBrowser, BrowserAction};
+use convert::Conversion;
+use destination::ExtractMode;
+use diskspace::SpaceNeed;
+use encryption::{EncryptionAlgorithm, EncryptionSettings};
+use filters::{FilteredFiles, SourceFilter};
+use groups::{FileGroups, GroupEditor, GROUPS_FILE};
+use overwrite::{Conflict, ExtractPlan, OverwritePolicy, Resolution};
+use presets::{PresetEditor, UserPreset, USER_PRESETS_FILE};
+use queue::{Job, JobKind, JobQueue, JobStatus, QUEUE_FILE, UpdateOp};
+use recommend::Recommendation;
+use recovery::{RecoverySize, RepairReport};
+use safety::SafetyReport;
+use sfx::{SfxBuild, SfxOptions, SfxOverwrite};
+use verify::{TestLine, TestOutcome, TestReport};
+use volumes::Reassembly;
+
+/// Message envoyé par le thread de commande quand l'utilisateur a annulé l'opération
+const CANCELLED: &str = "cancelled";
+/// Message envoyé quand l'extraction s'arrête pour demander quoi faire des fichiers déjà présents
+const CONFLICTS_PENDING: &str = "conflicts";
 
 
 // Import unique du trait Digest via sha3
@@ -32,11 +79,56 @@ struct CompressionProgress {
     estimated_remaining: Option<std::time::Duration>,
 }
 
+impl CompressionProgress {
+    fn new(total_bytes: u64) -> Self {
+        Self {
+            total_bytes,
+            processed_bytes: 0,
+            started_at: std::time::Instant::now(),
+            estimated_remaining: None,
+        }
+    }
+
+    fn update(&mut self, fraction: f32) {
+        if self.total_bytes > 0 {
+            self.processed_bytes = (self.total_bytes as f64 * fraction as f64) as u64;
+        }
+        // Pas d'estimation tant que la progression est trop faible pour être fiable
+        self.estimated_remaining = if fraction > 0.005 {
+            let elapsed = self.started_at.elapsed();
+            Some(elapsed.mul_f32((1.0 - fraction) / fraction))
+        } else {
+            None
+        };
+    }
+}
+
+fn format_duration(d: std::time::Duration) -> String {
+    let secs = d.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["o", "Ko", "Mo", "Go", "To"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.2} {}", value, UNITS[unit])
+    }
+}
+
 struct FileStats {
     total_size: u64,
     file_count: usize,
     largest_file: (PathBuf, u64),
     by_extension: HashMap<String, (usize, u64)>,  // (count, total_size)
+    duplicate_size: u64, // Octets en double probables (fichiers volumineux de même taille)
 }
 
 #[derive(Clone, Debug)]  // Remove serde derives since Color32 doesn't implement them
@@ -125,29 +217,135 @@ impl HashType {
 enum CommandUpdate {
     LogOutput(String),      // Une ligne de log (stdout ou stderr)
     Progress(f32),          // Progression en pourcentage (0.0 à 1.0)
+    TotalBytes(u64),        // Taille totale annoncée par l'outil
+    Started(u32),           // Pid du processus lancé
+    TestListing(Option<Vec<String>>), // Fichiers de l'archive testée
+    TestResult(TestLine),   // Fichier testé (OK ou en erreur)
+    PasswordRequired,       // Archive chiffrée lancée sans mot de passe
+    Conflicts(PathBuf, Vec<Conflict>), // Fichiers déjà présents dans la destination (politique "demander")
     ProcessCompleted(Result<String, String>), // Résultat: Ok(message_succès) ou Err(message_erreur)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Compress,
+    Extract,
+    Test,
+}
+
+/// Tâche de la file en cours d'exécution
+struct RunningJob {
+    job_id: u64,
+    label: String,
+    rx: mpsc::Receiver<CommandUpdate>, // Pour recevoir les logs/progressions
+    pid: Option<u32>,
+    cancel_flag: Arc<AtomicBool>, // Levé par le bouton Annuler
+    is_paused: bool,
+    progress_value: f32, // 0.0 à 1.0 pour la barre de progression
+    progress: CompressionProgress, // Octets traités et temps restant estimé
+    test_archive: Option<PathBuf>, // Archive testée (tâches de test uniquement)
+    test_listing: Option<Vec<String>>,
+    test_lines: Vec<TestLine>,
+    repair_archive: Option<(PathBuf, std::time::SystemTime)>, // Archive réparée et début de la réparation
+    repair_lines: Vec<String>,
+    password_error: bool, // L'outil a signalé un mot de passe manquant ou incorrect
+    conflicts: Option<(PathBuf, Vec<Conflict>)>, // Décisions à demander avant de relancer l'extraction
+}
+
+/// Fichiers à traiter autour d'une commande
+#[derive(Default)]
+struct CommandFiles {
+    partial_output: Option<PathBuf>, // Créé par l'opération, supprimé en cas d'annulation
+    split: Option<(PathBuf, u64)>,   // Archive à découper en volumes après succès
+    reassembly: Option<Reassembly>,  // Volumes à réassembler avant la commande (supprimé ensuite)
+    sfx: Option<SfxBuild>,           // Exécutable auto-extractible à terminer après succès
+    password_probe: Option<PathBuf>, // Archive à examiner : chiffrée et sans mot de passe, la commande n'est pas lancée
+}
+
+/// Demande du mot de passe d'une archive chiffrée, détectée au lancement de la tâche
+struct PasswordPrompt {
+    archive: PathBuf,
+    retry_of: u64, // Tâche arrêtée à relancer avec le mot de passe
+    password: String,
+}
+
+/// Extraction en attente des décisions sur les fichiers déjà présents dans la destination
+struct ConflictDialog {
+    job_id: u64, // Extraction arrêtée, relancée avec les décisions
+    dest: PathBuf,
+    conflicts: Vec<Conflict>,
+}
+
+/// Compression dont le besoin estimé en mémoire dépasse ce que la machine peut fournir
+struct RamWarning {
+    compression: u64,   // Octets estimés
+    decompression: u64,
+    available: u64,
+    total: u64,
+    downgraded: Option<Vec<String>>, // Options allégées qui tiennent dans la mémoire disponible
+    threads: usize,
+    encryption: Option<EncryptionSettings>,
+    password: Option<String>,
+}
+
 struct MonCompresseurApp {
     current_dir: PathBuf,
     history: Vec<PathBuf>,
     history_index: usize,
     selected: Vec<PathBuf>,
-    mode_compress: bool,
+    mode: Mode,
     preset: CompressionPreset,
     output_path: PathBuf,
     log_lines: Vec<String>,       // Pour les logs en temps réel
     stats: Option<CompressionStats>,
     preview_file: Option<PathBuf>,
     preview_content: String,
-    progress_value: f32, // 0.0 à 1.0 pour la barre de progression
     operation_status: String, // Ex: "Compression en cours...", "Terminé", "Erreur"
     is_processing: bool,  // True si une commande est en cours
     current_stats: Option<FileStats>,
+    stats_selection: Vec<PathBuf>, // Sélection analysée par `current_stats`
+    stats_rx: Option<mpsc::Receiver<(FileStats, Option<Recommendation>)>>,
+    recommendation: Option<Recommendation>,
     notification: Option<Notification>,
     current_theme: Theme,
-    command_rx: Option<mpsc::Receiver<CommandUpdate>>, // Pour recevoir les logs/progressions
-    command_tx: Option<mpsc::Sender<CommandUpdate>>,   // Pour envoyer depuis le thread (gardé temporairement)
+    queue: JobQueue,           // File d'attente persistante
+    running: Vec<RunningJob>,  // Tâches en cours d'exécution
+    show_queue_window: bool,
+    browser: Option<ArchiveBrowser>, // Contenu de l'archive consultée
+    test_report: Option<TestReport>, // Dernier rapport de test affiché
+    encryption_enabled: bool,
+    encryption: EncryptionSettings,
+    password: String,
+    password_confirm: String,
+    password_prompt: Option<PasswordPrompt>,
+    conflict_dialog: Option<ConflictDialog>,
+    overwrite_policy: OverwritePolicy, // Fichiers déjà présents dans le dossier d'extraction
+    extract_mode: ExtractMode,         // Dossier d'extraction : sélecteur ou en un clic
+    ram_warning: Option<RamWarning>,
+    limit_threads: bool, // Impose `threads` aux méthodes du preset
+    threads: usize,
+    affinity_mask: u64, // Coeurs autorisés, 0 = tous (Linux)
+    volumes_enabled: bool,
+    volume_size: u64, // Taille des volumes en octets
+    sfx_enabled: bool,
+    sfx: SfxOptions,
+    recovery_enabled: bool,
+    recovery_text: String, // Saisie : "3%", "4m"...
+    filters_enabled: bool,
+    filter: SourceFilter,
+    filter_preview: Option<FilteredFiles>, // Résultat des filtres sur la sélection
+    filter_preview_rx: Option<mpsc::Receiver<FilteredFiles>>,
+    filter_preview_key: Option<(Vec<PathBuf>, SourceFilter)>, // Sélection et filtres de l'aperçu
+    groups_enabled: bool, // Groupes de fichiers appliqués aux compressions FreeArc
+    file_groups: FileGroups,
+    group_editor: GroupEditor,
+    show_group_editor: bool,
+    user_presets: Vec<UserPreset>, // Presets définis par l'utilisateur
+    preset_editor: PresetEditor,
+    show_preset_editor: bool,
+    show_method_window: bool, // Analyse de la méthode du preset courant
+    benchmark: BenchmarkWindow,
+    show_benchmark: bool,
     show_hash_window: bool,
     selected_hash_type: HashType,
     hash_result: Option<String>,
@@ -157,7 +355,6 @@ struct MonCompresseurApp {
     cpu_usage: f32,        // 0.0 à 1.0
     ram_usage_mb: u64,
     ram_total_mb: u64,
-    arc_pid: Option<u32>,
 
     // Nouveau : Compteur de temps
     compression_start_time: Option<std::time::Instant>,
@@ -168,8 +365,8 @@ struct MonCompresseurApp {
     log_scroll_to_end: bool,  // Add this new field
 }
 
-/// Presets disponibles pour FreeArc, incluant des modes variés
-#[derive(Clone, PartialEq)]
+/// Presets disponibles pour FreeArc, 7-Zip et Sharky, incluant des modes variés
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 enum CompressionPreset {
     Instant,
     HDDspeed,
@@ -194,12 +391,25 @@ enum CompressionPreset {
     Xtoolj,
     Xtoolk,
     Experimental, // Pour les presets expérimentaux
-    Experimental3
+    Experimental3,
+
+    // 7-Zip
+    SevenZipFast,
+    SevenZipNormal,
+    SevenZipUltra,
+
+    // Sharky
+    SharkyFast,
+    SharkyBalanced,
+    SharkyInsane,
+
+    // Preset utilisateur (stelarc_presets.json), copié tel quel dans la file d'attente
+    User(UserPreset),
 }
 
 impl CompressionPreset {
     fn all() -> &'static [CompressionPreset] {
-        static ALL: [CompressionPreset; 23] = [
+        static ALL: [CompressionPreset; 29] = [
             CompressionPreset::Instant,
             CompressionPreset::HDDspeed,
             CompressionPreset::UltrafastSREP,
@@ -223,11 +433,52 @@ impl CompressionPreset {
             CompressionPreset::Xtoolj,
             CompressionPreset::Xtoolk,
             CompressionPreset::Experimental,
-            CompressionPreset::Experimental3,];
+            CompressionPreset::Experimental3,
+            CompressionPreset::SevenZipFast,
+            CompressionPreset::SevenZipNormal,
+            CompressionPreset::SevenZipUltra,
+            CompressionPreset::SharkyFast,
+            CompressionPreset::SharkyBalanced,
+            CompressionPreset::SharkyInsane,];
         &ALL
     }
 
-    fn label(&self) -> &'static str {
+    /// Recherche d'un preset par son nom de variante (ex: "XtoolF") ou par le nom
+    /// d'un preset utilisateur, utilisé par la ligne de commande
+    fn from_name(name: &str, user_presets: &[UserPreset]) -> Option<CompressionPreset> {
+        Self::all().iter().find(|p| format!("{:?}", p).eq_ignore_ascii_case(name)).cloned()
+            .or_else(|| user_presets.iter()
+                .find(|p| p.name.eq_ignore_ascii_case(name))
+                .map(|p| CompressionPreset::User(p.clone())))
+    }
+
+    /// Noms réservés par les presets intégrés
+    fn builtin_names() -> Vec<String> {
+        Self::all().iter().map(|p| format!("{:?}", p)).collect()
+    }
+
+    /// Extension par défaut de l'archive produite
+    fn extension(&self) -> &str {
+        match self {
+            CompressionPreset::User(preset) => &preset.extension,
+            _ => self.backend().backend().extensions()[0],
+        }
+    }
+
+    fn backend(&self) -> BackendKind {
+        match self {
+            CompressionPreset::SevenZipFast
+            | CompressionPreset::SevenZipNormal
+            | CompressionPreset::SevenZipUltra => BackendKind::SevenZip,
+            CompressionPreset::SharkyFast
+            | CompressionPreset::SharkyBalanced
+            | CompressionPreset::SharkyInsane => BackendKind::Sharky,
+            CompressionPreset::User(preset) => preset.backend,
+            _ => BackendKind::FreeArc,
+        }
+    }
+
+    fn label(&self) -> &str {
         match self {
             CompressionPreset::Instant => "Instant (-m1)",
             CompressionPreset::HDDspeed => "HDD speed   (-m2)",
@@ -252,12 +503,18 @@ impl CompressionPreset {
             CompressionPreset::Experimental => "berserk+srep (berserk+srep)",
             CompressionPreset::XtoolLOL => "Xtool[MGDeflate]+kraken6+lzma",
             CompressionPreset::Xtool2 => "Xtool[preflate]+kraken+LOLZ_NORMAL",
-           
+            CompressionPreset::SevenZipFast => "7-Zip rapide   (-mx1)",
+            CompressionPreset::SevenZipNormal => "7-Zip normal   (-mx5)",
+            CompressionPreset::SevenZipUltra => "7-Zip ultra    (-mx9)",
+            CompressionPreset::SharkyFast => "Sharky rapide  (XZ=1, Zstd=2)",
+            CompressionPreset::SharkyBalanced => "Sharky équilibré (XZ=4, Zstd=7)",
+            CompressionPreset::SharkyInsane => "Sharky insane  (XZ=9, Zstd=22)",
+            CompressionPreset::User(preset) => &preset.name,
         }
     
         }
     
-    fn flags(&self) -> Vec<&'static str> {
+    fn flags(&self) -> Vec<&str> {
         match self {
             CompressionPreset::Instant => vec!["-m1"],
             CompressionPreset::HDDspeed => vec!["-m2"],
@@ -282,6 +539,13 @@ impl CompressionPreset {
             CompressionPreset::Xtooln => vec!["-i2", "-m=xtool:o:t90p:g90p:mzlib:mpreflate:dd3+razorx"],
             CompressionPreset::Xtoolk => vec!["-i2", "-m=xtool:o:t90p:g90p:mzlib:mpreflate:dd3+zstdx:10:T0"],
             CompressionPreset::Experimental3 => vec![ "-i1","-m=xtool:o:t100p:g100p:mzlib:mpreflate:dd3+LOLZ_FAST"],
+            CompressionPreset::SevenZipFast => vec!["-mx1"],
+            CompressionPreset::SevenZipNormal => vec!["-mx5"],
+            CompressionPreset::SevenZipUltra => vec!["-mx9"],
+            CompressionPreset::SharkyFast => vec!["-x", "1", "-z", "2"],
+            CompressionPreset::SharkyBalanced => vec!["-x", "4", "-z", "7"],
+            CompressionPreset::SharkyInsane => vec!["-x", "9", "-z", "22"],
+            CompressionPreset::User(preset) => preset.flags.iter().map(String::as_str).collect(),
         }
     }
 }    
@@ -295,34 +559,82 @@ impl Default for MonCompresseurApp {
             PathBuf::from("archive.arc")
         };
 
+        // Les tâches restaurées attendent que l'utilisateur relance la file
+        let queue = JobQueue::load(Path::new(QUEUE_FILE));
+        let restored = queue.pending_count();
+        let mut log_lines = Vec::new();
+        if restored > 0 {
+            log_lines.push(format!("{} tâche(s) restaurée(s) depuis la file d'attente\n", restored));
+        }
+
+        let sys = System::new_all();
+        let cores = sys.cpus().len().max(1);
+
         Self {
             current_dir: cwd.clone(),
             history: vec![cwd.clone()],
             history_index: 0,
             selected: Vec::new(),
-            mode_compress: true,
+            mode: Mode::Compress,
             preset: CompressionPreset::Normal,
             output_path: initial_output_path,
-            log_lines: Vec::new(),
+            log_lines,
             stats: None,
             preview_file: None,
             preview_content: String::new(),
-            progress_value: 0.0,
             operation_status: String::new(),
             is_processing: false,
             current_stats: None,
+            stats_selection: Vec::new(),
+            stats_rx: None,
+            recommendation: None,
             notification: None,
             current_theme: Theme::default_themes()[0].clone(),
-            command_rx: None,
-            command_tx: None,
+            queue,
+            running: Vec::new(),
+            show_queue_window: restored > 0,
+            browser: None,
+            test_report: None,
+            encryption_enabled: false,
+            encryption: EncryptionSettings::default(),
+            password: String::new(),
+            password_confirm: String::new(),
+            password_prompt: None,
+            conflict_dialog: None,
+            overwrite_policy: OverwritePolicy::default(),
+            extract_mode: ExtractMode::default(),
+            ram_warning: None,
+            limit_threads: false,
+            threads: cores,
+            affinity_mask: 0,
+            volumes_enabled: false,
+            sfx_enabled: false,
+            sfx: SfxOptions::default(),
+            recovery_enabled: false,
+            recovery_text: RecoverySize::default().label(),
+            filters_enabled: false,
+            filter: SourceFilter::default(),
+            filter_preview: None,
+            filter_preview_rx: None,
+            filter_preview_key: None,
+            groups_enabled: Path::new(GROUPS_FILE).exists(), // Groupes enregistrés : appliqués d'emblée
+            file_groups: FileGroups::load(Path::new(GROUPS_FILE)),
+            group_editor: GroupEditor::default(),
+            show_group_editor: false,
+            volume_size: volumes::VOLUME_PRESETS[1].2,
+            user_presets: presets::load_user_presets(Path::new(USER_PRESETS_FILE)),
+            preset_editor: PresetEditor::default(),
+            show_preset_editor: false,
+            show_method_window: false,
+            benchmark: BenchmarkWindow::default(),
+            show_benchmark: false,
             show_hash_window: false,
             selected_hash_type: HashType::CRC32,
             hash_result: None,
-            sys: System::new_all(),
+            sys,
             cpu_usage: 0.0,
             ram_usage_mb: 0,
             ram_total_mb: 0,
-            arc_pid: None,
             compression_start_time: None,
             elapsed_time: String::from("00:00:00"),
             last_elapsed_time: None,
@@ -361,7 +673,7 @@ impl MonCompresseurApp {
             self.current_dir = dir.to_path_buf();
             self.selected.clear();
             // Sélectionner automatiquement le dossier pour la compression
-            if self.mode_compress {
+            if self.mode == Mode::Compress {
                 self.selected.push(dir.to_path_buf());
             }
             self.log_lines.push(format!("Navigated to: {}\n", dir.display()));
@@ -415,7 +727,7 @@ impl MonCompresseurApp {
 
                 if response.double_clicked() {
                     self.navigate_to(&p);
-                    if self.mode_compress {
+                    if self.mode == Mode::Compress {
                         self.selected.clear();
                         self.selected.push(p);
                     }
@@ -457,6 +769,10 @@ impl MonCompresseurApp {
                         if response.double_clicked() {
                             if p.is_dir() {
                                 self.navigate_to(&p);
+                            } else if convert::is_archive(&p) || volumes::is_volume(&p) {
+                                // Archive connue ou volume (les suivants renvoient au premier) ;
+                                // un fichier ordinaire ne fait rien
+                                self.open_browser(p.clone(), ui.ctx());
                             }
                         } else if response.clicked() {
                             if ui.ctx().input(|i| i.modifiers.ctrl) {
@@ -484,6 +800,21 @@ impl MonCompresseurApp {
         }
     }
 
+    /// Ouvre la fenêtre de contenu si l'outil associé sait lister l'archive
+    fn open_browser(&mut self, archive: PathBuf, ctx: &egui::Context) {
+        if volumes::is_volume(&archive) && !volumes::is_first_volume(&archive) {
+            self.log_lines.push(format!("{}\n", volumes::not_first_volume(&archive)));
+            return;
+        }
+        let backend = BackendKind::for_archive(&archive).backend();
+        if !backend.capabilities().list {
+            self.log_lines.push(format!("{} ne permet pas de lister le contenu de {}\n", backend.name(), archive.display()));
+            return;
+        }
+        self.log_lines.push(format!("Lecture du contenu de : {}\n", archive.display()));
+        self.browser = Some(ArchiveBrowser::open(archive, ctx));
+    }
+
     fn play_notification_sound() {
         static NOTIFICATION_BYTES: &[u8] = include_bytes!("notification_sound.wav");
         if let Ok((_stream, stream_handle)) = rodio::OutputStream::try_default() {
@@ -495,263 +826,1446 @@ impl MonCompresseurApp {
         }
     }
 
-    fn execute_command(&mut self, mut cmd: Command, action: &str, ctx: &egui::Context) {
-        println!("Commande exécutée : {:?}", cmd);
-        self.log_lines.push(format!("Exécution de la commande : {:?}\n", cmd));
-
-        let action = action.to_string();
-        let ctx_clone = ctx.clone();
-        let (tx, rx) = mpsc::channel();
-        self.command_rx = Some(rx);
-        self.command_tx = Some(tx.clone());
+    /// Relaie la sortie d'un outil vers l'interface : les lignes complètes vont dans le journal,
+    /// les mises à jour de progression (réécrites avec '\r') alimentent la barre de progression.
+    fn forward_output<R: std::io::Read + Send + 'static>(
+        reader: R,
+        tag: &'static str,
+        backend_kind: BackendKind,
+        tx: mpsc::Sender<CommandUpdate>,
+        ctx: egui::Context,
+    ) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            let backend = backend_kind.backend();
+            backend::read_output(reader, |line, complete| {
+                let info = backend.parse_progress(line);
+                if let Some(total) = info.total_bytes {
+                    tx.send(CommandUpdate::TotalBytes(total)).ok();
+                }
+                if let Some(fraction) = info.fraction {
+                    tx.send(CommandUpdate::Progress(fraction)).ok();
+                }
+                if complete {
+                    if let Some(result) = backend.parse_test_line(line) {
+                        tx.send(CommandUpdate::TestResult(result)).ok();
+                    }
+                    tx.send(CommandUpdate::LogOutput(format!("[{}] {}", tag, line))).ok();
+                }
+                ctx.request_repaint();
+            });
+        })
+    }
 
+    /// Lance la commande dans un thread et relaie logs, progression et résultat sur `tx`
+    fn spawn_command(
+        cmd: Command,
+        action: String,
+        backend_kind: BackendKind,
+        files: CommandFiles,
+        cancel_flag: Arc<AtomicBool>,
+        tx: mpsc::Sender<CommandUpdate>,
+        ctx: egui::Context,
+    ) {
         thread::spawn(move || {
-            cmd.stdout(std::process::Stdio::piped())
-               .stderr(std::process::Stdio::piped());
-
-            match cmd.spawn() {
-                Ok(mut child) => {
-                    let stdout = child.stdout.take().unwrap();
-                    let stderr = child.stderr.take().unwrap();
-
-                    let stdout_reader = std::io::BufReader::new(stdout);
-                    let stderr_reader = std::io::BufReader::new(stderr);
-
-                    // Ajout: lire stdout et stderr en parallèle pour logs temps réel
-                    let tx_clone = tx.clone();
-                    let ctx_clone2 = ctx_clone.clone();
-                    let stdout_handle = std::thread::spawn(move || {
-                        for line in stdout_reader.lines() {
-                            if let Ok(line) = line {
-                                tx_clone.send(CommandUpdate::LogOutput(format!("[stdout] {}", line))).ok();
-                                ctx_clone2.request_repaint();
-                            }
-                        }
-                    });
+            Self::run_command(cmd, action, backend_kind, files, cancel_flag, tx, ctx);
+        });
+    }
 
-                    let tx_clone = tx.clone();
-                    let ctx_clone3 = ctx_clone.clone();
-                    let stderr_handle = std::thread::spawn(move || {
-                        for line in stderr_reader.lines() {
-                            if let Ok(line) = line {
-                                tx_clone.send(CommandUpdate::LogOutput(format!("[stderr] {}", line))).ok();
-                                ctx_clone3.request_repaint();
-                            }
-                        }
-                    });
+    /// Test d'archive : liste d'abord son contenu pour que le rapport couvre
+    /// aussi les fichiers que l'outil ne cite pas, puis lance le test
+    fn spawn_test(
+        cmd: Command,
+        archive: PathBuf,
+        backend_kind: BackendKind,
+        mut files: CommandFiles,
+        cancel_flag: Arc<AtomicBool>,
+        tx: mpsc::Sender<CommandUpdate>,
+        ctx: egui::Context,
+    ) {
+        thread::spawn(move || {
+            // Les volumes doivent être réassemblés avant de lister l'archive
+            if let Some(reassembly) = &mut files.reassembly {
+                if let Err(e) = Self::reassemble(reassembly, &cancel_flag, &tx) {
+                    tx.send(CommandUpdate::ProcessCompleted(Err(e))).ok();
+                    ctx.request_repaint();
+                    return;
+                }
+            }
+            tx.send(CommandUpdate::TestListing(verify::listed_files(&archive))).ok();
+            Self::run_command(cmd, "le test".to_string(), backend_kind, files, cancel_flag, tx, ctx);
+        });
+    }
 
-                    // Attendre la fin des threads de logs
-                    let _ = stdout_handle.join();
-                    let _ = stderr_handle.join();
+    /// Conversion : extraction dans le dossier de travail, recompression puis test de
+    /// l'archive produite ; la barre de progression repart de zéro à chaque étape
+    fn spawn_convert(
+        mut conversion: Conversion,
+        flags: Vec<String>,
+        password: Option<String>,
+        affinity: Option<u64>,
+        cancel_flag: Arc<AtomicBool>,
+        tx: mpsc::Sender<CommandUpdate>,
+        ctx: egui::Context,
+    ) {
+        thread::spawn(move || {
+            let with_affinity = |cmd: Command| match affinity {
+                Some(mask) => process_tree::with_affinity(cmd, mask),
+                None => cmd,
+            };
+            let mut run = || -> Result<String, String> {
+                let log = |line: String| {
+                    tx.send(CommandUpdate::LogOutput(line)).ok();
+                };
+                let needs = conversion.reassembly_needs(&flags);
+                if let Some(reassembly) = &mut conversion.reassembly {
+                    diskspace::check(&needs).into_result(log)?;
+                    Self::reassemble(reassembly, &cancel_flag, &tx)?;
+                }
+                let encryption = conversion.source_backend.backend().capabilities().encryption;
+                if password.is_none() && encryption && listing::is_encrypted(conversion.listed_archive()) {
+                    return Err(Self::password_required(&tx));
+                }
 
-                    match child.wait() {
-                        Ok(status) => {
-                            if status.success() {
-                                Self::play_notification_sound();
-                                let notification = Notification {
-                                    message: format!("{} terminée avec succès", action),
-                                    level: NotificationLevel::Success,
-                                    timestamp: std::time::Instant::now(),
-                                };
-                                tx.send(CommandUpdate::ProcessCompleted(Ok(notification.message))).ok();
-                            } else {
-                                Self::play_notification_sound();
-                                let notification = Notification {
-                                    message: format!("Erreur lors de {}", action),
-                                    level: NotificationLevel::Error,
-                                    timestamp: std::time::Instant::now(),
-                                };
-                                tx.send(CommandUpdate::ProcessCompleted(Err(notification.message))).ok();
-                            }
-                        }
-                        Err(e) => {
-                            let notification = Notification {
-                                message: format!("Erreur: {}", e),
-                                level: NotificationLevel::Error,
-                                timestamp: std::time::Instant::now(),
-                            };
-                            tx.send(CommandUpdate::ProcessCompleted(Err(notification.message))).ok();
+                let preflight = match conversion.preflight(&flags) {
+                    Err(e) if encryption::is_password_error(&e) => return Err(Self::password_required(&tx)),
+                    Err(e) => return Err(format!("Contenu de l'archive illisible ({})", e)),
+                    Ok(preflight) => preflight,
+                };
+                match preflight {
+                    Some((report, space)) => {
+                        report.log_lines().into_iter().for_each(log);
+                        log(report.summary());
+                        if !report.is_clean() {
+                            return Err("Conversion refusée : l'archive contient des entrées dangereuses".to_string());
                         }
+                        space.into_result(log)?;
                     }
+                    None => log(format!(
+                        "Attention : {} ne permet pas de vérifier le contenu de l'archive avant conversion",
+                        conversion.source_backend.backend().name()
+                    )),
                 }
-                Err(e) => {
-                    let notification = Notification {
-                        message: format!("Erreur: {}", e),
-                        level: NotificationLevel::Error,
-                        timestamp: std::time::Instant::now(),
-                    };
-                    tx.send(CommandUpdate::ProcessCompleted(Err(notification.message))).ok();
+
+                tx.send(CommandUpdate::LogOutput(format!("Étape 1/3 : extraction dans {}", conversion.work_dir.display()))).ok();
+                let cmd = with_affinity(conversion.extract_command(password.as_deref()));
+                tx.send(CommandUpdate::LogOutput(format!("Commande : {}", encryption::describe_command(&cmd, password.as_deref())))).ok();
+                Self::run_step(cmd, "l'extraction", conversion.source_backend, &cancel_flag, &tx, &ctx)?;
+
+                tx.send(CommandUpdate::LogOutput(format!("Étape 2/3 : compression vers {}", conversion.output.display()))).ok();
+                tx.send(CommandUpdate::Progress(0.0)).ok();
+                let cmd = with_affinity(conversion.compress_command(&flags)?);
+                tx.send(CommandUpdate::LogOutput(format!("Commande : {}", encryption::describe_command(&cmd, None)))).ok();
+                Self::run_step(cmd, "la compression", conversion.target_backend, &cancel_flag, &tx, &ctx)?;
+
+                match conversion.test_command() {
+                    Some(cmd) => {
+                        tx.send(CommandUpdate::LogOutput("Étape 3/3 : test de l'archive convertie".to_string())).ok();
+                        tx.send(CommandUpdate::Progress(0.0)).ok();
+                        Self::run_step(with_affinity(cmd), "le test de l'archive convertie", conversion.target_backend, &cancel_flag, &tx, &ctx)?;
+                    }
+                    None => {
+                        let name = conversion.target_backend.backend().name();
+                        tx.send(CommandUpdate::LogOutput(format!("Étape 3/3 : {} ne sait pas tester l'archive, test ignoré", name))).ok();
+                    }
                 }
+                Ok(conversion.finish())
+            };
+            let result = run();
+            if !matches!(&result, Err(e) if e == CANCELLED) {
+                Self::play_notification_sound();
             }
+            drop(conversion); // Dossier de travail supprimé avant d'annoncer la fin
+            tx.send(CommandUpdate::ProcessCompleted(result)).ok();
+            ctx.request_repaint();
         });
     }
 
-    fn handle_action(&mut self, ctx: &egui::Context) {
-        self.log_lines.clear();
-        let exe = if cfg!(windows) { ".\\FreeArc\\arc.exe" } else { "./FreeArc/bin/arc" };
+    /// Extraction vérifiée : liste l'archive et écarte les entrées dangereuses, compare à la
+    /// destination si l'outil n'applique pas la politique lui-même, puis extrait sur place ce
+    /// qui doit l'être et passe par un dossier de transit pour les fichiers à renommer
+    fn spawn_extract_plan(
+        job: Job,
+        source: PathBuf,
+        mut files: CommandFiles,
+        cancel_flag: Arc<AtomicBool>,
+        tx: mpsc::Sender<CommandUpdate>,
+        ctx: egui::Context,
+    ) {
+        thread::spawn(move || {
+            let backend_kind = BackendKind::for_archive(&job.sources[0]);
+            let backend = backend_kind.backend();
+            let prepare = |mut cmd: Command| {
+                if let Some(password) = &job.password {
+                    cmd.args(backend.password_args(password));
+                }
+                match job.affinity {
+                    Some(mask) => process_tree::with_affinity(cmd, mask),
+                    None => cmd,
+                }
+            };
+            let mut run = || -> Result<String, String> {
+                if let Some(reassembly) = &mut files.reassembly {
+                    Self::reassemble(reassembly, &cancel_flag, &tx)?;
+                }
+                let entries = match listing::list_archive(&source) {
+                    Err(e) if encryption::is_password_error(&e) => return Err(Self::password_required(&tx)),
+                    entries => entries?,
+                };
+                if job.password.is_none() && entries.iter().any(|e| e.encrypted) {
+                    return Err(Self::password_required(&tx));
+                }
+                let output = if job.smart_destination {
+                    let output = destination::resolve_listed(&job.sources[0], &job.output, &job.selection, ExtractMode::Smart, Some(&entries));
+                    tx.send(CommandUpdate::LogOutput(format!("{} : {}", ExtractMode::Smart.label(), output.display()))).ok();
+                    output
+                } else {
+                    job.output.clone()
+                };
+                // Politique "demander" sans décisions : la fenêtre des conflits s'ouvre et la tâche
+                // sera relancée avec les choix de l'utilisateur
+                if job.overwrite == OverwritePolicy::Ask && job.resolutions.is_empty() {
+                    // Entrées dangereuses écartées : elles ne seront pas extraites
+                    let safe = SafetyReport::check(&entries).safe_entries(&entries);
+                    let conflicts = overwrite::find_conflicts(&safe, &job.selection, &output, OverwritePolicy::Ask);
+                    if !conflicts.is_empty() {
+                        tx.send(CommandUpdate::Conflicts(output, conflicts)).ok();
+                        return Err(CONFLICTS_PENDING.to_string());
+                    }
+                }
+                let plan = ExtractPlan::prepare(backend, &entries, &job.selection, &output, job.overwrite, |conflicts| {
+                    for conflict in conflicts {
+                        match job.resolutions.iter().find(|(entry, _)| *entry == conflict.entry) {
+                            Some((_, resolution)) => conflict.resolution = *resolution,
+                            // Fichier apparu depuis la fenêtre des conflits : on conserve
+                            None if job.overwrite == OverwritePolicy::Ask => conflict.resolution = Resolution::Skip,
+                            None => {}
+                        }
+                    }
+                });
+                for line in plan.safety.log_lines() {
+                    tx.send(CommandUpdate::LogOutput(line)).ok();
+                }
+                tx.send(CommandUpdate::LogOutput(plan.summary())).ok();
+                // Taille exacte ; un fichier réassemblé est déjà écrit, l'espace libre mesuré en tient compte
+                diskspace::check(&[SpaceNeed::exact(&output, plan.unpacked, "l'extraction")]).into_result(|warning| {
+                    tx.send(CommandUpdate::LogOutput(warning)).ok();
+                })?;
 
-        if !std::path::Path::new(exe).exists() {
-            self.log_lines.push("Erreur : FreeArc n'est pas installé correctement\n".to_string());
-            return;
-        }
+                if let Some(cmd) = plan.in_place_command(backend, &source, &output, job.id)? {
+                    let cmd = prepare(cmd);
+                    tx.send(CommandUpdate::LogOutput(format!("Commande : {}", encryption::describe_command(&cmd, job.password.as_deref())))).ok();
+                    Self::run_step(cmd, "l'extraction", backend_kind, &cancel_flag, &tx, &ctx)?;
+                }
 
-        if self.mode_compress {
-            // Mode compression
-            if self.selected.is_empty() {
-                self.log_lines.push("Erreur : Aucune source sélectionnée pour la compression.\n".to_string());
-                return;
-            }
-            if self.output_path.file_name().is_none() {
-                self.log_lines.push("Erreur : Chemin de sortie invalide.\n".to_string());
-                return;
+                let staging = overwrite::staging_dir(&output, job.id);
+                if let Some(cmd) = plan.rename_command(backend, &source, &staging, job.id)? {
+                    tx.send(CommandUpdate::Progress(0.0)).ok();
+                    let cmd = prepare(cmd);
+                    tx.send(CommandUpdate::LogOutput(format!("Commande : {}", encryption::describe_command(&cmd, job.password.as_deref())))).ok();
+                    let moved = Self::run_step(cmd, "l'extraction des fichiers à renommer", backend_kind, &cancel_flag, &tx, &ctx)
+                        .and_then(|_| overwrite::move_renamed(&staging, &output, &plan.renamed));
+                    if moved.is_err() {
+                        let _ = fs::remove_dir_all(&staging);
+                    }
+                    for line in moved? {
+                        tx.send(CommandUpdate::LogOutput(line)).ok();
+                    }
+                }
+                tx.send(CommandUpdate::LogOutput(plan.safety.summary())).ok();
+                if !plan.safety.is_clean() {
+                    // Tâche signalée en échec : une partie de l'archive n'a pas été extraite
+                    return Err(plan.safety.summary());
+                }
+                Ok(format!("Extraction terminée avec succès ({})", plan.summary()))
+            };
+            let result = run();
+            if !matches!(&result, Err(e) if e == CANCELLED || e == CONFLICTS_PENDING) {
+                Self::play_notification_sound();
             }
+            drop(files); // Volumes réassemblés supprimés avant d'annoncer la fin
+            tx.send(CommandUpdate::ProcessCompleted(result)).ok();
+            ctx.request_repaint();
+        });
+    }
 
-            // Obtenir le dossier de travail actuel
-            let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-            
-            // Convertir les chemins sélectionnés en chemins relatifs
-            let relative_paths: Vec<PathBuf> = self.selected.iter()
-                .filter_map(|path| {
-                    path.strip_prefix(&current_dir).ok()
-                        .or_else(|| Some(path.as_path()))
-                        .map(|p| p.to_path_buf())
-                })
-                .collect();
+    /// Une étape d'une tâche à plusieurs commandes : sortie relayée comme pour `run_command`,
+    /// Err(CANCELLED) si l'annulation a été demandée pendant l'étape
+    fn run_step(
+        mut cmd: Command,
+        action: &str,
+        backend_kind: BackendKind,
+        cancel_flag: &AtomicBool,
+        tx: &mpsc::Sender<CommandUpdate>,
+        ctx: &egui::Context,
+    ) -> Result<(), String> {
+        if cancel_flag.load(Ordering::SeqCst) {
+            return Err(CANCELLED.to_string());
+        }
+        cmd.stdin(std::process::Stdio::null())
+           .stdout(std::process::Stdio::piped())
+           .stderr(std::process::Stdio::piped());
+        let mut child = cmd.spawn().map_err(|e| format!("Erreur: {}", e))?;
+        tx.send(CommandUpdate::Started(child.id())).ok();
+        if cancel_flag.load(Ordering::SeqCst) {
+            process_tree::kill_tree(child.id());
+        }
+        let stdout_handle = Self::forward_output(child.stdout.take().unwrap(), "stdout", backend_kind, tx.clone(), ctx.clone());
+        let stderr_handle = Self::forward_output(child.stderr.take().unwrap(), "stderr", backend_kind, tx.clone(), ctx.clone());
+        let _ = stdout_handle.join();
+        let _ = stderr_handle.join();
+        let status = child.wait().map_err(|e| format!("Erreur: {}", e))?;
+        if cancel_flag.load(Ordering::SeqCst) {
+            Err(CANCELLED.to_string())
+        } else if status.success() {
+            Ok(())
+        } else {
+            Err(format!("Erreur lors de {} (code {:?})", action, status.code()))
+        }
+    }
 
-            self.log_lines.push(format!(
-                "Compression des fichiers : {:?}\nVers : {}\n",
-                relative_paths,
-                self.output_path.display()
-            ));
+    /// Examen préalable dans le thread de la tâche (le listage bloquerait l'interface) :
+    /// l'archive est chiffrée, la tâche s'arrête et le mot de passe est demandé
+    fn password_required(tx: &mpsc::Sender<CommandUpdate>) -> String {
+        tx.send(CommandUpdate::PasswordRequired).ok();
+        "Archive chiffrée : mot de passe requis".to_string()
+    }
 
-            let mut cmd = Command::new(exe);
-            cmd.arg("a");
-            cmd.arg(&self.output_path);
-            
-            // Utiliser les chemins relatifs pour la compression
-            for item in &relative_paths {
-                cmd.arg(item);
-            }
-            
-            for flag in self.preset.flags() {
-                cmd.arg(flag);
-            }
-            if self.output_path.extension().and_then(|ext| ext.to_str()) == Some("sfx") {
-                cmd.arg("-sfx");
-            }
+    fn reassemble(reassembly: &mut Reassembly, cancel_flag: &AtomicBool, tx: &mpsc::Sender<CommandUpdate>) -> Result<(), String> {
+        tx.send(CommandUpdate::LogOutput(format!(
+            "Réassemblage de {} volume(s) dans {}",
+            reassembly.volume_count(),
+            reassembly.joined.display()
+        ))).ok();
+        reassembly.join(cancel_flag).map_err(|e| match e.as_str() {
+            CANCELLED => e,
+            _ => format!("Erreur lors du réassemblage des volumes : {}", e),
+        })
+    }
 
-            // Démarre le compteur de temps
-            self.compression_start_time = Some(std::time::Instant::now());
-            self.elapsed_time = String::from("00:00:00");
-            self.is_processing = true;
+    fn split_volumes(archive: &Path, volume_size: u64, cancel_flag: &AtomicBool, tx: &mpsc::Sender<CommandUpdate>) -> Result<(), String> {
+        tx.send(CommandUpdate::LogOutput(format!("Découpage en volumes de {}...", format_size(volume_size)))).ok();
+        match volumes::split(archive, volume_size, cancel_flag) {
+            Ok(parts) => {
+                for part in &parts {
+                    tx.send(CommandUpdate::LogOutput(format!("Volume créé : {}", part.display()))).ok();
+                }
+                Ok(())
+            }
+            Err(e) if e == CANCELLED => Err(e),
+            Err(e) => Err(format!("Erreur lors du découpage en volumes : {}", e)),
+        }
+    }
 
-            self.execute_command(cmd, "la compression", ctx);
-        } else {
-            // Mode extraction
-            if self.selected.len() != 1 {
-                self.log_lines.push("Erreur : Veuillez sélectionner une seule archive pour l'extraction.\n".to_string());
+    fn run_command(
+        mut cmd: Command,
+        action: String,
+        backend_kind: BackendKind,
+        mut files: CommandFiles,
+        cancel_flag: Arc<AtomicBool>,
+        tx: mpsc::Sender<CommandUpdate>,
+        ctx: egui::Context,
+    ) {
+        if let Some(reassembly) = &mut files.reassembly {
+            if let Err(e) = Self::reassemble(reassembly, &cancel_flag, &tx) {
+                tx.send(CommandUpdate::ProcessCompleted(Err(e))).ok();
+                ctx.request_repaint();
                 return;
             }
-
-            let archive_to_extract = &self.selected[0];
-            if !archive_to_extract.exists() {
-                self.log_lines.push(format!("Erreur : L'archive {} n'existe pas\n", archive_to_extract.display()));
+        }
+        if let Some(archive) = &files.password_probe {
+            if listing::is_encrypted(archive) {
+                tx.send(CommandUpdate::ProcessCompleted(Err(Self::password_required(&tx)))).ok();
+                ctx.request_repaint();
                 return;
             }
+        }
 
-            if let Some(dest) = rfd::FileDialog::new().set_title("Choisir le dossier d'extraction").pick_folder() {
-                self.log_lines.push(format!(
-                    "Extraction de l'archive : {}\nVers : {}\n",
-                    archive_to_extract.display(),
-                    dest.display()
-                ));
+        // stdin fermé : un outil qui demande un mot de passe échoue au lieu d'attendre
+        cmd.stdin(std::process::Stdio::null())
+           .stdout(std::process::Stdio::piped())
+           .stderr(std::process::Stdio::piped());
 
-                let mut cmd = Command::new(exe);
-                cmd.args(&[
-                    "x",
-                    archive_to_extract.to_str().unwrap(),
-                    &format!("-dp{}", dest.display()),
-                    "-o+",
-                    "-y",
-                ]);
+        match cmd.spawn() {
+            Ok(mut child) => {
+                tx.send(CommandUpdate::Started(child.id())).ok();
+                // Annulation demandée avant que le pid ne soit connu de l'interface
+                if cancel_flag.load(Ordering::SeqCst) {
+                    process_tree::kill_tree(child.id());
+                }
 
-                self.execute_command(cmd, "l'extraction", ctx);
-            }
-        }
-    }
+                let stdout = child.stdout.take().unwrap();
+                let stderr = child.stderr.take().unwrap();
 
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Check for notifications at the start of the update
-        if let Some(rx) = &self.command_rx {
-            if let Ok(update) = rx.try_recv() {
-                match update {
-                    CommandUpdate::LogOutput(log) => {
-                        self.log_lines.push(log);
-                        self.log_scroll_to_end = true; // Use self.
-                    },
-                    CommandUpdate::Progress(progress) => {
-                        self.progress_value = progress;
-                    },
-                    CommandUpdate::ProcessCompleted(result) => {
+                // Ajout: lire stdout et stderr en parallèle pour logs temps réel
+                let stdout_handle = Self::forward_output(stdout, "stdout", backend_kind, tx.clone(), ctx.clone());
+                let stderr_handle = Self::forward_output(stderr, "stderr", backend_kind, tx.clone(), ctx.clone());
+
+                // Attendre la fin des threads de logs
+                let _ = stdout_handle.join();
+                let _ = stderr_handle.join();
+
+                match child.wait() {
+                    Ok(_) if cancel_flag.load(Ordering::SeqCst) => {
+                        if let Some(path) = files.partial_output.take().filter(|p| p.exists()) {
+                            match fs::remove_file(&path) {
+                                Ok(()) => tx.send(CommandUpdate::LogOutput(format!("Archive partielle supprimée : {}", path.display()))).ok(),
+                                Err(e) => tx.send(CommandUpdate::LogOutput(format!("Impossible de supprimer {} : {}", path.display(), e))).ok(),
+                            };
+                        }
+                        tx.send(CommandUpdate::ProcessCompleted(Err(CANCELLED.to_string()))).ok();
+                    }
+                    Ok(status) => {
+                        // Découpage de l'archive produite, quand l'outil ne le fait pas lui-même
+                        let result = match files.split.take() {
+                            Some((archive, size)) if status.success() => Self::split_volumes(&archive, size, &cancel_flag, &tx),
+                            _ if status.success() => Ok(()),
+                            _ => Err(format!("Erreur lors de {}", action)),
+                        };
+                        // Assemblage et auto-test de l'exécutable auto-extractible
+                        let result = match files.sfx.take() {
+                            Some(build) if result.is_ok() => build.finish().map(|lines| {
+                                for line in lines {
+                                    tx.send(CommandUpdate::LogOutput(line)).ok();
+                                }
+                            }).map_err(|e| format!("Archive auto-extractible : {}", e)),
+                            _ => result,
+                        };
                         match result {
-                            Ok(message) => {
-                                self.notification = Some(Notification {
-                                    message,
+                            Ok(()) => {
+                                Self::play_notification_sound();
+                                let notification = Notification {
+                                    message: format!("{} terminée avec succès", action),
                                     level: NotificationLevel::Success,
                                     timestamp: std::time::Instant::now(),
-                                });
-                                self.is_processing = false;
-                                self.last_elapsed_time = Some(self.elapsed_time.clone());
-                                self.compression_start_time = None;
-                                self.log_scroll_to_end = true;
-                            },
-                            Err(message) => {
-                                self.notification = Some(Notification {
-                                    message,
+                                };
+                                tx.send(CommandUpdate::ProcessCompleted(Ok(notification.message))).ok();
+                            }
+                            Err(e) if e == CANCELLED => {
+                                tx.send(CommandUpdate::ProcessCompleted(Err(e))).ok();
+                            }
+                            Err(e) => {
+                                Self::play_notification_sound();
+                                let notification = Notification {
+                                    message: e,
                                     level: NotificationLevel::Error,
                                     timestamp: std::time::Instant::now(),
-                                });
-                                self.is_processing = false;
-                                self.last_elapsed_time = Some(self.elapsed_time.clone());
-                                self.compression_start_time = None;
-                                self.log_scroll_to_end = true;
+                                };
+                                tx.send(CommandUpdate::ProcessCompleted(Err(notification.message))).ok();
                             }
                         }
                     }
+                    Err(e) => {
+                        let notification = Notification {
+                            message: format!("Erreur: {}", e),
+                            level: NotificationLevel::Error,
+                            timestamp: std::time::Instant::now(),
+                        };
+                        tx.send(CommandUpdate::ProcessCompleted(Err(notification.message))).ok();
+                    }
                 }
             }
+            Err(e) => {
+                let notification = Notification {
+                    message: format!("Erreur: {}", e),
+                    level: NotificationLevel::Error,
+                    timestamp: std::time::Instant::now(),
+                };
+                tx.send(CommandUpdate::ProcessCompleted(Err(notification.message))).ok();
+            }
         }
+        ctx.request_repaint();
+    }
 
-        // Mise à jour du compteur de temps si compression en cours
-        if self.is_processing {
-            if let Some(start) = self.compression_start_time {
-                let elapsed = start.elapsed();
-                let h = elapsed.as_secs() / 3600;
-                let m = (elapsed.as_secs() % 3600) / 60;
-                let s = elapsed.as_secs() % 60;
-                self.elapsed_time = format!("{:02}:{:02}:{:02}", h, m, s);
+    /// Calcule tous les hash d'un fichier en une seule lecture
+    fn hash_file_all(path: &Path, cancel_flag: &AtomicBool, mut on_progress: impl FnMut(f32)) -> Result<Vec<(HashType, String)>, String> {
+        use std::io::Read;
+
+        let mut file = fs::File::open(path).map_err(|e| format!("Erreur lors de la lecture du fichier: {}", e))?;
+        let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        let mut crc = crc32fast::Hasher::new();
+        let mut blake = blake3::Hasher::new();
+        let mut md5 = md5::Md5::new();
+        let mut sha256 = sha2::Sha256::new();
+        let mut sha3 = sha3::Sha3_256::new();
+
+        let mut buf = vec![0u8; 1 << 20];
+        let mut read_total = 0u64;
+        loop {
+            if cancel_flag.load(Ordering::SeqCst) {
+                return Err(CANCELLED.to_string());
+            }
+            let n = file.read(&mut buf).map_err(|e| format!("Erreur lors de la lecture du fichier: {}", e))?;
+            if n == 0 {
+                break;
+            }
+            let chunk = &buf[..n];
+            crc.update(chunk);
+            blake.update(chunk);
+            md5.update(chunk);
+            sha256.update(chunk);
+            sha3.update(chunk);
+            read_total += n as u64;
+            if total > 0 {
+                on_progress(read_total as f32 / total as f32);
+            }
+        }
+
+        Ok(vec![
+            (HashType::CRC32, format!("{:08X}", crc.finalize())),
+            (HashType::Blake3, blake.finalize().to_hex().to_string()),
+            (HashType::MD5, format!("{:x}", md5.finalize())),
+            (HashType::SHA256, format!("{:x}", sha256.finalize())),
+            (HashType::SHA3_256, format!("{:x}", sha3.finalize())),
+        ])
+    }
+
+    fn spawn_hash(path: PathBuf, cancel_flag: Arc<AtomicBool>, tx: mpsc::Sender<CommandUpdate>, ctx: egui::Context) {
+        thread::spawn(move || {
+            let result = Self::hash_file_all(&path, &cancel_flag, |fraction| {
+                tx.send(CommandUpdate::Progress(fraction)).ok();
                 ctx.request_repaint();
+            });
+            match result {
+                Ok(hashes) => {
+                    tx.send(CommandUpdate::LogOutput(format!("Hash de {} :", path.display()))).ok();
+                    for (hash_type, hash) in hashes {
+                        tx.send(CommandUpdate::LogOutput(format!("{:<8} : {}", hash_type.label(), hash))).ok();
+                    }
+                    Self::play_notification_sound();
+                    tx.send(CommandUpdate::ProcessCompleted(Ok("Calcul du hash terminé avec succès".to_string()))).ok();
+                }
+                Err(e) => {
+                    tx.send(CommandUpdate::ProcessCompleted(Err(e))).ok();
+                }
             }
-        } else {
-            self.compression_start_time = None;
-            self.elapsed_time = String::from("00:00:00");
+            ctx.request_repaint();
+        });
+    }
+
+    /// Options du preset d'une tâche de compression ou de conversion. Les groupes de fichiers
+    /// (fichier de groupes et méthodes propres à chaque groupe) passent avant le nombre de
+    /// threads, pour que celui-ci s'impose à toutes les chaînes
+    fn preset_job_flags(job: &Job, backend: &dyn backend::ArchiveBackend) -> Result<Vec<String>, String> {
+        let mut preset_flags: Vec<String> = job.preset.flags().iter().map(|f| f.to_string()).collect();
+        let mut groups_args = Vec::new();
+        if let Some(groups) = job.groups.as_ref().filter(|_| backend.groups_args(Path::new("")).is_some()) {
+            preset_flags = groups.compression_flags(&preset_flags)?;
+            groups_args = backend.groups_args(&groups.write_groups_file(job.id)?).unwrap_or_default();
         }
+        let mut flags = match job.threads {
+            Some(threads) => backend.thread_flags(&preset_flags.iter().map(String::as_str).collect::<Vec<_>>(), threads),
+            None => preset_flags,
+        };
+        flags.extend(groups_args);
+        Ok(flags)
+    }
 
-        let mut style = (*ctx.style()).clone();
-        style.spacing.item_spacing = egui::vec2(10.0, 10.0);
-        let visuals = Visuals::dark();  // Utiliser uniquement le thème sombre
-        style.visuals = visuals;
-        ctx.set_style(style);
+    /// Estimation mémoire d'une compression ou d'une conversion, quelle que soit l'origine de la tâche
+    /// (bouton Exécuter, file restaurée) : échec au-delà de la mémoire totale, avertissements au journal sinon
+    fn check_job_memory(&mut self, job: &Job, backend: &dyn backend::ArchiveBackend, flags: &[String]) -> Result<(), String> {
+        let cpus = job.threads.unwrap_or_else(method::cpu_count);
+        let memory = self.refresh_memory();
+        for warning in method::check_memory(backend, flags, cpus, memory)? {
+            self.log_lines.push(format!("Attention : {}\n", warning));
+        }
+        Ok(())
+    }
 
-        // Toolbar panel
-        egui::TopBottomPanel::top("main_toolbar")
-            .frame(Frame::default()
-                .fill(Color32::from_rgb(24, 26, 32))
-                .outer_margin(10.0)
-                .corner_radius(12.0)
-                .shadow(Shadow {
-                    offset: [0, 2],
-                    blur: 6,
+    /// Construit la commande d'une tâche et la lance
+    fn start_job(&mut self, job: &Job, ctx: &egui::Context) -> Result<(), String> {
+        let (tx, rx) = mpsc::channel();
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let mut total_bytes = 0;
+
+        match &job.kind {
+            JobKind::Compress => {
+                let backend = job.preset.backend().backend();
+                if !backend.is_available() {
+                    return Err(format!("{} n'est pas installé correctement", backend.name()));
+                }
+
+                // Obtenir le dossier de travail actuel
+                let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+
+                // Convertir les chemins sélectionnés en chemins relatifs
+                let relative_paths: Vec<PathBuf> = job.sources.iter()
+                    .map(|path| path.strip_prefix(&current_dir).unwrap_or(path).to_path_buf())
+                    .collect();
+
+                if relative_paths.len() > 1 && !backend.capabilities().multiple_sources {
+                    return Err(format!("{} ne compresse qu'une seule source à la fois", backend.name()));
+                }
+
+                // Filtres : seuls les fichiers retenus sont passés à l'outil, par fichier liste
+                let filtered = match job.filter.as_ref().filter(|f| !f.is_empty()) {
+                    Some(filter) => {
+                        filter.validate()?;
+                        let filtered = filter.collect(&job.sources);
+                        if filtered.files.is_empty() {
+                            return Err("Aucun fichier ne correspond aux filtres".to_string());
+                        }
+                        self.log_lines.push(format!(
+                            "Filtres : {} ({} fichier(s) retenu(s), {} écarté(s))\n",
+                            filter.summary(),
+                            filtered.files.len(),
+                            filtered.excluded
+                        ));
+                        Some(filtered)
+                    }
+                    None => None,
+                };
+                let relative_paths = match &filtered {
+                    Some(filtered) => {
+                        let list_file = filters::write_list_file(&filtered.files, &current_dir, job.id)?;
+                        let source = backend
+                            .list_file_source(&list_file)
+                            .ok_or_else(|| format!("{} ne permet pas de filtrer les fichiers", backend.name()))?;
+                        vec![source]
+                    }
+                    None => relative_paths,
+                };
+
+                self.log_lines.push(format!(
+                    "Compression des fichiers : {:?}\nVers : {}\n",
+                    relative_paths,
+                    job.output.display()
+                ));
+
+                let mut flags = Self::preset_job_flags(job, backend)?;
+                self.check_job_memory(job, backend, &flags)?;
+                let encryption_args = match &job.encryption {
+                    Some(settings) => {
+                        let password = job.password.as_deref()
+                            .ok_or("Mot de passe inconnu (tâche restaurée) : relancez la compression chiffrée")?;
+                        backend.encryption_args(&job.output, settings, password)?
+                    }
+                    None => Vec::new(),
+                };
+                flags.extend(encryption_args);
+                // Volumes : options de l'outil, sinon découpage par Stelarc une fois l'archive créée
+                let mut files = CommandFiles::default();
+                // Auto-extractible : module intégré par l'outil, sinon accolé à une archive intermédiaire
+                if let Some(options) = &job.sfx {
+                    let mut build = SfxBuild::prepare(options, backend.kind(), &job.output, job.id)?;
+                    match backend.sfx_args(&build.module, &build.config) {
+                        Some(args) => flags.extend(args),
+                        None => build.separate_archive(job.id),
+                    }
+                    files.sfx = Some(build);
+                }
+                let archive = files.sfx.as_ref().map_or_else(|| job.output.clone(), |b| b.archive.clone());
+                if let Some(size) = &job.recovery {
+                    if !backend.capabilities().recovery {
+                        return Err(format!("{} ne permet pas d'ajouter un enregistrement de récupération", backend.name()));
+                    }
+                    flags.extend(backend.recovery_args(size));
+                }
+                if let Some(size) = job.volume_size {
+                    if backend.capabilities().volumes {
+                        flags.extend(backend.volume_args(size));
+                    } else {
+                        files.split = Some((archive.clone(), size));
+                    }
+                }
+                let flags: Vec<&str> = flags.iter().map(String::as_str).collect();
+
+                // Utiliser les chemins relatifs pour la compression
+                let mut cmd = backend.compress_command(&archive, &relative_paths, &flags);
+                if let Some(mask) = job.affinity {
+                    cmd = process_tree::with_affinity(cmd, mask);
+                }
+                total_bytes = match &filtered {
+                    Some(filtered) => filtered.total_size,
+                    None => job.sources.iter().map(|p| self.calculate_directory_size(p)).sum(),
+                };
+                // Espace libre pour l'archive, les volumes découpés ensuite et les fichiers temporaires
+                let needs = diskspace::compression_needs(&archive, total_bytes, &flags, files.split.is_some());
+                diskspace::check(&needs).into_result(|warning| self.log_lines.push(format!("{}\n", warning)))?;
+                // Ne jamais supprimer une archive existante à laquelle on ajoute des fichiers
+                files.partial_output = (!archive.exists()).then(|| archive.clone());
+
+                // Mot de passe masqué dans le journal et la console
+                let description = encryption::describe_command(&cmd, job.password.as_deref());
+                println!("Commande exécutée : {}", description);
+                self.log_lines.push(format!("Exécution de la commande : {}\n", description));
+                Self::spawn_command(cmd, job.kind.action().to_string(), backend.kind(), files, cancel_flag.clone(), tx, ctx.clone());
+            }
+            JobKind::Extract | JobKind::Test => {
+                let archive = job.sources.first().ok_or("Aucune archive")?;
+                if !archive.exists() {
+                    return Err(format!("L'archive {} n'existe pas", archive.display()));
+                }
+                let backend = BackendKind::for_archive(archive).backend();
+                if !backend.is_available() {
+                    return Err(format!("{} n'est pas installé correctement", backend.name()));
+                }
+                if job.kind == JobKind::Test && !backend.capabilities().test {
+                    return Err(format!("{} ne sait pas tester une archive", backend.name()));
+                }
+
+                // Archive découpée : tous les volumes doivent être présents ; réassemblage
+                // préalable si l'outil ne sait pas les lire lui-même
+                let mut files = CommandFiles {
+                    reassembly: volumes::prepare_reassembly(archive, backend.capabilities().volumes, job.id)?,
+                    ..Default::default()
+                };
+                let source = files.reassembly.as_ref().map_or_else(|| archive.clone(), |r| r.joined.clone());
+                if job.password.is_none() && backend.capabilities().encryption {
+                    files.password_probe = Some(source.clone());
+                }
+                if let Some(reassembly) = &files.reassembly {
+                    // Même disque : le fichier réassemblé et l'extraction s'additionnent
+                    let needs = match job.kind {
+                        JobKind::Extract => reassembly.space_needs(&job.output, "l'extraction"),
+                        _ => vec![reassembly.space_need()],
+                    };
+                    diskspace::check(&needs).into_result(|warning| self.log_lines.push(format!("{}\n", warning)))?;
+                }
+
+                // Extraction planifiée d'après le contenu de l'archive (entrées vérifiées, fichiers
+                // déjà présents) ; sans listage possible, options de l'outil pour la politique
+                let overwrite_args = backend.overwrite_args(job.overwrite);
+                if job.kind == JobKind::Extract && backend.capabilities().list {
+                    self.log_lines.push(format!(
+                        "Extraction de l'archive : {}\nVers : {}\nFichiers existants : {}\n",
+                        archive.display(),
+                        job.output.display(),
+                        job.overwrite.label()
+                    ));
+                    Self::spawn_extract_plan(job.clone(), source, files, cancel_flag.clone(), tx, ctx.clone());
+                } else if job.kind == JobKind::Extract && overwrite_args.is_none() {
+                    return Err(format!("{} remplace toujours les fichiers existants", backend.name()));
+                } else {
+                    if job.kind == JobKind::Extract {
+                        self.log_lines.push(format!("Attention : {} ne permet pas de vérifier le contenu de l'archive avant extraction\n", backend.name()));
+                    }
+                    // Mode intelligent sans contenu connu : sous-dossier au nom de l'archive
+                    let output = if job.smart_destination {
+                        destination::resolve_listed(archive, &job.output, &job.selection, ExtractMode::Smart, None)
+                    } else {
+                        job.output.clone()
+                    };
+                    let mut cmd = if job.kind == JobKind::Extract && !job.selection.is_empty() {
+                        self.log_lines.push(format!(
+                            "Extraction de {} entrée(s) de l'archive : {}\nVers : {}\n",
+                            job.selection.len(),
+                            archive.display(),
+                            output.display()
+                        ));
+                        let list_file = listing::write_list_file(&job.selection, job.id)?;
+                        backend.extract_files_command(&source, &output, &list_file)
+                            .ok_or_else(|| format!("{} ne permet pas l'extraction sélective", backend.name()))?
+                    } else if job.kind == JobKind::Extract {
+                        self.log_lines.push(format!(
+                            "Extraction de l'archive : {}\nVers : {}\n",
+                            archive.display(),
+                            output.display()
+                        ));
+                        backend.extract_command(&source, &output)
+                    } else {
+                        self.log_lines.push(format!("Test de l'archive : {}\n", archive.display()));
+                        backend.test_command(&source)
+                            .ok_or_else(|| format!("{} ne sait pas tester une archive", backend.name()))?
+                    };
+
+                    cmd.args(overwrite_args.unwrap_or_default());
+                    if let Some(password) = &job.password {
+                        cmd.args(backend.password_args(password));
+                    }
+                    if let Some(mask) = job.affinity {
+                        cmd = process_tree::with_affinity(cmd, mask);
+                    }
+
+                    let description = encryption::describe_command(&cmd, job.password.as_deref());
+                    println!("Commande exécutée : {}", description);
+                    self.log_lines.push(format!("Exécution de la commande : {}\n", description));
+                    // Taille décompressée inconnue : l'outil l'annonce en début d'opération
+                    if job.kind == JobKind::Test {
+                        Self::spawn_test(cmd, source, backend.kind(), files, cancel_flag.clone(), tx, ctx.clone());
+                    } else {
+                        Self::spawn_command(cmd, job.kind.action().to_string(), backend.kind(), files, cancel_flag.clone(), tx, ctx.clone());
+                    }
+                }
+            }
+            JobKind::Update(op) => {
+                let archive = fs::canonicalize(&job.output)
+                    .map_err(|e| format!("L'archive {} n'existe pas ({})", job.output.display(), e))?;
+                let backend = BackendKind::for_archive(&archive).backend();
+                if !backend.is_available() {
+                    return Err(format!("{} n'est pas installé correctement", backend.name()));
+                }
+                if volumes::is_volume(&archive) {
+                    return Err("Une archive en plusieurs volumes ne peut pas être modifiée".to_string());
+                }
+                let capabilities = backend.capabilities();
+                let supported = match op {
+                    UpdateOp::Rename { .. } => capabilities.rename,
+                    _ => capabilities.update,
+                };
+                if !supported {
+                    return Err(format!("{} ne permet pas {}", backend.name(), job.kind.action()));
+                }
+
+                let list_file = match op {
+                    UpdateOp::Freshen | UpdateOp::Delete => Some(listing::write_list_file(&job.selection, job.id)?),
+                    UpdateOp::Add | UpdateOp::Rename { .. } => None,
+                };
+                // Options du préréglage seulement s'il vise le même outil que l'archive
+                let flags: Vec<String> = if job.preset.backend() == backend.kind() {
+                    match job.threads {
+                        Some(threads) => backend.thread_flags(&job.preset.flags(), threads),
+                        None => job.preset.flags().iter().map(|f| f.to_string()).collect(),
+                    }
+                } else {
+                    Vec::new()
+                };
+                let flags: Vec<&str> = flags.iter().map(String::as_str).collect();
+
+                // Les entrées sont nommées relativement au dossier de travail de l'outil
+                let (work_dir, sources) = match op {
+                    UpdateOp::Add => {
+                        let dir = job.sources.first().and_then(|p| p.parent()).map(Path::to_path_buf);
+                        let sources = job.sources.iter()
+                            .map(|p| dir.as_deref().and_then(|d| p.strip_prefix(d).ok()).unwrap_or(p).to_path_buf())
+                            .collect();
+                        (dir, sources)
+                    }
+                    UpdateOp::Freshen => (job.sources.first().cloned(), Vec::new()),
+                    _ => (None, Vec::new()),
+                };
+                if let Some(dir) = &work_dir {
+                    if !dir.is_dir() {
+                        return Err(format!("Le dossier {} n'existe pas", dir.display()));
+                    }
+                }
+
+                self.log_lines.push(match op {
+                    UpdateOp::Add => format!("Ajout de {:?}\nÀ l'archive : {}\n", sources, archive.display()),
+                    UpdateOp::Freshen => format!(
+                        "Actualisation de l'archive : {}\nDepuis : {}\n",
+                        archive.display(),
+                        work_dir.as_deref().unwrap_or(Path::new(".")).display()
+                    ),
+                    UpdateOp::Delete => format!("Suppression de {} entrée(s) de l'archive : {}\n", job.selection.len(), archive.display()),
+                    UpdateOp::Rename { from, to } => format!("Renommage de {} en {} dans l'archive : {}\n", from, to, archive.display()),
+                });
+
+                let mut cmd = backend
+                    .update_command(&archive, op, &sources, list_file.as_deref(), &flags)
+                    .ok_or_else(|| format!("{} ne permet pas {}", backend.name(), job.kind.action()))?;
+                if let Some(dir) = &work_dir {
+                    cmd.current_dir(dir);
+                }
+                if let Some(password) = &job.password {
+                    cmd.args(backend.password_args(password));
+                }
+                if let Some(mask) = job.affinity {
+                    cmd = process_tree::with_affinity(cmd, mask);
+                }
+                if *op == UpdateOp::Add {
+                    total_bytes = job.sources.iter().map(|p| self.calculate_directory_size(p)).sum();
+                }
+
+                let description = encryption::describe_command(&cmd, job.password.as_deref());
+                println!("Commande exécutée : {}", description);
+                self.log_lines.push(format!("Exécution de la commande : {}\n", description));
+                Self::spawn_command(cmd, job.kind.action().to_string(), backend.kind(), CommandFiles::default(), cancel_flag.clone(), tx, ctx.clone());
+            }
+            JobKind::Repair => {
+                let archive = job.sources.first().ok_or("Aucune archive")?;
+                // Chemin absolu : l'outil est lancé depuis le dossier de l'archive
+                let archive = &fs::canonicalize(archive).map_err(|_| format!("L'archive {} n'existe pas", archive.display()))?;
+                let backend = BackendKind::for_archive(archive).backend();
+                if !backend.is_available() {
+                    return Err(format!("{} n'est pas installé correctement", backend.name()));
+                }
+                let mut cmd = backend
+                    .repair_command(archive)
+                    .ok_or_else(|| format!("{} ne sait pas réparer une archive", backend.name()))?;
+                // L'archive réparée est écrite dans le dossier courant de l'outil
+                if let Some(dir) = archive.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    cmd.current_dir(dir);
+                }
+                if let Some(password) = &job.password {
+                    cmd.args(backend.password_args(password));
+                }
+                if let Some(mask) = job.affinity {
+                    cmd = process_tree::with_affinity(cmd, mask);
+                }
+
+                self.log_lines.push(format!("Réparation de l'archive : {}\n", archive.display()));
+                let description = encryption::describe_command(&cmd, job.password.as_deref());
+                println!("Commande exécutée : {}", description);
+                self.log_lines.push(format!("Exécution de la commande : {}\n", description));
+                let files = CommandFiles {
+                    password_probe: (job.password.is_none() && backend.capabilities().encryption).then(|| archive.clone()),
+                    ..Default::default()
+                };
+                Self::spawn_command(cmd, job.kind.action().to_string(), backend.kind(), files, cancel_flag.clone(), tx, ctx.clone());
+            }
+            JobKind::Convert => {
+                let archive = job.sources.first().ok_or("Aucune archive")?;
+                let source_backend = BackendKind::for_archive(archive).backend();
+                let target_backend = job.preset.backend().backend();
+                for backend in [source_backend, target_backend] {
+                    if !backend.is_available() {
+                        return Err(format!("{} n'est pas installé correctement", backend.name()));
+                    }
+                }
+                let flags = Self::preset_job_flags(job, target_backend)?;
+                self.check_job_memory(job, target_backend, &flags)?;
+                let conversion = Conversion::prepare(archive, &job.output, target_backend.kind(), job.id)?;
+                total_bytes = conversion.source_size();
+
+                self.log_lines.push(format!(
+                    "Conversion de l'archive : {}\nVers : {} ({}, preset {})\nDossier de travail : {}\n",
+                    conversion.source.display(),
+                    conversion.output.display(),
+                    target_backend.name(),
+                    job.preset.label().trim(),
+                    conversion.work_dir.display()
+                ));
+                Self::spawn_convert(conversion, flags, job.password.clone(), job.affinity, cancel_flag.clone(), tx, ctx.clone());
+            }
+            JobKind::Hash => {
+                let file = job.sources.first().ok_or("Aucun fichier")?;
+                if !file.is_file() {
+                    return Err("Le fichier spécifié n'existe pas".to_string());
+                }
+                Self::spawn_hash(file.clone(), cancel_flag.clone(), tx, ctx.clone());
+            }
+        }
+
+        // Démarre le compteur de temps au lancement de la première tâche
+        if self.running.is_empty() {
+            self.compression_start_time = Some(std::time::Instant::now());
+            self.elapsed_time = String::from("00:00:00");
+        }
+        self.is_processing = true;
+        self.running.push(RunningJob {
+            job_id: job.id,
+            label: format!("#{} {}", job.id, job.kind.label()),
+            rx,
+            pid: None,
+            cancel_flag,
+            is_paused: false,
+            progress_value: 0.0,
+            progress: CompressionProgress::new(total_bytes),
+            test_archive: (job.kind == JobKind::Test).then(|| job.sources[0].clone()),
+            test_listing: None,
+            test_lines: Vec::new(),
+            repair_archive: (job.kind == JobKind::Repair).then(|| (job.sources[0].clone(), std::time::SystemTime::now())),
+            repair_lines: Vec::new(),
+            password_error: false,
+            conflicts: None,
+        });
+        Ok(())
+    }
+
+    /// Démarre les tâches en attente dans la limite de la concurrence configurée
+    fn run_queue(&mut self, ctx: &egui::Context) {
+        let mut changed = false;
+        while self.queue.active && self.running.len() < self.queue.max_concurrent {
+            let Some(job) = self.queue.next_pending().cloned() else { break };
+            changed = true;
+            match self.start_job(&job, ctx) {
+                Ok(()) => self.queue.set_status(job.id, JobStatus::Running),
+                Err(e) => {
+                    self.log_lines.push(format!("Erreur (tâche #{}) : {}\n", job.id, e));
+                    self.queue.set_status(job.id, JobStatus::Failed(e));
+                }
+            }
+        }
+        if changed {
+            self.save_queue();
+        }
+    }
+
+    fn save_queue(&mut self) {
+        if let Err(e) = self.queue.save(Path::new(QUEUE_FILE)) {
+            self.log_lines.push(format!("Erreur : impossible de sauvegarder la file d'attente : {}\n", e));
+        }
+    }
+
+    fn enqueue(&mut self, kind: JobKind, sources: Vec<PathBuf>, output: PathBuf) -> u64 {
+        let id = self.queue.push(kind.clone(), self.preset.clone(), sources, output);
+        let threads = self.limit_threads.then_some(self.threads);
+        let affinity = (self.affinity_mask != 0).then_some(self.affinity_mask);
+        let recompressed = matches!(kind, JobKind::Compress | JobKind::Convert);
+        let groups = (self.groups_enabled && recompressed).then(|| self.file_groups.clone());
+        let overwrite = if kind == JobKind::Extract { self.overwrite_policy } else { OverwritePolicy::default() };
+        if let Some(job) = self.queue.get_mut(id) {
+            job.threads = threads;
+            job.affinity = affinity;
+            job.groups = groups;
+            job.overwrite = overwrite;
+        }
+        self.queue.active = true;
+        self.save_queue();
+        if !self.running.is_empty() {
+            self.log_lines.push(format!("Tâche #{} ({}) ajoutée à la file d'attente\n", id, kind.label()));
+        }
+        id
+    }
+
+    fn cancel_job(&mut self, job_id: u64) {
+        if let Some(run) = self.running.iter().find(|r| r.job_id == job_id) {
+            run.cancel_flag.store(true, Ordering::SeqCst);
+            if let Some(pid) = run.pid {
+                if !process_tree::kill_tree(pid) {
+                    self.log_lines.push(format!("Attention : certains processus (pid {}) n'ont pas pu être arrêtés\n", pid));
+                }
+            }
+            self.log_lines.push(format!("Annulation demandée (tâche #{})...\n", job_id));
+        } else {
+            self.queue.set_status(job_id, JobStatus::Cancelled);
+            self.save_queue();
+        }
+        self.log_scroll_to_end = true;
+    }
+
+    fn toggle_pause(&mut self, job_id: u64) {
+        let Some(run) = self.running.iter_mut().find(|r| r.job_id == job_id) else { return };
+        let Some(pid) = run.pid else { return };
+        let ok = if run.is_paused {
+            process_tree::resume_tree(pid)
+        } else {
+            process_tree::pause_tree(pid)
+        };
+        if ok {
+            run.is_paused = !run.is_paused;
+            self.log_lines.push(if run.is_paused { "Opération en pause\n" } else { "Reprise de l'opération\n" }.to_string());
+        } else {
+            self.log_lines.push("Erreur : impossible de suspendre/reprendre le processus\n".to_string());
+        }
+        self.log_scroll_to_end = true;
+    }
+
+    fn finish_job(&mut self, job_id: u64, result: Result<String, String>) {
+        let position = self.running.iter().position(|r| r.job_id == job_id);
+        let run = position.map(|i| self.running.remove(i));
+
+        // Échec dû au mot de passe : le redemander puis relancer la tâche
+        let password_error = run.as_ref().is_some_and(|r| r.password_error);
+        if password_error && self.password_prompt.is_none() && result.is_err() && !matches!(&result, Err(message) if message == CANCELLED) {
+            if let Some(job) = self.queue.jobs.iter().find(|j| j.id == job_id && matches!(j.kind, JobKind::Extract | JobKind::Test | JobKind::Repair | JobKind::Convert)) {
+                self.log_lines.push("Mot de passe manquant ou incorrect\n".to_string());
+                self.password_prompt = Some(PasswordPrompt {
+                    archive: job.sources[0].clone(),
+                    retry_of: job_id,
+                    password: String::new(),
+                });
+            }
+        }
+
+        // Test terminé : le rapport remplace le message générique
+        let result = match (run, result) {
+            (Some(RunningJob { test_archive: Some(archive), test_listing, test_lines, .. }), result)
+                if !matches!(&result, Err(message) if message == CANCELLED) =>
+            {
+                let report = TestReport::build(archive, test_listing, test_lines, result.is_ok());
+                let summary = report.summary();
+                self.log_lines.push(format!("{}\n", summary));
+                let passed = report.success && report.count(&TestOutcome::Failed(String::new())) == 0;
+                self.test_report = Some(report);
+                if passed { Ok(summary) } else { Err(summary) }
+            }
+            // Réparation terminée : détail de ce qui a été récupéré
+            (Some(RunningJob { repair_archive: Some((archive, started)), repair_lines, .. }), result)
+                if !matches!(&result, Err(message) if message == CANCELLED) =>
+            {
+                let report = RepairReport::build(&archive, &repair_lines, result.is_ok(), started);
+                for line in &report.recovered {
+                    self.log_lines.push(format!("✔ {}\n", line));
+                }
+                for line in &report.unrecoverable {
+                    self.log_lines.push(format!("❌ {}\n", line));
+                }
+                let summary = report.summary();
+                self.log_lines.push(format!("{}\n", summary));
+                if report.success { Ok(summary) } else { Err(summary) }
+            }
+            // Fichiers déjà présents : la tâche attend les décisions de la fenêtre des conflits
+            (Some(RunningJob { conflicts: Some((dest, conflicts)), .. }), Err(message)) if message == CONFLICTS_PENDING => {
+                self.log_lines.push(format!("{} fichier(s) déjà présent(s) dans {}\n", conflicts.len(), dest.display()));
+                self.conflict_dialog = Some(ConflictDialog { job_id, dest, conflicts });
+                Err(message)
+            }
+            (_, result) => result,
+        };
+        let (status, notification) = match result {
+            Err(message) if message == CONFLICTS_PENDING => (
+                JobStatus::Cancelled,
+                Notification {
+                    message: "Fichiers déjà présents : choisissez quoi en faire".to_string(),
+                    level: NotificationLevel::Warning,
+                    timestamp: std::time::Instant::now(),
+                },
+            ),
+            Err(message) if message == CANCELLED => (
+                JobStatus::Cancelled,
+                Notification {
+                    message: "Opération annulée".to_string(),
+                    level: NotificationLevel::Warning,
+                    timestamp: std::time::Instant::now(),
+                },
+            ),
+            Ok(message) => (
+                JobStatus::Done,
+                Notification {
+                    message,
+                    level: NotificationLevel::Success,
+                    timestamp: std::time::Instant::now(),
+                },
+            ),
+            Err(message) => (
+                JobStatus::Failed(message.clone()),
+                Notification {
+                    message,
+                    level: NotificationLevel::Error,
+                    timestamp: std::time::Instant::now(),
+                },
+            ),
+        };
+        // Archive modifiée : la fenêtre de contenu ouverte sur elle est relue
+        if status == JobStatus::Done {
+            let updated = self.queue.jobs.iter().find(|j| j.id == job_id && matches!(j.kind, JobKind::Update(_)));
+            if let (Some(job), Some(browser)) = (updated, &mut self.browser) {
+                if fs::canonicalize(&job.output).ok() == fs::canonicalize(&browser.archive).ok() {
+                    browser.refresh();
+                }
+            }
+        }
+        self.notification = Some(notification);
+        self.queue.set_status(job_id, status);
+        self.save_queue();
+        self.log_scroll_to_end = true;
+
+        if self.running.is_empty() {
+            self.is_processing = false;
+            self.last_elapsed_time = Some(self.elapsed_time.clone());
+            self.compression_start_time = None;
+        }
+    }
+
+    fn handle_action(&mut self, ctx: &egui::Context) {
+        if self.running.is_empty() {
+            self.log_lines.clear();
+        }
+
+        if self.mode == Mode::Compress {
+            // Mode compression
+            if self.selected.is_empty() {
+                self.log_lines.push("Erreur : Aucune source sélectionnée pour la compression.\n".to_string());
+                return;
+            }
+            if self.output_path.file_name().is_none() {
+                self.log_lines.push("Erreur : Chemin de sortie invalide.\n".to_string());
+                return;
+            }
+
+            let (encryption, password) = if self.encryption_enabled {
+                if let Err(e) = encryption::validate_password(&self.password, &self.password_confirm) {
+                    self.log_lines.push(format!("Erreur : {}.\n", e));
+                    return;
+                }
+                // Vérifier tout de suite que l'outil accepte ces options
+                let backend = self.preset.backend().backend();
+                if let Err(e) = backend.encryption_args(&self.output_path, &self.encryption, &self.password) {
+                    self.log_lines.push(format!("Erreur : {}.\n", e));
+                    return;
+                }
+                (Some(self.encryption.clone()), Some(self.password.clone()))
+            } else {
+                (None, None)
+            };
+
+            if self.filters_enabled && !self.filter.is_empty() {
+                if let Err(e) = self.filter.validate() {
+                    self.log_lines.push(format!("Erreur : {}.\n", e));
+                    return;
+                }
+                let backend = self.preset.backend().backend();
+                if backend.list_file_source(Path::new("")).is_none() {
+                    self.log_lines.push(format!("Erreur : {} ne permet pas de filtrer les fichiers.\n", backend.name()));
+                    return;
+                }
+            }
+
+            if self.recovery_enabled {
+                if !self.preset.backend().backend().capabilities().recovery {
+                    self.log_lines.push(format!(
+                        "Erreur : {} ne permet pas d'ajouter un enregistrement de récupération.\n",
+                        self.preset.backend().backend().name()
+                    ));
+                    return;
+                }
+                if RecoverySize::parse(&self.recovery_text).is_none() {
+                    self.log_lines.push(format!("Erreur : taille d'enregistrement de récupération invalide : {}\n", self.recovery_text));
+                    return;
+                }
+            }
+
+            if self.sfx_enabled {
+                if self.volumes_enabled {
+                    self.log_lines.push("Erreur : une archive auto-extractible ne peut pas être découpée en volumes.\n".to_string());
+                    return;
+                }
+                if let Err(e) = self.sfx.validate() {
+                    self.log_lines.push(format!("Erreur : {}.\n", e));
+                    return;
+                }
+            }
+
+            // Vérifier que la méthode tient en mémoire avant de lancer, avec le nombre de threads retenu
+            let cpus = if self.limit_threads { self.threads } else { method::cpu_count() };
+            let flags = match self.preset_flags_with_groups() {
+                Ok(flags) => flags,
+                Err(e) => {
+                    self.log_lines.push(format!("Erreur : groupes de fichiers : {}.\n", e));
+                    return;
+                }
+            };
+            let backend = self.preset.backend().backend();
+            let flags: Vec<&str> = flags.iter().map(String::as_str).collect();
+            let flags = if self.limit_threads { backend.thread_flags(&flags, self.threads) } else { flags.iter().map(|f| f.to_string()).collect() };
+            let flags: Vec<&str> = flags.iter().map(String::as_str).collect();
+            if let Some((compression, decompression)) = backend.estimate_memory(&flags, cpus) {
+                let (available, total) = self.refresh_memory();
+                if compression > available || decompression > total {
+                    self.ram_warning = Some(RamWarning {
+                        compression,
+                        decompression,
+                        available,
+                        total,
+                        // La réduction automatique ne sait alléger que les méthodes FreeArc
+                        downgraded: (backend.kind() == BackendKind::FreeArc)
+                            .then(|| method::downgrade_flags(&flags, available, cpus))
+                            .flatten(),
+                        threads: cpus,
+                        encryption,
+                        password,
+                    });
+                    return;
+                }
+            }
+
+            self.enqueue_compression(self.preset.clone(), encryption, password);
+        } else if self.mode == Mode::Test {
+            // Mode test : une ou plusieurs archives, une tâche par archive
+            let archives: Vec<PathBuf> = self.selected.iter().filter(|p| p.is_file()).cloned().collect();
+            if archives.is_empty() {
+                self.log_lines.push("Erreur : Veuillez sélectionner au moins une archive à tester.\n".to_string());
+                return;
+            }
+            for archive in archives {
+                let backend = BackendKind::for_archive(&archive).backend();
+                if !backend.capabilities().test {
+                    self.log_lines.push(format!("Erreur : {} ne sait pas tester une archive ({})\n", backend.name(), archive.display()));
+                    continue;
+                }
+                self.enqueue_archive_job(JobKind::Test, archive.clone(), archive, Vec::new());
+            }
+        } else {
+            // Mode extraction
+            if self.selected.len() != 1 {
+                self.log_lines.push("Erreur : Veuillez sélectionner une seule archive pour l'extraction.\n".to_string());
+                return;
+            }
+
+            let archive_to_extract = self.selected[0].clone();
+            if !archive_to_extract.exists() {
+                self.log_lines.push(format!("Erreur : L'archive {} n'existe pas\n", archive_to_extract.display()));
+                return;
+            }
+
+            if let Some(dest) = self.extraction_destination(&archive_to_extract, &[]) {
+                self.enqueue_extraction(archive_to_extract, dest, Vec::new());
+            }
+        }
+        self.run_queue(ctx);
+    }
+
+    fn enqueue_compression(&mut self, preset: CompressionPreset, encryption: Option<EncryptionSettings>, password: Option<String>) {
+        let id = self.enqueue(JobKind::Compress, self.selected.clone(), self.output_path.clone());
+        let volume_size = self.volumes_enabled.then_some(self.volume_size);
+        let sfx = self.sfx_enabled.then(|| self.sfx.clone());
+        let recovery = if self.recovery_enabled { RecoverySize::parse(&self.recovery_text) } else { None };
+        let filter = (self.filters_enabled && !self.filter.is_empty()).then(|| self.filter.clone());
+        if let Some(job) = self.queue.get_mut(id) {
+            job.filter = filter;
+            job.recovery = recovery;
+            job.preset = preset;
+            job.encryption = encryption;
+            job.password = password;
+            job.volume_size = volume_size;
+            job.sfx = sfx;
+        }
+        self.save_queue();
+    }
+
+    /// Options du preset courant, complétées des méthodes des groupes de fichiers s'ils s'appliquent
+    fn preset_flags_with_groups(&self) -> Result<Vec<String>, String> {
+        let flags = self.preset.flags();
+        if self.groups_enabled && self.preset.backend().backend().groups_args(Path::new("")).is_some() {
+            self.file_groups.compression_flags(&flags)
+        } else {
+            Ok(flags.iter().map(|f| f.to_string()).collect())
+        }
+    }
+
+    /// Met à jour les compteurs RAM ; retourne (disponible, totale) en octets
+    fn refresh_memory(&mut self) -> (u64, u64) {
+        self.sys.refresh_memory();
+        self.ram_total_mb = self.sys.total_memory() / (1024 * 1024);
+        self.ram_usage_mb = self.sys.used_memory() / (1024 * 1024);
+        (self.sys.available_memory(), self.sys.total_memory())
+    }
+
+    /// Conversion de l'archive sélectionnée vers la destination, avec le preset courant
+    fn enqueue_conversion(&mut self, archive: PathBuf, ctx: &egui::Context) {
+        if self.output_path.exists() {
+            self.log_lines.push(format!("Erreur : L'archive {} existe déjà, choisissez une autre destination.\n", self.output_path.display()));
+            return;
+        }
+        if self.preset.backend() == BackendKind::for_archive(&archive)
+            && self.output_path.extension() == archive.extension()
+        {
+            self.log_lines.push("Attention : l'archive est recompressée dans son propre format.\n".to_string());
+        }
+        self.enqueue_archive_job(JobKind::Convert, archive, self.output_path.clone(), Vec::new());
+        self.run_queue(ctx);
+    }
+
+    /// Extraction, test, réparation ou conversion ; une archive chiffrée est détectée au
+    /// lancement de la tâche, qui demande alors le mot de passe
+    fn enqueue_archive_job(&mut self, kind: JobKind, archive: PathBuf, output: PathBuf, selection: Vec<String>) -> u64 {
+        let id = self.enqueue(kind, vec![archive], output);
+        if let Some(job) = self.queue.get_mut(id) {
+            job.selection = selection;
+        }
+        self.save_queue();
+        id
+    }
+
+    /// Dossier d'extraction selon le mode choisi ; None si le sélecteur a été fermé. En mode
+    /// intelligent, dossier de l'archive : le sous-dossier dépend du contenu (`enqueue_extraction`)
+    fn extraction_destination(&mut self, archive: &Path, selection: &[String]) -> Option<PathBuf> {
+        match self.extract_mode {
+            ExtractMode::Choose => rfd::FileDialog::new().set_title("Choisir le dossier d'extraction").pick_folder(),
+            ExtractMode::Smart => Some(destination::archive_dir(archive)),
+            mode => {
+                let dest = destination::resolve_listed(archive, &destination::archive_dir(archive), selection, mode, None);
+                self.log_lines.push(format!("{} : {}\n", mode.label(), dest.display()));
+                Some(dest)
+            }
+        }
+    }
+
+    /// Extraction ; le contenu de l'archive est lu au lancement de la tâche, qui décide alors
+    /// du sous-dossier en mode intelligent et, avec la politique "demander", ouvre la
+    /// fenêtre des conflits s'il y a des fichiers déjà présents
+    fn enqueue_extraction(&mut self, archive: PathBuf, dest: PathBuf, selection: Vec<String>) {
+        let smart = self.extract_mode == ExtractMode::Smart;
+        let id = self.enqueue_archive_job(JobKind::Extract, archive, dest, selection);
+        if let Some(job) = self.queue.get_mut(id).filter(|_| smart) {
+            job.smart_destination = true;
+            self.save_queue();
+        }
+    }
+
+    /// Modification d'une archive existante ; le mot de passe saisi pour la compression
+    /// est repris si l'archive est chiffrée
+    fn enqueue_update(&mut self, op: UpdateOp, archive: PathBuf, sources: Vec<PathBuf>, selection: Vec<String>) {
+        let password = (self.encryption_enabled && !self.password.is_empty()).then(|| self.password.clone());
+        let id = self.enqueue(JobKind::Update(op), sources, archive);
+        if let Some(job) = self.queue.get_mut(id) {
+            job.selection = selection;
+            job.password = password;
+        }
+        self.save_queue();
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Check for notifications at the start of the update
+        let mut finished = Vec::new();
+        let several = self.running.len() > 1;
+        for run in &mut self.running {
+            // Vider tous les messages en attente : les mises à jour de progression arrivent en rafale
+            while let Ok(update) = run.rx.try_recv() {
+                match update {
+                    CommandUpdate::LogOutput(log) => {
+                        run.password_error |= encryption::is_password_error(&log);
+                        if run.repair_archive.is_some() {
+                            let line = log.strip_prefix("[stdout] ").or_else(|| log.strip_prefix("[stderr] ")).unwrap_or(&log);
+                            run.repair_lines.push(line.to_string());
+                        }
+                        // Préfixer par la tâche quand plusieurs tournent en parallèle
+                        self.log_lines.push(if several { format!("[#{}] {}", run.job_id, log) } else { log });
+                        self.log_scroll_to_end = true; // Use self.
+                    },
+                    CommandUpdate::Progress(progress) => {
+                        run.progress_value = progress;
+                        run.progress.update(progress);
+                    },
+                    CommandUpdate::TotalBytes(total) => {
+                        run.progress.total_bytes = total;
+                    },
+                    CommandUpdate::Started(pid) => {
+                        run.pid = Some(pid);
+                    },
+                    CommandUpdate::TestListing(listing) => {
+                        run.test_listing = listing;
+                    },
+                    CommandUpdate::TestResult(line) => {
+                        run.test_lines.push(line);
+                    },
+                    CommandUpdate::PasswordRequired => {
+                        run.password_error = true;
+                    },
+                    CommandUpdate::Conflicts(dest, conflicts) => {
+                        run.conflicts = Some((dest, conflicts));
+                    },
+                    CommandUpdate::ProcessCompleted(result) => {
+                        finished.push((run.job_id, result));
+                    }
+                }
+            }
+        }
+        for (job_id, result) in finished {
+            self.finish_job(job_id, result);
+        }
+        self.run_queue(ctx);
+        self.update_stats(ctx);
+        self.update_filter_preview(ctx);
+
+        // Mise à jour du compteur de temps si compression en cours
+        if self.is_processing {
+            if let Some(start) = self.compression_start_time {
+                self.elapsed_time = format_duration(start.elapsed());
+                ctx.request_repaint();
+            }
+        } else {
+            self.compression_start_time = None;
+            self.elapsed_time = String::from("00:00:00");
+        }
+
+        let mut style = (*ctx.style()).clone();
+        style.spacing.item_spacing = egui::vec2(10.0, 10.0);
+        let visuals = Visuals::dark();  // Utiliser uniquement le thème sombre
+        style.visuals = visuals;
+        ctx.set_style(style);
+
+        // Toolbar panel
+        egui::TopBottomPanel::top("main_toolbar")
+            .frame(Frame::default()
+                .fill(Color32::from_rgb(24, 26, 32))
+                .outer_margin(10.0)
+                .corner_radius(12.0)
+                .shadow(Shadow {
+                    offset: [0, 2],
+                    blur: 6,
                     spread: 2,
                     color: Color32::from_black_alpha(80),
                 }))
@@ -792,10 +2306,12 @@ impl MonCompresseurApp {
                     // Mode selection group
                     ui.group(|ui| {
                         ui.horizontal(|ui| {
-                            ui.selectable_value(&mut self.mode_compress, true,
+                            ui.selectable_value(&mut self.mode, Mode::Compress,
                                 RichText::new("📦 Compresser").size(16.0));
-                            ui.selectable_value(&mut self.mode_compress, false,
+                            ui.selectable_value(&mut self.mode, Mode::Extract,
                                 RichText::new("📂 Extraire").size(16.0));
+                            ui.selectable_value(&mut self.mode, Mode::Test,
+                                RichText::new("🧪 Tester").size(16.0));
                         });
                     });
 
@@ -805,6 +2321,7 @@ impl MonCompresseurApp {
                     ui.group(|ui| {
                         ui.horizontal(|ui| {
                             ui.label(RichText::new("Preset:").size(16.0));
+                            let previous = self.preset.clone();
                             egui::ComboBox::new("preset_selector", "")
                                 .selected_text(self.preset.label())
                                 .show_ui(ui, |ui| {
@@ -815,9 +2332,160 @@ impl MonCompresseurApp {
                                             preset.label()
                                         );
                                     }
+                                    if !self.user_presets.is_empty() {
+                                        ui.separator();
+                                        for preset in &self.user_presets {
+                                            ui.selectable_value(
+                                                &mut self.preset,
+                                                CompressionPreset::User(preset.clone()),
+                                                format!("★ {}", preset.name)
+                                            ).on_hover_text(&preset.description);
+                                        }
+                                    }
                                 });
+                            if self.preset != previous {
+                                if let CompressionPreset::User(preset) = &self.preset {
+                                    // Preset utilisateur : son extension est imposée, ses filtres repris
+                                    self.output_path.set_extension(&preset.extension);
+                                    if let Some(filter) = &preset.filter {
+                                        self.filter = (**filter).clone();
+                                        self.filters_enabled = true;
+                                    }
+                                } else if self.preset.backend() != previous.backend() {
+                                    // Changement d'outil : adapter l'extension de sortie
+                                    let backend = self.preset.backend().backend();
+                                    let ext = self.output_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+                                    if !backend.extensions().contains(&ext) {
+                                        self.output_path.set_extension(backend.extensions()[0]);
+                                    }
+                                }
+                            }
+                            if ui.button("✏").on_hover_text("Gérer les presets utilisateur").clicked() {
+                                self.show_preset_editor = !self.show_preset_editor;
+                            }
+                            if ui.button("🔬").on_hover_text("Analyser la méthode du preset").clicked() {
+                                self.show_method_window = !self.show_method_window;
+                            }
+                            let groups_button = if self.groups_enabled { RichText::new("🗂").strong() } else { RichText::new("🗂") };
+                            if ui.button(groups_button).on_hover_text("Groupes de fichiers et méthodes par groupe").clicked() {
+                                self.show_group_editor = !self.show_group_editor;
+                            }
+
+                            // Preset conseillé d'après le contenu de la sélection
+                            if self.mode == Mode::Compress {
+                                if self.stats_rx.is_some() {
+                                    ui.spinner().on_hover_text("Analyse de la sélection...");
+                                } else if let Some(recommendation) = &self.recommendation {
+                                    let reasons = recommendation.reasons.join("\n");
+                                    if recommendation.preset == self.preset {
+                                        ui.label(RichText::new("💡 Conseillé").color(Color32::from_rgb(52, 168, 83)))
+                                            .on_hover_text(reasons);
+                                    } else if ui.button(format!("💡 {}", recommendation.preset.label().trim()))
+                                        .on_hover_text(format!("Preset conseillé (cliquer pour l'appliquer) :\n{}", reasons))
+                                        .clicked() {
+                                        self.preset = recommendation.preset.clone();
+                                        let backend = self.preset.backend().backend();
+                                        let ext = self.output_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+                                        if !backend.extensions().contains(&ext) {
+                                            self.output_path.set_extension(self.preset.extension());
+                                        }
+                                    }
+                                }
+                            }
                         });
                     });
+
+                    ui.add_space(10.0);
+
+                    // Chiffrement (détails dans le panneau central)
+                    ui.group(|ui| {
+                        let supported = self.preset.backend().backend().capabilities().encryption;
+                        if !supported {
+                            self.encryption_enabled = false;
+                        }
+                        ui.add_enabled(supported, egui::Checkbox::new(&mut self.encryption_enabled,
+                            RichText::new("🔒 Chiffrement").size(16.0)))
+                            .on_disabled_hover_text("Cet outil ne prend pas en charge le chiffrement");
+                    });
+
+                    ui.add_space(10.0);
+
+                    // Archive auto-extractible (options dans le panneau central)
+                    ui.group(|ui| {
+                        let backend = self.preset.backend().backend();
+                        let supported = backend.capabilities().sfx;
+                        if !supported && self.sfx_enabled {
+                            self.sfx_enabled = false;
+                            self.output_path.set_extension(self.preset.extension());
+                        }
+                        if ui.add_enabled(supported, egui::Checkbox::new(&mut self.sfx_enabled,
+                            RichText::new("📦 SFX").size(16.0)))
+                            .on_hover_text("Archive auto-extractible (.exe)")
+                            .on_disabled_hover_text("Cet outil ne crée pas d'archive auto-extractible")
+                            .changed() {
+                            if self.sfx_enabled {
+                                self.output_path.set_extension("exe");
+                                if !self.sfx.module.is_file() {
+                                    self.sfx.module = backend.sfx_modules().into_iter().next().unwrap_or_default();
+                                }
+                            } else {
+                                self.output_path.set_extension(self.preset.extension());
+                            }
+                        }
+                    });
+
+                    ui.add_space(10.0);
+
+                    // Threads et affinité, appliqués aux tâches ajoutées ensuite
+                    ui.group(|ui| {
+                        let cores = self.sys.cpus().len().max(1);
+                        ui.checkbox(&mut self.limit_threads, RichText::new("🧵 Threads").size(16.0))
+                            .on_hover_text("Imposer le nombre de threads aux méthodes du preset (xtool, 4x4, zstd, 7-Zip)");
+                        ui.add_enabled(self.limit_threads, egui::DragValue::new(&mut self.threads).range(1..=cores));
+                        if cfg!(target_os = "linux") {
+                            let label = if self.affinity_mask == 0 {
+                                "Tous les coeurs".to_string()
+                            } else {
+                                format!("{} coeur(s)", self.affinity_mask.count_ones())
+                            };
+                            ui.menu_button(label, |ui| {
+                                ui.label("Coeurs autorisés :");
+                                if ui.button("Tous").clicked() {
+                                    self.affinity_mask = 0;
+                                }
+                                egui::Grid::new("affinity_grid").show(ui, |ui| {
+                                    for core in 0..cores.min(64) {
+                                        let bit = 1u64 << core;
+                                        let mut allowed = self.affinity_mask == 0 || self.affinity_mask & bit != 0;
+                                        if ui.checkbox(&mut allowed, core.to_string()).changed() {
+                                            let all = if cores >= 64 { u64::MAX } else { (1u64 << cores) - 1 };
+                                            let mask = if self.affinity_mask == 0 { all } else { self.affinity_mask };
+                                            let mask = if allowed { mask | bit } else { mask & !bit };
+                                            // Au moins un coeur ; tous cochés = pas de restriction
+                                            if mask != 0 {
+                                                self.affinity_mask = if mask == all { 0 } else { mask };
+                                            }
+                                        }
+                                        if core % 8 == 7 {
+                                            ui.end_row();
+                                        }
+                                    }
+                                });
+                            }).response.on_hover_text("Laisser des coeurs libres pendant un long traitement");
+                        }
+                    });
+
+                    ui.add_space(10.0);
+
+                    let queue_label = format!("📋 File d'attente ({})", self.queue.pending_count() + self.running.len());
+                    if ui.button(RichText::new(queue_label).size(16.0)).clicked() {
+                        self.show_queue_window = !self.show_queue_window;
+                    }
+                    if ui.button(RichText::new("⏱ Banc d'essai").size(16.0))
+                        .on_hover_text("Comparer plusieurs presets sur la sélection")
+                        .clicked() {
+                        self.show_benchmark = !self.show_benchmark;
+                    }
                 });
             });
 
@@ -897,6 +2565,28 @@ impl MonCompresseurApp {
                     ui.add_space(10.0);
                 }
 
+                if self.mode == Mode::Compress && self.encryption_enabled {
+                    self.show_encryption_options(ui);
+                    ui.add_space(10.0);
+                }
+
+                if self.mode == Mode::Compress && self.sfx_enabled {
+                    self.show_sfx_options(ui);
+                    ui.add_space(10.0);
+                }
+
+                if self.mode == Mode::Extract {
+                    self.show_extract_options(ui);
+                    ui.add_space(10.0);
+                }
+
+                if self.mode == Mode::Compress {
+                    self.show_volume_options(ui);
+                    self.show_recovery_options(ui);
+                    self.show_filter_options(ui);
+                    ui.add_space(10.0);
+                }
+
                 // Action buttons
                 ui.horizontal(|ui| {
                     if ui.add(egui::Button::new(RichText::new("📂 Parcourir...").size(16.0))
@@ -938,7 +2628,10 @@ impl MonCompresseurApp {
                             .and_then(|e| e.to_str())
                             .unwrap_or("arc")))
                         .show_ui(ui, |ui| {
-                            let extensions = ["arc", "bin", "doi", "bbv", "pak", "sfx"];
+                            let mut extensions = self.preset.backend().backend().extensions().to_vec();
+                            if !extensions.contains(&self.preset.extension()) {
+                                extensions.insert(0, self.preset.extension());
+                            }
                             for ext in extensions.iter() {
                                 let target_path = self.output_path.with_extension(ext);
                                 if ui.selectable_value(
@@ -951,11 +2644,41 @@ impl MonCompresseurApp {
                             }
                         });
 
+                    if self.mode == Mode::Compress {
+                        let archive = match self.selected.as_slice() {
+                            [single] if convert::is_archive(single) => Some(single.clone()),
+                            _ => None,
+                        };
+                        if ui.add_enabled(archive.is_some(), egui::Button::new(RichText::new("🔁 Convertir").size(16.0))
+                            .fill(Color32::from_rgb(66, 133, 244))
+                            .min_size(egui::vec2(110.0, 36.0)))
+                            .on_hover_text("Recompresser l'archive sélectionnée avec le preset choisi, vers la destination")
+                            .on_disabled_hover_text("Sélectionnez une seule archive à convertir")
+                            .clicked() {
+                            if let Some(archive) = archive {
+                                self.enqueue_conversion(archive, ctx);
+                            }
+                        }
+                    }
+
+                    if self.mode != Mode::Compress {
+                        let archive = self.selected.first().filter(|p| p.is_file()).cloned();
+                        if ui.add_enabled(archive.is_some(), egui::Button::new(RichText::new("🔍 Contenu").size(16.0))
+                            .fill(Color32::from_rgb(66, 133, 244))
+                            .min_size(egui::vec2(110.0, 36.0)))
+                            .on_hover_text("Afficher le contenu de l'archive sélectionnée")
+                            .clicked() {
+                            if let Some(archive) = archive {
+                                self.open_browser(archive, ctx);
+                            }
+                        }
+                    }
+
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if ui.add(egui::Button::new(RichText::new("▶ Exécuter").size(17.0))
                             .fill(Color32::from_rgb(52, 168, 83))
                             .min_size(egui::vec2(130.0, 38.0)))
-                            .on_hover_text("Lancer la compression ou l'extraction")
+                            .on_hover_text("Lancer la compression, l'extraction ou le test")
                             .clicked() {
                             self.handle_action(ctx);
                         }
@@ -966,73 +2689,457 @@ impl MonCompresseurApp {
 
                 // Ajout: Affichage du processus en cours (commande, progression)
                 if self.is_processing {
+                    let mut to_cancel = None;
+                    let mut to_pause = None;
                     ui.group(|ui| {
-                        ui.horizontal(|ui| {
-                            ui.label(RichText::new("⏳ Processus en cours :").strong().color(Color32::from_rgb(251, 188, 4)));
-                            ui.add(egui::ProgressBar::new(self.progress_value)
-                                .desired_width(200.0)
-                                .show_percentage());
-                        });
+                        for run in &self.running {
+                            ui.horizontal(|ui| {
+                                ui.label(RichText::new(format!("⏳ {} :", run.label)).strong().color(Color32::from_rgb(251, 188, 4)));
+                                ui.add(egui::ProgressBar::new(run.progress_value)
+                                    .desired_width(200.0)
+                                    .show_percentage());
+
+                                if process_tree::supports_pause() {
+                                    let label = if run.is_paused { "▶ Reprendre" } else { "⏸ Pause" };
+                                    if ui.add_enabled(run.pid.is_some(), egui::Button::new(label)).clicked() {
+                                        to_pause = Some(run.job_id);
+                                    }
+                                }
+                                if ui.add(egui::Button::new("⏹ Annuler").fill(Color32::from_rgb(234, 67, 53)))
+                                    .on_hover_text("Arrêter l'opération et supprimer l'archive partielle")
+                                    .clicked() {
+                                    to_cancel = Some(run.job_id);
+                                }
+                                if run.is_paused {
+                                    ui.label(RichText::new("En pause").color(Color32::GRAY));
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                if run.progress.total_bytes > 0 {
+                                    ui.label(format!(
+                                        "Traité : {} / {}",
+                                        format_size(run.progress.processed_bytes),
+                                        format_size(run.progress.total_bytes)
+                                    ));
+                                }
+                                if let Some(remaining) = run.progress.estimated_remaining {
+                                    ui.separator();
+                                    ui.label(format!("Temps restant estimé : {}", format_duration(remaining)));
+                                }
+                            });
+                        }
+                        let pending = self.queue.pending_count();
+                        if pending > 0 {
+                            ui.label(RichText::new(format!("{} tâche(s) en attente", pending)).color(Color32::GRAY));
+                        }
                         // Affiche la dernière commande exécutée si possible
                         if let Some(last_log) = self.log_lines.last() {
                             ui.label(RichText::new(format!("Dernier log : {}", last_log)).color(Color32::GRAY));
                         }
                     });
-                    ui.add_space(10.0);
+                    if let Some(job_id) = to_pause {
+                        self.toggle_pause(job_id);
+                    }
+                    if let Some(job_id) = to_cancel {
+                        self.cancel_job(job_id);
+                    }
+                    ui.add_space(10.0);
+                }
+
+                // Logs avec auto-scroll
+                ui.heading(RichText::new("📝 Journal d'activité").size(19.0).strong().color(Color32::from_rgb(66, 133, 244)));
+                ui.add_space(8.0);
+
+                // Ajout: ScrollArea avec auto-scroll
+                let log_id = ui.make_persistent_id("log_scroll_area");
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .max_height(220.0)
+                    .id_salt(log_id)
+                    .show(ui, |ui| {
+                        let joined = self.log_lines.join("\n");
+                        let mut log_str = joined.as_str();  // Make the binding mutable
+                        let text_edit = egui::TextEdit::multiline(&mut log_str)
+                            .desired_rows(10)
+                            .desired_width(f32::INFINITY)
+                            .font(egui::TextStyle::Monospace)
+                            .interactive(false)
+                            .text_color(Color32::from_rgb(200, 200, 200));
+                        let response = ui.add(text_edit);
+
+                        // Auto-scroll to bottom if new log
+                        if self.log_scroll_to_end {
+                            response.scroll_to_me(Some(egui::Align::BOTTOM));
+                            self.log_scroll_to_end = false;
+                        }
+                    });
+
+                // Notification
+                self.show_notification(ui);
+            });
+
+        // Status bar
+        egui::TopBottomPanel::bottom("status_bar")
+            .frame(Frame::default()
+                .fill(Color32::from_rgb(28, 29, 32))
+                .corner_radius(10.0)
+                .outer_margin(10.0))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                        ui.label(RichText::new("⚡ Mode:").strong().color(Color32::from_rgb(251, 188, 4)));
+                        ui.label(match self.mode {
+                            Mode::Compress => "Compression",
+                            Mode::Extract => "Extraction",
+                            Mode::Test => "Test",
+                        });
+                        ui.separator();
+                        ui.label(RichText::new("📊 Sélection:").strong());
+                        ui.label(format!("{} fichier(s)", self.selected.len()));
+                    });
+                });
+            });
+
+        // Ajouter l'affichage de la fenêtre de hash
+        self.show_hash_window(ctx);
+        self.show_queue_window(ctx);
+        self.show_test_report(ctx);
+        self.show_password_prompt(ctx);
+        self.show_conflict_dialog(ctx);
+        self.show_ram_warning(ctx);
+
+        if self.show_method_window {
+            let mut open = true;
+            egui::Window::new("🔬 Méthode de compression")
+                .open(&mut open)
+                .resizable(true)
+                .default_width(600.0)
+                .show(ctx, |ui| {
+                    ui.label(RichText::new(self.preset.label()).strong());
+                    let flags = self.preset_flags_with_groups().unwrap_or_else(|_| {
+                        self.preset.flags().iter().map(|f| f.to_string()).collect()
+                    });
+                    ui.label(RichText::new(flags.join(" ")).monospace().color(Color32::GRAY));
+                    ui.separator();
+                    if self.preset.backend() == BackendKind::FreeArc {
+                        method::show_analysis(ui, &flags);
+                    } else {
+                        ui.label("L'analyse des méthodes n'est disponible que pour FreeArc.");
+                    }
+                });
+            self.show_method_window = open;
+        }
+
+        if self.show_group_editor {
+            let mut open = true;
+            let preset_flags: Vec<String> = self.preset.flags().iter().map(|f| f.to_string()).collect();
+            let preset_flags = (self.preset.backend() == BackendKind::FreeArc).then_some(preset_flags.as_slice());
+            if self.group_editor.show(ctx, &mut open, &mut self.file_groups, &mut self.groups_enabled, preset_flags) {
+                if let Err(e) = self.file_groups.save(Path::new(GROUPS_FILE)) {
+                    self.log_lines.push(format!("Erreur : impossible de sauvegarder les groupes de fichiers : {}\n", e));
+                }
+            }
+            self.show_group_editor = open;
+        }
+
+        if self.show_benchmark {
+            let mut open = true;
+            let presets: Vec<CompressionPreset> = CompressionPreset::all().iter().cloned()
+                .chain(self.user_presets.iter().cloned().map(CompressionPreset::User))
+                .collect();
+            self.benchmark.show(ctx, &mut open, &presets, &self.selected);
+            self.show_benchmark = open;
+        }
+
+        if self.show_preset_editor {
+            let mut open = true;
+            let builtin_names = CompressionPreset::builtin_names();
+            let current_filter = (self.filters_enabled && !self.filter.is_empty()).then_some(&self.filter);
+            if self.preset_editor.show(ctx, &mut open, &mut self.user_presets, &builtin_names, current_filter) {
+                if let Err(e) = presets::save_user_presets(Path::new(USER_PRESETS_FILE), &self.user_presets) {
+                    self.log_lines.push(format!("Erreur : impossible de sauvegarder les presets : {}\n", e));
+                }
+                // Le preset sélectionné a pu être modifié ou supprimé
+                if let CompressionPreset::User(current) = &self.preset {
+                    self.preset = match self.user_presets.iter().find(|p| p.name == current.name) {
+                        Some(preset) => CompressionPreset::User(preset.clone()),
+                        None => CompressionPreset::Normal,
+                    };
+                }
+            }
+            self.show_preset_editor = open;
+        }
+
+        if let Some(browser) = &mut self.browser {
+            let mut open = true;
+            let action = browser.show(ctx, &mut open);
+            let archive = browser.archive.clone();
+            if !open {
+                self.browser = None;
+            }
+            match action {
+                Some(BrowserAction::ExtractSelection(selection)) => {
+                    if let Some(dest) = self.extraction_destination(&archive, &selection) {
+                        self.enqueue_extraction(archive, dest, selection);
+                        self.run_queue(ctx);
+                    }
+                }
+                Some(BrowserAction::Update { op, sources, selection }) => {
+                    self.enqueue_update(op, archive, sources, selection);
+                    self.run_queue(ctx);
+                }
+                None => {}
+            }
+        }
+    }
+
+    fn show_encryption_options(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.label(RichText::new("🔒 Chiffrement").strong().size(16.0));
+            ui.horizontal(|ui| {
+                ui.label("Algorithme :");
+                egui::ComboBox::new("encryption_algorithm", "")
+                    .selected_text(self.encryption.algorithm.label())
+                    .show_ui(ui, |ui| {
+                        for algorithm in EncryptionAlgorithm::all() {
+                            ui.selectable_value(&mut self.encryption.algorithm, *algorithm, algorithm.label());
+                        }
+                    });
+                if self.encryption.algorithm == EncryptionAlgorithm::Cascade {
+                    ui.add(egui::TextEdit::singleline(&mut self.encryption.cascade)
+                        .hint_text("aes+serpent/cfb+twofish")
+                        .desired_width(220.0));
+                }
+                ui.checkbox(&mut self.encryption.encrypt_headers, "Chiffrer les en-têtes")
+                    .on_hover_text("Masque aussi la liste des fichiers de l'archive");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Mot de passe :");
+                ui.add(egui::TextEdit::singleline(&mut self.password).password(true).desired_width(160.0));
+                ui.label("Confirmation :");
+                ui.add(egui::TextEdit::singleline(&mut self.password_confirm).password(true).desired_width(160.0));
+                if !self.password_confirm.is_empty() && self.password != self.password_confirm {
+                    ui.colored_label(Color32::from_rgb(234, 67, 53), "Les mots de passe ne correspondent pas");
+                }
+            });
+            // Vérification immédiate : algorithme ou en-têtes non supportés par l'outil
+            let backend = self.preset.backend().backend();
+            if let Err(e) = backend.encryption_args(&self.output_path, &self.encryption, "") {
+                ui.colored_label(Color32::from_rgb(251, 188, 4), e);
+            }
+        });
+    }
+
+    fn show_sfx_options(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.label(RichText::new("📦 Archive auto-extractible").strong().size(16.0));
+            let modules = self.preset.backend().backend().sfx_modules();
+            egui::Grid::new("sfx_options").num_columns(2).show(ui, |ui| {
+                ui.label("Module :");
+                ui.horizontal(|ui| {
+                    let name = self.sfx.module.file_name().map_or("(aucun)".into(), |n| n.to_string_lossy());
+                    egui::ComboBox::new("sfx_module", "")
+                        .selected_text(name)
+                        .show_ui(ui, |ui| {
+                            for module in &modules {
+                                let label = module.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                                ui.selectable_value(&mut self.sfx.module, module.clone(), label);
+                            }
+                        });
+                    if ui.button("…").on_hover_text("Choisir un autre module .sfx").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("Module SFX", &["sfx"]).pick_file() {
+                            self.sfx.module = path;
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Dossier d'extraction :");
+                ui.add(egui::TextEdit::singleline(&mut self.sfx.extract_dir).hint_text("%ProgramFiles%\\MonJeu").desired_width(300.0));
+                ui.end_row();
+
+                ui.label("Titre :");
+                ui.add(egui::TextEdit::singleline(&mut self.sfx.title).desired_width(300.0));
+                ui.end_row();
+
+                ui.label("Message :");
+                ui.add(egui::TextEdit::multiline(&mut self.sfx.message).desired_rows(2).desired_width(300.0));
+                ui.end_row();
+
+                ui.label("Fichiers existants :");
+                egui::ComboBox::new("sfx_overwrite", "")
+                    .selected_text(self.sfx.overwrite.label())
+                    .show_ui(ui, |ui| {
+                        for mode in SfxOverwrite::all() {
+                            ui.selectable_value(&mut self.sfx.overwrite, *mode, mode.label());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Commande après extraction :");
+                ui.add(egui::TextEdit::singleline(&mut self.sfx.run_after).hint_text("setup.exe").desired_width(300.0));
+                ui.end_row();
+
+                ui.label("Icône :");
+                ui.horizontal(|ui| {
+                    match &self.sfx.icon {
+                        Some(icon) => ui.label(icon.display().to_string()),
+                        None => ui.label(RichText::new("icône du module").color(Color32::GRAY)),
+                    };
+                    if ui.button("…").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("Icône", &["ico"]).pick_file() {
+                            self.sfx.icon = Some(path);
+                        }
+                    }
+                    if self.sfx.icon.is_some() && ui.small_button("✖").clicked() {
+                        self.sfx.icon = None;
+                    }
+                });
+                ui.end_row();
+            });
+            if let Err(e) = self.sfx.validate() {
+                ui.colored_label(Color32::from_rgb(251, 188, 4), e);
+            }
+        });
+    }
+
+    fn show_volume_options(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.volumes_enabled, RichText::new("✂ Découper en volumes").size(16.0))
+                .on_hover_text("Archive découpée en fichiers .001, .002... (clé FAT32, limite d'envoi)");
+            ui.add_enabled_ui(self.volumes_enabled, |ui| {
+                let selected = volumes::VOLUME_PRESETS.iter()
+                    .find(|(_, _, size)| *size == self.volume_size)
+                    .map_or("Personnalisé", |(label, _, _)| *label);
+                egui::ComboBox::new("volume_size", "")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (label, _, size) in volumes::VOLUME_PRESETS {
+                            ui.selectable_value(&mut self.volume_size, *size, *label);
+                        }
+                    });
+                let mut mb = self.volume_size / (1024 * 1024);
+                if ui.add(egui::DragValue::new(&mut mb).range(1..=u32::MAX as u64).suffix(" Mo")).changed() {
+                    self.volume_size = mb * 1024 * 1024;
+                }
+                if !self.preset.backend().backend().capabilities().volumes {
+                    ui.label(RichText::new("(découpage après compression)").color(Color32::GRAY))
+                        .on_hover_text("L'outil ne découpe pas lui-même : l'archive est créée puis découpée, prévoir l'espace disque");
                 }
+            });
+        });
+    }
 
-                // Logs avec auto-scroll
-                ui.heading(RichText::new("📝 Journal d'activité").size(19.0).strong().color(Color32::from_rgb(66, 133, 244)));
-                ui.add_space(8.0);
+    fn show_recovery_options(&mut self, ui: &mut egui::Ui) {
+        let supported = self.preset.backend().backend().capabilities().recovery;
+        ui.horizontal(|ui| {
+            ui.add_enabled(supported, egui::Checkbox::new(&mut self.recovery_enabled, RichText::new("🛡 Enregistrement de récupération").size(16.0)))
+                .on_hover_text("Données redondantes permettant de réparer l'archive si elle est endommagée")
+                .on_disabled_hover_text("Disponible uniquement avec FreeArc");
+            ui.add_enabled_ui(supported && self.recovery_enabled, |ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.recovery_text).desired_width(70.0))
+                    .on_hover_text("Pourcentage de l'archive (3%) ou taille fixe (512k, 4m)");
+                match RecoverySize::parse(&self.recovery_text) {
+                    Some(size) => ui.label(RichText::new(size.label()).color(Color32::GRAY)),
+                    None => ui.colored_label(Color32::from_rgb(234, 67, 53), "Taille invalide"),
+                };
+            });
+        });
+    }
 
-                // Ajout: ScrollArea avec auto-scroll
-                let log_id = ui.make_persistent_id("log_scroll_area");
-                egui::ScrollArea::vertical()
-                    .auto_shrink([false; 2])
-                    .max_height(220.0)
-                    .id_salt(log_id)
-                    .show(ui, |ui| {
-                        let joined = self.log_lines.join("\n");
-                        let mut log_str = joined.as_str();  // Make the binding mutable
-                        let text_edit = egui::TextEdit::multiline(&mut log_str)
-                            .desired_rows(10)
-                            .desired_width(f32::INFINITY)
-                            .font(egui::TextStyle::Monospace)
-                            .interactive(false)
-                            .text_color(Color32::from_rgb(200, 200, 200));
-                        let response = ui.add(text_edit);
+    fn show_extract_options(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("📁 Destination :").size(16.0))
+                .on_hover_text("Intelligent : crée un dossier au nom de l'archive seulement si elle contient plusieurs éléments à la racine");
+            egui::ComboBox::new("extract_mode", "")
+                .selected_text(self.extract_mode.label())
+                .show_ui(ui, |ui| {
+                    for mode in ExtractMode::all() {
+                        ui.selectable_value(&mut self.extract_mode, *mode, mode.label());
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("📄 Fichiers existants :").size(16.0))
+                .on_hover_text("Que faire des fichiers déjà présents dans le dossier d'extraction");
+            egui::ComboBox::new("overwrite_policy", "")
+                .selected_text(self.overwrite_policy.label())
+                .show_ui(ui, |ui| {
+                    for policy in OverwritePolicy::all() {
+                        ui.selectable_value(&mut self.overwrite_policy, *policy, policy.label());
+                    }
+                });
+        });
+    }
 
-                        // Auto-scroll to bottom if new log
-                        if self.log_scroll_to_end {
-                            response.scroll_to_me(Some(egui::Align::BOTTOM));
-                            self.log_scroll_to_end = false;
-                        }
-                    });
+    fn show_filter_options(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.filters_enabled, RichText::new("🔎 Filtrer les fichiers").size(16.0))
+            .on_hover_text("Inclure ou exclure des fichiers de la sélection selon leur nom, taille ou date");
+        if !self.filters_enabled {
+            return;
+        }
+        ui.group(|ui| {
+            egui::Grid::new("filter_grid").num_columns(2).show(ui, |ui| {
+                ui.label("Inclure :");
+                ui.add(egui::TextEdit::singleline(&mut self.filter.include).hint_text("*.rs; docs/**").desired_width(280.0));
+                ui.end_row();
 
-                // Notification
-                self.show_notification(ui);
-            });
+                ui.label("Exclure :");
+                ui.add(egui::TextEdit::singleline(&mut self.filter.exclude).hint_text("target; .git; *.tmp").desired_width(280.0));
+                ui.end_row();
 
-        // Status bar
-        egui::TopBottomPanel::bottom("status_bar")
-            .frame(Frame::default()
-                .fill(Color32::from_rgb(28, 29, 32))
-                .corner_radius(10.0)
-                .outer_margin(10.0))
-            .show(ctx, |ui| {
+                ui.label("Extensions :");
+                ui.add(egui::TextEdit::singleline(&mut self.filter.extensions).hint_text("jpg, png").desired_width(280.0));
+                ui.end_row();
+
+                ui.label("Taille :");
                 ui.horizontal(|ui| {
-                    ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                        ui.label(RichText::new("⚡ Mode:").strong().color(Color32::from_rgb(251, 188, 4)));
-                        ui.label(if self.mode_compress {"Compression"} else {"Extraction"});
-                        ui.separator();
-                        ui.label(RichText::new("📊 Sélection:").strong());
-                        ui.label(format!("{} fichier(s)", self.selected.len()));
-                    });
+                    for (label, size) in [("min", &mut self.filter.min_size), ("max", &mut self.filter.max_size)] {
+                        let mut enabled = size.is_some();
+                        if ui.checkbox(&mut enabled, label).changed() {
+                            *size = enabled.then_some(1024 * 1024);
+                        }
+                        if let Some(bytes) = size {
+                            let mut kb = *bytes / 1024;
+                            if ui.add(egui::DragValue::new(&mut kb).speed(64).suffix(" Ko")).changed() {
+                                *bytes = kb * 1024;
+                            }
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Modifié :");
+                ui.horizontal(|ui| {
+                    ui.label("après le");
+                    ui.add(egui::TextEdit::singleline(&mut self.filter.modified_after).hint_text("AAAA-MM-JJ").desired_width(90.0));
+                    ui.label("avant le");
+                    ui.add(egui::TextEdit::singleline(&mut self.filter.modified_before).hint_text("AAAA-MM-JJ").desired_width(90.0));
                 });
+                ui.end_row();
             });
+            ui.checkbox(&mut self.filter.exclude_hidden, "Exclure les fichiers et dossiers cachés");
 
-        // Ajouter l'affichage de la fenêtre de hash
-        self.show_hash_window(ctx);
+            // Aperçu du résultat
+            if let Err(e) = self.filter.validate() {
+                ui.colored_label(Color32::from_rgb(234, 67, 53), e);
+            } else if self.selected.is_empty() {
+                ui.label(RichText::new("Sélectionnez des fichiers pour voir l'aperçu").color(Color32::GRAY));
+            } else if let Some(preview) = &self.filter_preview {
+                let color = if preview.files.is_empty() { Color32::from_rgb(234, 67, 53) } else { Color32::from_rgb(52, 168, 83) };
+                ui.colored_label(color, format!(
+                    "Aperçu : {} fichier(s), {} ({} écarté(s))",
+                    preview.files.len(),
+                    format_size(preview.total_size),
+                    preview.excluded
+                ));
+            } else {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Calcul de l'aperçu...");
+                });
+            }
+        });
     }
 
     fn show_preview(&mut self, ui: &mut egui::Ui) {
@@ -1073,19 +3180,68 @@ impl MonCompresseurApp {
         }
     }
 
-    fn update_stats(&mut self) {
-        let stats = FileStats { // Removed mut as it's not needed
-            total_size: 0,
-            file_count: 0,
-            largest_file: (PathBuf::new(), 0),
-            by_extension: HashMap::new(),
-        };
+    /// Analyse la sélection en arrière-plan quand elle change et met à jour la recommandation
+    fn update_stats(&mut self, ctx: &egui::Context) {
+        if let Some(rx) = &self.stats_rx {
+            if let Ok((stats, recommendation)) = rx.try_recv() {
+                self.current_stats = Some(stats);
+                self.recommendation = recommendation;
+                self.stats_rx = None;
+            }
+        }
+
+        if self.mode != Mode::Compress || self.selected == self.stats_selection {
+            return;
+        }
+        self.stats_selection = self.selected.clone();
+        self.current_stats = None;
+        self.recommendation = None;
+        self.stats_rx = None; // Résultat d'une analyse précédente ignoré
+        if self.selected.is_empty() {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let paths = self.selected.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let stats = recommend::scan(&paths);
+            let recommendation = recommend::recommend(&stats);
+            tx.send((stats, recommendation)).ok();
+            ctx.request_repaint();
+        });
+        self.stats_rx = Some(rx);
+    }
+
+    /// Recalcule en arrière-plan le nombre de fichiers retenus quand la sélection ou les filtres changent
+    fn update_filter_preview(&mut self, ctx: &egui::Context) {
+        if let Some(rx) = &self.filter_preview_rx {
+            if let Ok(preview) = rx.try_recv() {
+                self.filter_preview = Some(preview);
+                self.filter_preview_rx = None;
+            }
+        }
 
-        for _path in &self.selected { // Added underscore to unused variable
-            // Calculer les statistiques...
+        if self.mode != Mode::Compress || !self.filters_enabled || self.selected.is_empty() {
+            self.filter_preview_key = None;
+            return;
+        }
+        let key = (self.selected.clone(), self.filter.clone());
+        if self.filter_preview_key.as_ref() == Some(&key) {
+            return;
         }
+        self.filter_preview_key = Some(key);
+        self.filter_preview = None;
 
-        self.current_stats = Some(stats);
+        let (tx, rx) = mpsc::channel();
+        let paths = self.selected.clone();
+        let filter = self.filter.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            tx.send(filter.collect(&paths)).ok();
+            ctx.request_repaint();
+        });
+        self.filter_preview_rx = Some(rx); // Un aperçu précédent encore en cours est ignoré
     }
 
     fn apply_theme(&self, ctx: &egui::Context, theme: &Theme) {
@@ -1128,88 +3284,505 @@ impl MonCompresseurApp {
                 let crc = crc32fast::hash(&data);
                 Ok((HashType::CRC32, format!("{:08X}", crc)))
             }
-            Err(e) => Err(format!("Erreur lors de la lecture du fichier: {}", e))
+            Err(e) => Err(format!("Erreur lors de la lecture du fichier: {}", e))
+        }
+    }
+
+    fn show_hash_window(&mut self, ctx: &egui::Context) {
+        if self.show_hash_window {
+            let mut show = true;
+            egui::Window::new("Calculateur de Hash")
+                .open(&mut show)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.heading("Sélectionnez le type de hash");
+
+                    ui.horizontal(|ui| {
+                        if ui.selectable_label(self.selected_hash_type == HashType::CRC32, "CRC32").clicked() {
+                            self.selected_hash_type = HashType::CRC32;
+                        }
+                        if ui.selectable_label(self.selected_hash_type == HashType::Blake3, "BLAKE3").clicked() {
+                            self.selected_hash_type = HashType::Blake3;
+                        }
+                        if ui.selectable_label(self.selected_hash_type == HashType::MD5, "MD5").clicked() {
+                            self.selected_hash_type = HashType::MD5;
+                        }
+                        if ui.selectable_label(self.selected_hash_type == HashType::SHA256, "SHA-256").clicked() {
+                            self.selected_hash_type = HashType::SHA256;
+                        }
+                        if ui.selectable_label(self.selected_hash_type == HashType::SHA3_256, "SHA3-256").clicked() {
+                            self.selected_hash_type = HashType::SHA3_256;
+                        }
+                    });
+
+                    ui.add_space(10.0);
+
+                    let selected_file = self.selected.first().cloned();
+                    if let Some(file_path) = selected_file {
+                        ui.label(format!("Fichier sélectionné : {}", file_path.display()));
+                        if ui.button("Ajouter à la file (tous les hash)").clicked() {
+                            self.enqueue(JobKind::Hash, vec![file_path.clone()], PathBuf::new());
+                        }
+                        if ui.button("Calculer le hash").clicked() {
+                            match Self::calculate_file_hash(&file_path) {
+                                Ok((hash_type, hash)) => {
+                                    self.hash_result = Some(hash);
+                                    self.notification = Some(Notification {
+                                        message: format!("Hash {} calculé avec succès", hash_type.label()),
+                                        level: NotificationLevel::Success,
+                                        timestamp: std::time::Instant::now(),
+                                    });
+                                }
+                                Err(e) => {
+                                    self.notification = Some(Notification {
+                                        message: e,
+                                        level: NotificationLevel::Error,
+                                        timestamp: std::time::Instant::now(),
+                                    });
+                                }
+                            }
+                        }
+                    } else {
+                        ui.label("Aucun fichier sélectionné");
+                    }
+
+                    if let Some(hash) = &self.hash_result {
+                        ui.add_space(10.0);
+                        ui.group(|ui| {
+                            ui.label(format!("{} :", self.selected_hash_type.label()));
+                            let _text_response = ui.add(
+                                egui::TextEdit::singleline(&mut hash.as_str())
+                                    .desired_width(f32::INFINITY)
+                                    .font(egui::TextStyle::Monospace)
+                            );
+
+                            if ui.button("Copier").clicked() {
+                                ctx.copy_text(hash.clone());
+                                self.notification = Some(Notification {
+                                    message: "Hash copié dans le presse-papiers".to_string(),
+                                    level: NotificationLevel::Info,
+                                    timestamp: std::time::Instant::now(),
+                                });
+                            }
+                        });
+                    }
+                });
+            self.show_hash_window = show;
+        }
+    }
+
+    fn show_queue_window(&mut self, ctx: &egui::Context) {
+        if !self.show_queue_window {
+            return;
+        }
+
+        enum QueueAction {
+            Cancel(u64),
+            Remove(u64),
+            Retry(u64),
+            Move(u64, isize),
+        }
+
+        let mut show = true;
+        let mut action = None;
+        let mut changed = false;
+        egui::Window::new("File d'attente")
+            .open(&mut show)
+            .resizable(true)
+            .default_width(620.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let max_threads = self.sys.cpus().len().max(1);
+                    ui.label("Tâches simultanées :");
+                    changed |= ui.add(egui::DragValue::new(&mut self.queue.max_concurrent).range(1..=max_threads)).changed();
+
+                    let (label, hover) = if self.queue.active {
+                        ("⏸ Suspendre la file", "Les tâches en cours continuent, les suivantes attendent")
+                    } else {
+                        ("▶ Démarrer la file", "Lancer les tâches en attente")
+                    };
+                    if ui.button(label).on_hover_text(hover).clicked() {
+                        self.queue.active = !self.queue.active;
+                    }
+                    if ui.button("🧹 Retirer les terminées").clicked() {
+                        self.queue.clear_finished();
+                        changed = true;
+                    }
+                });
+                ui.separator();
+
+                if self.queue.jobs.is_empty() {
+                    ui.label("Aucune tâche");
+                    return;
+                }
+
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    egui::Grid::new("queue_grid").striped(true).show(ui, |ui| {
+                        ui.label(RichText::new("#").strong());
+                        ui.label(RichText::new("Type").strong());
+                        ui.label(RichText::new("Détails").strong());
+                        ui.label(RichText::new("Statut").strong());
+                        ui.label("");
+                        ui.end_row();
+
+                        for job in &self.queue.jobs {
+                            ui.label(job.id.to_string());
+                            ui.label(job.kind.label());
+                            ui.label(job.description()).on_hover_text(job.preset.label());
+
+                            let status_color = match job.status {
+                                JobStatus::Pending => Color32::GRAY,
+                                JobStatus::Running => Color32::from_rgb(251, 188, 4),
+                                JobStatus::Done => Color32::from_rgb(52, 168, 83),
+                                JobStatus::Failed(_) => Color32::from_rgb(234, 67, 53),
+                                JobStatus::Cancelled => Color32::from_rgb(180, 180, 180),
+                            };
+                            match self.running.iter().find(|r| r.job_id == job.id) {
+                                Some(run) => {
+                                    ui.add(egui::ProgressBar::new(run.progress_value).desired_width(120.0).show_percentage());
+                                }
+                                None => {
+                                    ui.colored_label(status_color, job.status.label());
+                                }
+                            }
+
+                            ui.horizontal(|ui| {
+                                match job.status {
+                                    JobStatus::Running => {
+                                        if ui.small_button("⏹").on_hover_text("Annuler").clicked() {
+                                            action = Some(QueueAction::Cancel(job.id));
+                                        }
+                                    }
+                                    JobStatus::Pending => {
+                                        if ui.small_button("⬆").clicked() {
+                                            action = Some(QueueAction::Move(job.id, -1));
+                                        }
+                                        if ui.small_button("⬇").clicked() {
+                                            action = Some(QueueAction::Move(job.id, 1));
+                                        }
+                                        if ui.small_button("✖").on_hover_text("Retirer").clicked() {
+                                            action = Some(QueueAction::Remove(job.id));
+                                        }
+                                    }
+                                    _ => {
+                                        if ui.small_button("↻").on_hover_text("Relancer").clicked() {
+                                            action = Some(QueueAction::Retry(job.id));
+                                        }
+                                        if ui.small_button("✖").on_hover_text("Retirer").clicked() {
+                                            action = Some(QueueAction::Remove(job.id));
+                                        }
+                                    }
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.show_queue_window = show;
+
+        match action {
+            Some(QueueAction::Cancel(id)) => self.cancel_job(id),
+            Some(QueueAction::Remove(id)) => {
+                self.queue.remove(id);
+                changed = true;
+            }
+            Some(QueueAction::Retry(id)) => {
+                self.queue.set_status(id, JobStatus::Pending);
+                self.queue.active = true;
+                changed = true;
+            }
+            Some(QueueAction::Move(id, offset)) => {
+                self.queue.move_job(id, offset);
+                changed = true;
+            }
+            None => {}
+        }
+        if changed {
+            self.save_queue();
+        }
+    }
+
+    fn show_test_report(&mut self, ctx: &egui::Context) {
+        let Some(report) = &self.test_report else { return };
+
+        let mut open = true;
+        let mut only_errors = ctx.data(|d| d.get_temp::<bool>(egui::Id::new("test_report_errors")).unwrap_or(false));
+        let mut repair = false;
+        egui::Window::new("Rapport de test")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.label(RichText::new(report.archive.display().to_string()).strong());
+                let color = if report.success && report.count(&TestOutcome::Failed(String::new())) == 0 {
+                    Color32::from_rgb(52, 168, 83)
+                } else {
+                    Color32::from_rgb(234, 67, 53)
+                };
+                ui.colored_label(color, report.summary());
+                for error in &report.archive_errors {
+                    ui.colored_label(Color32::from_rgb(234, 67, 53), format!("❌ {}", error));
+                }
+                let damaged = !report.success || report.count(&TestOutcome::Failed(String::new())) > 0;
+                if damaged && BackendKind::for_archive(&report.archive).backend().capabilities().recovery {
+                    repair = ui.button("🛠 Réparer")
+                        .on_hover_text("Reconstruire les données endommagées à partir de l'enregistrement de récupération")
+                        .clicked();
+                }
+                ui.checkbox(&mut only_errors, "Afficher uniquement les erreurs");
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    egui::Grid::new("test_report_grid").striped(true).show(ui, |ui| {
+                        ui.label(RichText::new("Statut").strong());
+                        ui.label(RichText::new("Fichier").strong());
+                        ui.label(RichText::new("Détails").strong());
+                        ui.end_row();
+
+                        for (path, outcome) in &report.results {
+                            let (icon, color, details) = match outcome {
+                                TestOutcome::Passed if only_errors => continue,
+                                TestOutcome::Passed => ("✔ OK", Color32::from_rgb(52, 168, 83), ""),
+                                TestOutcome::Failed(message) => ("❌ Erreur", Color32::from_rgb(234, 67, 53), message.as_str()),
+                                TestOutcome::Unverified => ("? Non vérifié", Color32::GRAY, ""),
+                            };
+                            ui.colored_label(color, icon);
+                            ui.label(path);
+                            ui.label(details);
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        ctx.data_mut(|d| d.insert_temp(egui::Id::new("test_report_errors"), only_errors));
+        if repair {
+            let archive = report.archive.clone();
+            self.enqueue_archive_job(JobKind::Repair, archive.clone(), archive, Vec::new());
+            self.run_queue(ctx);
+        }
+        if !open {
+            self.test_report = None;
+        }
+    }
+
+    fn show_password_prompt(&mut self, ctx: &egui::Context) {
+        let Some(prompt) = &mut self.password_prompt else { return };
+
+        let mut open = true;
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new("🔒 Mot de passe requis")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("L'archive {} est chiffrée.", prompt.archive.display()));
+                ui.colored_label(Color32::from_rgb(234, 67, 53), "Mot de passe manquant ou incorrect.");
+                let response = ui.add(egui::TextEdit::singleline(&mut prompt.password)
+                    .password(true)
+                    .hint_text("Mot de passe"));
+                response.request_focus();
+                let enter = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!prompt.password.is_empty(), egui::Button::new("OK")).clicked()
+                        || (enter && !prompt.password.is_empty())
+                    {
+                        confirmed = true;
+                    }
+                    if ui.button("Annuler").clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+
+        if confirmed {
+            let Some(prompt) = self.password_prompt.take() else { return };
+            self.queue.set_status(prompt.retry_of, JobStatus::Pending);
+            self.queue.active = true;
+            if let Some(job) = self.queue.get_mut(prompt.retry_of) {
+                job.password = Some(prompt.password);
+            }
+            self.save_queue();
+            self.run_queue(ctx);
+        } else if cancelled || !open {
+            self.password_prompt = None;
         }
     }
 
-    fn show_hash_window(&mut self, ctx: &egui::Context) {
-        if self.show_hash_window {
-            let mut show = true;
-            egui::Window::new("Calculateur de Hash")
-                .open(&mut show)
-                .resizable(false)
-                .show(ctx, |ui| {
-                    ui.heading("Sélectionnez le type de hash");
+    /// Fichiers de l'archive déjà présents dans la destination : taille et date des deux
+    /// versions, et décision pour chacun avant de lancer l'extraction
+    fn show_conflict_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.conflict_dialog else { return };
 
-                    ui.horizontal(|ui| {
-                        if ui.selectable_label(self.selected_hash_type == HashType::CRC32, "CRC32").clicked() {
-                            self.selected_hash_type = HashType::CRC32;
-                        }
-                        if ui.selectable_label(self.selected_hash_type == HashType::Blake3, "BLAKE3").clicked() {
-                            self.selected_hash_type = HashType::Blake3;
-                        }
-                        if ui.selectable_label(self.selected_hash_type == HashType::MD5, "MD5").clicked() {
-                            self.selected_hash_type = HashType::MD5;
-                        }
-                        if ui.selectable_label(self.selected_hash_type == HashType::SHA256, "SHA-256").clicked() {
-                            self.selected_hash_type = HashType::SHA256;
+        let mut open = true;
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new("⚠ Fichiers déjà présents")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(720.0)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} fichier(s) de l'archive existent déjà dans {}",
+                    dialog.conflicts.len(),
+                    dialog.dest.display()
+                ));
+                ui.horizontal(|ui| {
+                    ui.label("Pour tous :");
+                    for resolution in [Resolution::Overwrite, Resolution::Skip, Resolution::Rename] {
+                        if ui.button(resolution.label()).clicked() {
+                            for conflict in &mut dialog.conflicts {
+                                conflict.resolution = resolution;
+                            }
                         }
-                        if ui.selectable_label(self.selected_hash_type == HashType::SHA3_256, "SHA3-256").clicked() {
-                            self.selected_hash_type = HashType::SHA3_256;
+                    }
+                    if ui.button("Plus récents").on_hover_text("Remplacer seulement par les fichiers plus récents de l'archive").clicked() {
+                        for conflict in &mut dialog.conflicts {
+                            conflict.resolution = if conflict.archive_newer == Some(true) {
+                                Resolution::Overwrite
+                            } else {
+                                Resolution::Skip
+                            };
                         }
-                    });
-
-                    ui.add_space(10.0);
-
-                    let selected_file = self.selected.first().cloned();
-                    if let Some(file_path) = selected_file {
-                        ui.label(format!("Fichier sélectionné : {}", file_path.display()));
-                        if ui.button("Calculer le hash").clicked() {
-                            match Self::calculate_file_hash(&file_path) {
-                                Ok((hash_type, hash)) => {
-                                    self.hash_result = Some(hash);
-                                    self.notification = Some(Notification {
-                                        message: format!("Hash {} calculé avec succès", hash_type.label()),
-                                        level: NotificationLevel::Success,
-                                        timestamp: std::time::Instant::now(),
-                                    });
-                                }
-                                Err(e) => {
-                                    self.notification = Some(Notification {
-                                        message: e,
-                                        level: NotificationLevel::Error,
-                                        timestamp: std::time::Instant::now(),
-                                    });
-                                }
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    egui::Grid::new("conflict_grid").num_columns(4).striped(true).show(ui, |ui| {
+                        ui.strong("Fichier");
+                        ui.strong("Dans l'archive");
+                        ui.strong("Existant");
+                        ui.strong("Action");
+                        ui.end_row();
+                        for conflict in &mut dialog.conflicts {
+                            ui.label(&conflict.entry);
+                            let newer = conflict.archive_newer == Some(true);
+                            let archive_text = format!("{}  {}", format_size(conflict.archive_size), conflict.archive_modified);
+                            let existing_text = format!("{}  {}", format_size(conflict.existing_size), conflict.existing_modified);
+                            if newer {
+                                ui.label(RichText::new(archive_text).strong());
+                                ui.label(existing_text);
+                            } else {
+                                ui.label(archive_text);
+                                ui.label(RichText::new(existing_text).strong());
                             }
+                            ui.horizontal(|ui| {
+                                for resolution in [Resolution::Overwrite, Resolution::Skip, Resolution::Rename] {
+                                    ui.selectable_value(&mut conflict.resolution, resolution, resolution.label());
+                                }
+                            });
+                            ui.end_row();
                         }
-                    } else {
-                        ui.label("Aucun fichier sélectionné");
+                    });
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Extraire").clicked() {
+                        confirmed = true;
+                    }
+                    if ui.button("Annuler").clicked() {
+                        cancelled = true;
                     }
+                });
+            });
 
-                    if let Some(hash) = &self.hash_result {
-                        ui.add_space(10.0);
-                        ui.group(|ui| {
-                            ui.label(format!("{} :", self.selected_hash_type.label()));
-                            let _text_response = ui.add(
-                                egui::TextEdit::singleline(&mut hash.as_str())
-                                    .desired_width(f32::INFINITY)
-                                    .font(egui::TextStyle::Monospace)
-                            );
+        if confirmed {
+            let Some(dialog) = self.conflict_dialog.take() else { return };
+            let resolutions: Vec<(String, Resolution)> = dialog.conflicts.into_iter().map(|c| (c.entry, c.resolution)).collect();
+            // Destination figée : le mode intelligent a déjà choisi le dossier comparé
+            if let Some(job) = self.queue.get_mut(dialog.job_id) {
+                job.output = dialog.dest;
+                job.smart_destination = false;
+                job.resolutions = resolutions;
+            }
+            self.queue.set_status(dialog.job_id, JobStatus::Pending);
+            self.queue.active = true;
+            self.save_queue();
+            self.run_queue(ctx);
+        } else if cancelled || !open {
+            if let Some(dialog) = self.conflict_dialog.take() {
+                self.log_lines.push(format!("Extraction annulée (tâche #{})\n", dialog.job_id));
+            }
+        }
+    }
 
-                            if ui.button("Copier").clicked() {
-                                ctx.copy_text(hash.clone());
-                                self.notification = Some(Notification {
-                                    message: "Hash copié dans le presse-papiers".to_string(),
-                                    level: NotificationLevel::Info,
-                                    timestamp: std::time::Instant::now(),
-                                });
-                            }
-                        });
+    fn show_ram_warning(&mut self, ctx: &egui::Context) {
+        let Some(warning) = &self.ram_warning else { return };
+
+        enum Choice {
+            Downgrade,
+            Force,
+            Cancel,
+        }
+        let mut open = true;
+        let mut choice = None;
+        egui::Window::new("⚠ Mémoire insuffisante")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("Le preset « {} » risque de saturer la mémoire.", self.preset.label()));
+                egui::Grid::new("ram_warning_grid").num_columns(2).show(ui, |ui| {
+                    ui.label("Compression :");
+                    ui.label(format!("≈ {} ({} threads)", format_size(warning.compression), warning.threads));
+                    ui.end_row();
+                    ui.label("Décompression :");
+                    ui.label(format!("≈ {}", format_size(warning.decompression)));
+                    ui.end_row();
+                    ui.label("Mémoire disponible :");
+                    ui.label(format!("{} sur {}", format_size(warning.available), format_size(warning.total)));
+                    ui.end_row();
+                });
+                if warning.decompression > warning.total {
+                    ui.colored_label(Color32::from_rgb(251, 188, 4), "L'archive ne pourra pas être extraite sur cette machine.");
+                }
+                let blocked = warning.compression > warning.total;
+                if blocked {
+                    ui.colored_label(Color32::from_rgb(234, 67, 53), "La compression dépasse la mémoire totale : lancement impossible.");
+                }
+                if let Some(flags) = &warning.downgraded {
+                    ui.separator();
+                    ui.label("Options allégées proposées :");
+                    ui.label(RichText::new(flags.join(" ")).monospace().small());
+                } else if self.preset.backend() == BackendKind::FreeArc {
+                    ui.label(RichText::new("Aucune réduction des dictionnaires ne suffit pour ce preset.").color(Color32::GRAY));
+                } else {
+                    ui.label(RichText::new("La réduction automatique des dictionnaires n'existe que pour les presets FreeArc.").color(Color32::GRAY));
+                }
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(warning.downgraded.is_some(), egui::Button::new("📉 Réduire automatiquement les dictionnaires")).clicked() {
+                        choice = Some(Choice::Downgrade);
+                    }
+                    if ui.add_enabled(!blocked, egui::Button::new("Lancer quand même")).clicked() {
+                        choice = Some(Choice::Force);
+                    }
+                    if ui.button("Annuler").clicked() {
+                        choice = Some(Choice::Cancel);
                     }
                 });
-            self.show_hash_window = show;
+            });
+
+        if !open {
+            choice = Some(Choice::Cancel);
         }
+        let Some(choice) = choice else { return };
+        let Some(warning) = self.ram_warning.take() else { return };
+        let preset = match choice {
+            Choice::Cancel => return,
+            Choice::Force => self.preset.clone(),
+            // Preset temporaire : le preset choisi reste intact
+            Choice::Downgrade => CompressionPreset::User(UserPreset {
+                name: format!("{} (réduit)", self.preset.label().trim()),
+                backend: BackendKind::FreeArc,
+                flags: warning.downgraded.unwrap_or_default(),
+                description: String::new(),
+                extension: self.preset.extension().to_string(),
+                filter: None,
+            }),
+        };
+        self.enqueue_compression(preset, warning.encryption, warning.password);
+        self.run_queue(ctx);
     }
 
     fn show_context_menu(&mut self, ui: &mut egui::Ui, path: &Path) {
@@ -1238,6 +3811,98 @@ impl eframe::App for MonCompresseurApp {
     }
 }
 
+/// Lance une commande d'archivage en mode console (sortie affichée directement)
+fn run_cli_command(mut cmd: Command, action: &str) -> bool {
+    match cmd.status() {
+        Ok(status) if status.success() => true,
+        Ok(status) => {
+            eprintln!("Erreur lors de {} (code {:?})", action, status.code());
+            false
+        }
+        Err(e) => {
+            eprintln!("Erreur lors de l'exécution de la commande : {}", e);
+            false
+        }
+    }
+}
+
+/// --add <archive> <fichiers...> | --freshen <archive> [dossier] | --delete <archive> <entrées...>
+/// | --rename <archive> <ancien> <nouveau>
+fn run_update_cli(command: &str, args: &[String]) -> bool {
+    let Some(archive) = args.first().map(PathBuf::from) else {
+        eprintln!("Erreur : Aucune archive fournie.");
+        return false;
+    };
+    let archive = match fs::canonicalize(&archive) {
+        Ok(path) => path,
+        Err(_) => {
+            eprintln!("Erreur : L'archive n'existe pas");
+            return false;
+        }
+    };
+    let backend = BackendKind::for_archive(&archive).backend();
+    if !backend.is_available() {
+        eprintln!("Erreur : {} n'est pas installé correctement", backend.name());
+        return false;
+    }
+    let rest = &args[1..];
+    let (op, sources, selection, work_dir) = match command {
+        "--add" if !rest.is_empty() => {
+            let sources: Vec<PathBuf> = rest.iter().map(PathBuf::from).collect();
+            (UpdateOp::Add, sources, Vec::new(), None)
+        }
+        "--freshen" => {
+            // Dossier des fichiers d'origine : celui de l'archive par défaut
+            let dir = rest.first().map(PathBuf::from)
+                .unwrap_or_else(|| archive.parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from(".")));
+            let entries = match listing::list_archive(&archive) {
+                Ok(entries) => entries.into_iter().filter(|e| !e.is_dir).map(|e| e.path).collect(),
+                Err(e) => {
+                    eprintln!("Erreur : {}", e);
+                    return false;
+                }
+            };
+            (UpdateOp::Freshen, Vec::new(), entries, Some(dir))
+        }
+        "--delete" if !rest.is_empty() => (UpdateOp::Delete, Vec::new(), rest.to_vec(), None),
+        "--rename" if rest.len() == 2 => {
+            (UpdateOp::Rename { from: rest[0].clone(), to: rest[1].clone() }, Vec::new(), Vec::new(), None)
+        }
+        _ => {
+            eprintln!("Erreur : arguments manquants pour {}", command);
+            return false;
+        }
+    };
+    let job_kind = JobKind::Update(op.clone());
+
+    let list_file = if selection.is_empty() {
+        None
+    } else {
+        match listing::write_list_file(&selection, std::process::id() as u64) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                eprintln!("Erreur : {}", e);
+                return false;
+            }
+        }
+    };
+    let Some(mut cmd) = backend.update_command(&archive, &op, &sources, list_file.as_deref(), &[]) else {
+        eprintln!("Erreur : {} ne permet pas {}", backend.name(), job_kind.action());
+        return false;
+    };
+    if let Some(dir) = work_dir {
+        cmd.current_dir(dir);
+    }
+    let success = run_cli_command(cmd, job_kind.action());
+    if let Some(path) = list_file {
+        let _ = fs::remove_file(path);
+    }
+    if success {
+        println!("{} réussi : {}", job_kind.label(), archive.display());
+    }
+    success
+}
+
 fn main() -> Result<(), eframe::Error> {
     let args: Vec<String> = std::env::args().collect();
 
@@ -1317,24 +3982,150 @@ fn main() -> Result<(), eframe::Error> {
                     let path = &args[2];
                     println!("Compression demandée pour : {}", path);
 
-                    // Exemple de logique de compression
-                    let output_path = format!("{}.arc", path);
-                    let mut cmd = Command::new(".\\FreeArc\\bin\\arc.exe");
-                    cmd.args(&["a", &output_path, path]);
+                    // Options : --preset <Nom> (ex: XtoolF, SevenZipUltra, ou un preset utilisateur)
+                    // --volume <taille> (700m, 4g, ou cd, fat32, dvd, bluray), --recovery <taille> (3%, 4m)
+                    // et --include / --exclude <motifs> (filtres, en plus de ceux du preset)
+                    let mut preset = CompressionPreset::Normal;
+                    let mut volume_size = None;
+                    let mut recovery = None;
+                    let mut filter = SourceFilter::default();
+                    let mut options = args[3..].iter();
+                    while let Some(option) = options.next() {
+                        match (option.as_str(), options.next()) {
+                            ("--preset", Some(name)) => {
+                                let user_presets = presets::load_user_presets(Path::new(USER_PRESETS_FILE));
+                                match CompressionPreset::from_name(name, &user_presets) {
+                                    Some(found) => preset = found,
+                                    None => {
+                                        eprintln!("Preset non reconnu : {}", name);
+                                        return Ok(());
+                                    }
+                                }
+                            }
+                            ("--volume", Some(size)) => match volumes::parse_volume_size(size) {
+                                Some(size) => volume_size = Some(size),
+                                None => {
+                                    eprintln!("Taille de volume invalide : {}", size);
+                                    return Ok(());
+                                }
+                            },
+                            ("--include", Some(patterns)) => filter.include = patterns.clone(),
+                            ("--exclude", Some(patterns)) => filter.exclude = patterns.clone(),
+                            ("--recovery", Some(size)) => match RecoverySize::parse(size) {
+                                Some(size) => recovery = Some(size),
+                                None => {
+                                    eprintln!("Taille d'enregistrement de récupération invalide : {}", size);
+                                    return Ok(());
+                                }
+                            },
+                            _ => {
+                                eprintln!("Option inconnue : {}", option);
+                                return Ok(());
+                            }
+                        }
+                    }
+
+                    let backend = preset.backend().backend();
+                    if !backend.is_available() {
+                        eprintln!("Erreur : {} n'est pas installé correctement", backend.name());
+                        return Ok(());
+                    }
 
-                    match cmd.output() {
-                        Ok(output) => {
-                            if output.status.success() {
-                                println!("Compression réussie : {}", output_path);
-                            } else {
-                                eprintln!(
-                                    "Erreur lors de la compression : {}",
-                                    String::from_utf8_lossy(&output.stderr)
-                                );
+                    let output_path = PathBuf::from(format!("{}.{}", path, preset.extension()));
+                    let mut flags: Vec<String> = preset.flags().iter().map(|f| f.to_string()).collect();
+                    // Groupes de fichiers enregistrés depuis l'interface
+                    if Path::new(GROUPS_FILE).exists() && backend.groups_args(Path::new("")).is_some() {
+                        let groups = FileGroups::load(Path::new(GROUPS_FILE));
+                        let groups_args = groups
+                            .compression_flags(&flags)
+                            .and_then(|grouped| {
+                                flags = grouped;
+                                groups.write_groups_file(std::process::id() as u64)
+                            })
+                            .map(|file| backend.groups_args(&file).unwrap_or_default());
+                        match groups_args {
+                            Ok(args) => flags.extend(args),
+                            Err(e) => {
+                                eprintln!("Erreur : groupes de fichiers : {}", e);
+                                return Ok(());
+                            }
+                        }
+                    }
+                    if let Some(size) = &recovery {
+                        if !backend.capabilities().recovery {
+                            eprintln!("Erreur : {} ne permet pas d'ajouter un enregistrement de récupération", backend.name());
+                            return Ok(());
+                        }
+                        flags.extend(backend.recovery_args(size));
+                    }
+                    if let Some(size) = volume_size.filter(|_| backend.capabilities().volumes) {
+                        flags.extend(backend.volume_args(size));
+                    }
+                    match method::check_memory(backend, &flags, method::cpu_count(), method::system_memory()) {
+                        Ok(warnings) => {
+                            for warning in warnings {
+                                println!("Attention : {}", warning);
                             }
                         }
                         Err(e) => {
-                            eprintln!("Erreur lors de l'exécution de la commande : {}", e);
+                            eprintln!("Erreur : {}", e);
+                            return Ok(());
+                        }
+                    }
+                    let flags: Vec<&str> = flags.iter().map(String::as_str).collect();
+
+                    // Filtres du preset utilisateur, complétés par ceux de la ligne de commande
+                    if let CompressionPreset::User(UserPreset { filter: Some(preset_filter), .. }) = &preset {
+                        let cli_filter = std::mem::replace(&mut filter, (**preset_filter).clone());
+                        if !cli_filter.include.is_empty() {
+                            filter.include = cli_filter.include;
+                        }
+                        if !cli_filter.exclude.is_empty() {
+                            filter.exclude = [filter.exclude.as_str(), cli_filter.exclude.as_str()].join(";");
+                        }
+                    }
+                    let mut sources = vec![PathBuf::from(path)];
+                    // Taille des données retenues (sans filtre : tous les fichiers) pour l'espace libre
+                    let filtered = filter.collect(&sources);
+                    let needs = diskspace::compression_needs(&output_path, filtered.total_size, &flags, volume_size.is_some() && !backend.capabilities().volumes);
+                    for warning in diskspace::check(&needs).warnings {
+                        println!("{}", warning);
+                    }
+                    if !filter.is_empty() {
+                        if let Err(e) = filter.validate() {
+                            eprintln!("Erreur : {}", e);
+                            return Ok(());
+                        }
+                        println!("Filtres : {} ({} fichier(s), {})", filter.summary(), filtered.files.len(), format_size(filtered.total_size));
+                        if filtered.files.is_empty() {
+                            eprintln!("Erreur : aucun fichier ne correspond aux filtres");
+                            return Ok(());
+                        }
+                        let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+                        let source = filters::write_list_file(&filtered.files, &current_dir, std::process::id() as u64)
+                            .and_then(|list| backend.list_file_source(&list)
+                                .ok_or_else(|| format!("{} ne permet pas de filtrer les fichiers", backend.name())));
+                        match source {
+                            Ok(source) => sources = vec![source],
+                            Err(e) => {
+                                eprintln!("Erreur : {}", e);
+                                return Ok(());
+                            }
+                        }
+                    }
+                    let cmd = backend.compress_command(&output_path, &sources, &flags);
+
+                    if run_cli_command(cmd, "la compression") {
+                        match volume_size.filter(|_| !backend.capabilities().volumes) {
+                            Some(size) => match volumes::split(&output_path, size, &AtomicBool::new(false)) {
+                                Ok(parts) => {
+                                    for part in parts {
+                                        println!("Volume créé : {}", part.display());
+                                    }
+                                }
+                                Err(e) => eprintln!("Erreur lors du découpage en volumes : {}", e),
+                            },
+                            None => println!("Compression réussie : {}", output_path.display()),
                         }
                     }
                 } else {
@@ -1343,13 +4134,248 @@ fn main() -> Result<(), eframe::Error> {
             }
             "--extract" => {
                 if args.len() > 2 {
-                    let path = &args[2];
-                    println!("Extraction demandée pour : {}", path);
-                    // Ajoutez ici la logique d'extraction
+                    let path = PathBuf::from(&args[2]);
+                    println!("Extraction demandée pour : {}", path.display());
+                    if !path.exists() {
+                        eprintln!("Erreur : L'archive n'existe pas");
+                        return Ok(());
+                    }
+
+                    // Destination optionnelle, sinon à côté de l'archive ; --overwrite ask|all|skip|newer|rename ;
+                    // --mode here|folder|smart (sous-dossier au nom de l'archive dans la destination)
+                    let mut dest = None;
+                    let mut policy = OverwritePolicy::default();
+                    let mut mode = ExtractMode::Here;
+                    let mut options = args[3..].iter();
+                    while let Some(option) = options.next() {
+                        match (option.as_str(), dest.is_none()) {
+                            ("--overwrite", _) => match options.next().and_then(|k| OverwritePolicy::parse(k)) {
+                                Some(found) => policy = found,
+                                None => {
+                                    eprintln!("Erreur : --overwrite attend ask, all, skip, newer ou rename");
+                                    return Ok(());
+                                }
+                            },
+                            ("--mode", _) => match options.next().and_then(|k| ExtractMode::parse(k)) {
+                                Some(found) => mode = found,
+                                None => {
+                                    eprintln!("Erreur : --mode attend here, folder ou smart");
+                                    return Ok(());
+                                }
+                            },
+                            (_, true) => dest = Some(PathBuf::from(option)),
+                            _ => {
+                                eprintln!("Option inconnue : {}", option);
+                                return Ok(());
+                            }
+                        }
+                    }
+                    let base = dest.unwrap_or_else(|| destination::archive_dir(&path));
+
+                    let backend = BackendKind::for_archive(&path).backend();
+                    if !backend.is_available() {
+                        eprintln!("Erreur : {} n'est pas installé correctement", backend.name());
+                        return Ok(());
+                    }
+
+                    // Premier volume d'une archive découpée
+                    let mut reassembly = match volumes::prepare_reassembly(&path, backend.capabilities().volumes, std::process::id() as u64) {
+                        Ok(reassembly) => reassembly,
+                        Err(e) => {
+                            eprintln!("Erreur : {}", e);
+                            return Ok(());
+                        }
+                    };
+                    // Le fichier réassemblé est supprimé à la fin du bloc
+                    if let Some(reassembly) = &mut reassembly {
+                        if let Err(e) = diskspace::check(&reassembly.space_needs(&base, "l'extraction")).into_result(|warning| println!("{}", warning)) {
+                            eprintln!("Erreur : {}", e);
+                            return Ok(());
+                        }
+                        println!("Réassemblage de {} volume(s)...", reassembly.volume_count());
+                        if let Err(e) = reassembly.join(&AtomicBool::new(false)) {
+                            eprintln!("Erreur lors du réassemblage des volumes : {}", e);
+                            return Ok(());
+                        }
+                    }
+                    let source = reassembly.as_ref().map_or_else(|| path.clone(), |r| r.joined.clone());
+                    let dest = destination::resolve(&path, &source, &base, &[], mode);
+                    println!("Destination : {}", dest.display());
+
+                    if overwrite::run_extract_cli(backend, &source, &dest, policy) {
+                        println!("Extraction réussie vers : {}", dest.display());
+                    }
                 } else {
                     eprintln!("Erreur : Aucun chemin fourni pour l'extraction.");
                 }
             }
+            "--repair" => {
+                if args.len() > 2 {
+                    let path = PathBuf::from(&args[2]);
+                    println!("Réparation demandée pour : {}", path.display());
+                    if !path.is_file() {
+                        eprintln!("Erreur : L'archive n'existe pas");
+                        return Ok(());
+                    }
+                    if !recovery::run_repair_cli(&path) {
+                        std::process::exit(1);
+                    }
+                } else {
+                    eprintln!("Erreur : Aucun chemin fourni pour la réparation.");
+                }
+            }
+            "--convert" => {
+                // --convert <archive> [--preset <Nom>] [--output <archive>]
+                let Some(path) = args.get(2).map(PathBuf::from) else {
+                    eprintln!("Erreur : Aucune archive fournie pour la conversion.");
+                    return Ok(());
+                };
+                let mut preset = CompressionPreset::Normal;
+                let mut output = None;
+                let mut options = args[3..].iter();
+                while let Some(option) = options.next() {
+                    match (option.as_str(), options.next()) {
+                        ("--preset", Some(name)) => {
+                            let user_presets = presets::load_user_presets(Path::new(USER_PRESETS_FILE));
+                            match CompressionPreset::from_name(name, &user_presets) {
+                                Some(found) => preset = found,
+                                None => {
+                                    eprintln!("Preset non reconnu : {}", name);
+                                    return Ok(());
+                                }
+                            }
+                        }
+                        ("--output", Some(target)) => output = Some(PathBuf::from(target)),
+                        _ => {
+                            eprintln!("Option inconnue : {}", option);
+                            return Ok(());
+                        }
+                    }
+                }
+                // Par défaut : même nom, à côté de l'original, avec l'extension du preset
+                let base = if volumes::is_volume(&path) { volumes::base_archive(&path) } else { path.clone() };
+                let output = output.unwrap_or_else(|| base.with_extension(preset.extension()));
+                println!("Conversion demandée : {} → {}", path.display(), output.display());
+                if !convert::run_convert_cli(&path, &output, &preset) {
+                    std::process::exit(1);
+                }
+            }
+            "--add" | "--freshen" | "--delete" | "--rename" => {
+                if !run_update_cli(&args[1], &args[2..]) {
+                    std::process::exit(1);
+                }
+            }
+            "--benchmark" => {
+                // --benchmark <chemin> [--presets A,B,C] [--sample <Mo>] [--decompress] [--export <fichier.csv|.json>]
+                if args.len() > 2 {
+                    let path = PathBuf::from(&args[2]);
+                    if !path.exists() {
+                        eprintln!("Erreur : Le chemin n'existe pas");
+                        return Ok(());
+                    }
+                    let user_presets = presets::load_user_presets(Path::new(USER_PRESETS_FILE));
+                    let mut config = benchmark::BenchmarkConfig {
+                        // Par défaut : tous les presets dont l'outil est installé
+                        presets: CompressionPreset::all().iter()
+                            .filter(|p| p.backend().backend().is_available())
+                            .cloned()
+                            .collect(),
+                        sources: vec![path],
+                        sample_limit: None,
+                        with_decompression: false,
+                    };
+                    let mut export = None;
+                    let mut options = args[3..].iter();
+                    while let Some(option) = options.next() {
+                        match (option.as_str(), options.clone().next()) {
+                            ("--presets", Some(list)) => {
+                                options.next();
+                                let mut selected = Vec::new();
+                                for name in list.split(',') {
+                                    match CompressionPreset::from_name(name.trim(), &user_presets) {
+                                        Some(preset) => selected.push(preset),
+                                        None => {
+                                            eprintln!("Preset non reconnu : {}", name);
+                                            return Ok(());
+                                        }
+                                    }
+                                }
+                                config.presets = selected;
+                            }
+                            ("--sample", Some(mb)) => {
+                                options.next();
+                                match mb.parse::<u64>() {
+                                    Ok(mb) => config.sample_limit = Some(mb * 1024 * 1024),
+                                    Err(_) => {
+                                        eprintln!("Taille d'échantillon invalide : {}", mb);
+                                        return Ok(());
+                                    }
+                                }
+                            }
+                            ("--export", Some(file)) => {
+                                options.next();
+                                export = Some(PathBuf::from(file));
+                            }
+                            ("--decompress", _) => config.with_decompression = true,
+                            _ => {
+                                eprintln!("Option inconnue : {}", option);
+                                return Ok(());
+                            }
+                        }
+                    }
+
+                    let cancel = AtomicBool::new(false);
+                    let mut results = Vec::new();
+                    benchmark::run_benchmark(&config, &cancel, |event| match event {
+                        benchmark::BenchmarkEvent::Started(index, name) => {
+                            println!("[{}/{}] {}...", index + 1, config.presets.len(), name);
+                        }
+                        benchmark::BenchmarkEvent::Finished(result) => {
+                            match &result.error {
+                                Some(e) => println!("    Erreur : {}", e),
+                                None => println!(
+                                    "    {} ({:.1} %) en {:.1} s, {:.1} Mo/s, mémoire {}{}",
+                                    format_size(result.compressed_size),
+                                    result.ratio(),
+                                    result.compress_secs,
+                                    result.speed(),
+                                    format_size(result.peak_memory),
+                                    result.decompress_secs.map(|s| format!(", décompression {:.1} s", s)).unwrap_or_default()
+                                ),
+                            }
+                            results.push(result);
+                        }
+                        benchmark::BenchmarkEvent::Done(Err(e)) => eprintln!("Erreur : {}", e),
+                        benchmark::BenchmarkEvent::Done(Ok(())) => {}
+                    });
+
+                    match export {
+                        Some(file) => match benchmark::export(&results, &file) {
+                            Ok(()) => println!("Résultats exportés : {}", file.display()),
+                            Err(e) => eprintln!("Erreur d'export : {}", e),
+                        },
+                        None => print!("{}", benchmark::to_csv(&results)),
+                    }
+                } else {
+                    eprintln!("Erreur : Aucun chemin fourni pour le banc d'essai.");
+                }
+            }
+            "--test" => {
+                if args.len() > 2 {
+                    let path = PathBuf::from(&args[2]);
+                    println!("Test demandé pour : {}", path.display());
+                    if !path.exists() {
+                        eprintln!("Erreur : L'archive n'existe pas");
+                        return Ok(());
+                    }
+                    // Code de sortie non nul si l'archive est endommagée, pour les scripts
+                    if !verify::run_test_cli(&path) {
+                        std::process::exit(1);
+                    }
+                } else {
+                    eprintln!("Erreur : Aucun chemin fourni pour le test.");
+                }
+            }
             _ => {
                 eprintln!("Argument inconnu : {}", args[1]);
             }
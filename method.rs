@@ -0,0 +1,856 @@
+// Chaînes de méthodes FreeArc (`-m=xtool:...+lzma:...`, `-mc$default,$obj:+preshark`, `-m4/$text=ppmd`) :
+// analyse en arbre, validation, schéma des étapes et estimation de la mémoire nécessaire.
+
+use eframe::egui;
+use egui::{Color32, RichText};
+use std::fmt;
+
+use crate::backend::ArchiveBackend;
+use crate::format_size;
+
+const KB: u64 = 1024;
+const MB: u64 = 1024 * KB;
+const GB: u64 = 1024 * MB;
+
+/// Méthodes connues : une méthode inconnue est signalée mais n'empêche pas la compression
+/// (FreeArc accepte les compresseurs externes déclarés dans arc.ini)
+const KNOWN_METHODS: &[&str] = &[
+    "storing", "tornado", "lzma", "lzma2", "ppmd", "lzp", "grzip", "dict", "lz4", "delta", "exe",
+    "rep", "srep", "maxsrep", "precomp", "xtool", "lolz", "zstd", "zstdx", "kraken", "razor", "razorx",
+    "4x4", "berserk", "luna", "preshark", "bcm", "bsc", "dispack", "mm", "tta", "lzham",
+];
+
+/// Méthodes qui enveloppent une autre méthode (`4x4:b64mb:lzma:...`)
+const WRAPPERS: &[&str] = &["4x4"];
+
+/// Paramètre d'une étape ; `key` est le préfixe alphabétique (`d` dans `d256m`, vide dans `32mb`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Param {
+    Size { key: String, bytes: u64, raw: String },
+    Percent { key: String, value: u32 },
+    Number { key: String, value: u64 },
+    Flag(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stage {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub inner: Option<Box<Stage>>, // Méthode enveloppée (4x4)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chain {
+    pub stages: Vec<Stage>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupOp {
+    Replace { from: String, to: Chain }, // -mc:lzma/lzma:max:32mb
+    Add(Chain),                          // -mc$default,$obj:+preshark
+    Remove(String),                      // -mc-delta
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MethodArg {
+    Level { level: u8, modifiers: String }, // -m4, -m9d
+    Chain(Chain),                           // -m=...
+    Group { groups: Vec<String>, op: GroupOp },
+    /// `-m4/$text=ppmd/$exe=exe+lzma` : méthode principale et méthodes propres à certains groupes
+    WithGroups { main: Box<MethodArg>, groups: Vec<(String, Chain)> },
+}
+
+fn is_known(name: &str) -> bool {
+    let name = name.to_lowercase();
+    KNOWN_METHODS.contains(&name.as_str())
+        || ["precomp", "lolz", "zstd", "razor", "srep"].iter().any(|family| name.starts_with(family))
+}
+
+fn parse_size(text: &str) -> Option<u64> {
+    let digits_end = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    if digits_end == 0 {
+        return None;
+    }
+    let value: u64 = text[..digits_end].parse().ok()?;
+    let unit = match &text[digits_end..].to_lowercase()[..] {
+        "b" => 1,
+        "k" | "kb" => KB,
+        "m" | "mb" => MB,
+        "g" | "gb" => GB,
+        _ => return None,
+    };
+    value.checked_mul(unit)
+}
+
+fn parse_param(token: &str) -> Result<Param, String> {
+    if token.is_empty() {
+        return Err("paramètre vide (\"::\")".to_string());
+    }
+    let key_end = token.find(|c: char| c.is_ascii_digit()).unwrap_or(token.len());
+    let (key, rest) = token.split_at(key_end);
+    if rest.is_empty() {
+        return Ok(Param::Flag(token.to_string()));
+    }
+    if let Some(value) = rest.strip_suffix('p').filter(|v| v.chars().all(|c| c.is_ascii_digit())) {
+        let value: u32 = value.parse().map_err(|_| format!("pourcentage invalide : {}", token))?;
+        if value > 100 {
+            return Err(format!("pourcentage supérieur à 100 : {}", token));
+        }
+        return Ok(Param::Percent { key: key.to_string(), value });
+    }
+    if rest.chars().all(|c| c.is_ascii_digit()) {
+        let value = rest.parse().map_err(|_| format!("nombre trop grand : {}", token))?;
+        return Ok(Param::Number { key: key.to_string(), value });
+    }
+    if let Some(bytes) = parse_size(rest) {
+        return Ok(Param::Size { key: key.to_string(), bytes, raw: token.to_string() });
+    }
+    Ok(Param::Flag(token.to_string()))
+}
+
+fn parse_stage(tokens: &[&str]) -> Result<Stage, String> {
+    let (name, tokens) = tokens.split_first().ok_or("étape vide")?;
+    if name.is_empty() {
+        return Err("étape sans nom de méthode".to_string());
+    }
+    let mut stage = Stage { name: name.to_string(), params: Vec::new(), inner: None };
+    let wraps = WRAPPERS.contains(&name.to_lowercase().as_str());
+    for (i, token) in tokens.iter().enumerate() {
+        // Première méthode connue après les paramètres du wrapper : le reste lui appartient
+        if wraps && is_known(token) && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            stage.inner = Some(Box::new(parse_stage(&tokens[i..])?));
+            break;
+        }
+        stage.params.push(parse_param(token).map_err(|e| format!("{} : {}", name, e))?);
+    }
+    if wraps && stage.inner.is_none() {
+        return Err(format!("{} : méthode enveloppée manquante (ex. {}:b64mb:lzma)", name, name));
+    }
+    Ok(stage)
+}
+
+pub fn parse_chain(text: &str) -> Result<Chain, String> {
+    if text.trim().is_empty() {
+        return Err("chaîne de méthodes vide".to_string());
+    }
+    let stages = text
+        .split('+')
+        .enumerate()
+        .map(|(i, part)| {
+            let tokens: Vec<&str> = part.split(':').collect();
+            parse_stage(&tokens).map_err(|e| format!("étape {} : {}", i + 1, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Chain { stages })
+}
+
+/// Analyse une option `-m...` ; None si l'option ne concerne pas la méthode
+pub fn parse_arg(arg: &str) -> Option<Result<MethodArg, String>> {
+    if let Some(rest) = arg.strip_prefix("-mc") {
+        return Some(parse_group(rest).map_err(|e| format!("{} : {}", arg, e)));
+    }
+    if let Some((main, sections)) = arg.split_once("/$").filter(|_| arg.starts_with("-m")) {
+        return Some(parse_with_groups(main, sections).map_err(|e| format!("{} : {}", arg, e)));
+    }
+    if let Some(chain) = arg.strip_prefix("-m=") {
+        return Some(parse_chain(chain).map(MethodArg::Chain).map_err(|e| format!("{} : {}", arg, e)));
+    }
+    let rest = arg.strip_prefix("-m")?;
+    let digits_end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    Some(match rest[..digits_end].parse::<u8>() {
+        Ok(level) if level <= 9 => Ok(MethodArg::Level { level, modifiers: rest[digits_end..].to_string() }),
+        Ok(_) => Err(format!("{} : niveau hors limites (0 à 9)", arg)),
+        // -m<chaîne> sans '=' : forme abrégée de -m=
+        Err(_) => parse_chain(rest).map(MethodArg::Chain).map_err(|e| format!("{} : {}", arg, e)),
+    })
+}
+
+/// Sections `$groupe=chaîne` qui suivent la méthode principale
+fn parse_with_groups(main: &str, sections: &str) -> Result<MethodArg, String> {
+    let main = parse_arg(main).ok_or("méthode principale manquante")??;
+    let groups = sections
+        .split("/$")
+        .map(|section| {
+            let (group, chain) = section.split_once('=').ok_or_else(|| format!("'=' attendu après ${}", section))?;
+            if group.is_empty() {
+                return Err("nom de groupe vide".to_string());
+            }
+            Ok((format!("${}", group), parse_chain(chain).map_err(|e| format!("${} : {}", group, e))?))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(MethodArg::WithGroups { main: Box::new(main), groups })
+}
+
+fn parse_group(rest: &str) -> Result<MethodArg, String> {
+    // -mc-delta : désactive une méthode pour tous les groupes
+    if let Some(method) = rest.strip_prefix('-') {
+        if method.is_empty() {
+            return Err("méthode à retirer manquante".to_string());
+        }
+        return Ok(MethodArg::Group { groups: Vec::new(), op: GroupOp::Remove(method.to_string()) });
+    }
+    let (groups, change) = rest.split_once(':').ok_or("':' attendu après les groupes")?;
+    let groups: Vec<String> = groups.split(',').filter(|g| !g.is_empty()).map(str::to_string).collect();
+    if let Some(group) = groups.iter().find(|g| !g.starts_with('$')) {
+        return Err(format!("groupe invalide \"{}\" (doit commencer par $)", group));
+    }
+    let op = if let Some(added) = change.strip_prefix('+') {
+        GroupOp::Add(parse_chain(added)?)
+    } else if let Some(removed) = change.strip_prefix('-') {
+        GroupOp::Remove(removed.to_string())
+    } else {
+        let (from, to) = change.split_once('/').ok_or("remplacement attendu sous la forme ancienne/nouvelle")?;
+        if from.is_empty() {
+            return Err("méthode à remplacer manquante".to_string());
+        }
+        GroupOp::Replace { from: from.to_string(), to: parse_chain(to)? }
+    };
+    Ok(MethodArg::Group { groups, op })
+}
+
+/// Analyse toutes les options de méthode d'un preset ; les autres options sont ignorées
+pub fn parse_flags<S: AsRef<str>>(flags: &[S]) -> Result<Vec<MethodArg>, String> {
+    flags.iter().filter_map(|f| parse_arg(f.as_ref())).collect()
+}
+
+/// Avertissements non bloquants (méthodes inconnues)
+pub fn warnings(args: &[MethodArg]) -> Vec<String> {
+    let mut unknown = Vec::new();
+    let mut check = |chain: &Chain| {
+        for stage in &chain.stages {
+            let mut current = Some(stage);
+            while let Some(s) = current {
+                if !is_known(&s.name) && !unknown.contains(&s.name) {
+                    unknown.push(s.name.clone());
+                }
+                current = s.inner.as_deref();
+            }
+        }
+    };
+    for arg in args {
+        match arg {
+            MethodArg::Chain(chain) => check(chain),
+            MethodArg::Group { op: GroupOp::Add(chain) | GroupOp::Replace { to: chain, .. }, .. } => check(chain),
+            MethodArg::WithGroups { main, groups } => {
+                if let MethodArg::Chain(chain) = main.as_ref() {
+                    check(chain);
+                }
+                groups.iter().for_each(|(_, chain)| check(chain));
+            }
+            _ => {}
+        }
+    }
+    unknown.into_iter().map(|name| format!("Méthode inconnue : {} (compresseur externe ?)", name)).collect()
+}
+
+impl Stage {
+    fn size(&self, key: &str) -> Option<u64> {
+        self.params.iter().find_map(|p| match p {
+            Param::Size { key: k, bytes, .. } if k == key => Some(*bytes),
+            _ => None,
+        })
+    }
+
+    fn number(&self, key: &str) -> Option<u64> {
+        self.params.iter().find_map(|p| match p {
+            Param::Number { key: k, value } if k == key => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Nombre de threads : `t4` ou `t90p` (pourcentage des coeurs)
+    fn threads(&self, cpus: usize) -> u64 {
+        let percent = self.params.iter().find_map(|p| match p {
+            Param::Percent { key, value } if key == "t" => Some(*value),
+            _ => None,
+        });
+        match (self.number("t"), percent) {
+            (Some(t), _) => t.max(1),
+            (None, Some(p)) => (cpus as u64 * p as u64 / 100).max(1),
+            (None, None) => cpus as u64,
+        }
+    }
+
+    /// Mémoire estimée à la compression (ordre de grandeur)
+    pub fn estimate_memory(&self, cpus: usize) -> u64 {
+        let name = self.name.to_lowercase();
+        let dict = self.size("d").or_else(|| self.size(""));
+        match name.as_str() {
+            "lzma" | "lzma2" => {
+                // Recherche bt4 : ~11 fois le dictionnaire
+                let factor = if self.params.contains(&Param::Flag("hc4".to_string())) { 7 } else { 11 };
+                dict.unwrap_or(64 * MB) * factor
+            }
+            "4x4" => {
+                let threads = self.threads(cpus);
+                let block = self.size("b").unwrap_or(8 * MB);
+                let inner = self.inner.as_ref().map_or(64 * MB, |s| s.estimate_memory(cpus));
+                // Chaque thread traite un bloc : le dictionnaire ne dépasse pas sa taille
+                threads * (inner.min(block * 12) + 2 * block)
+            }
+            "rep" => dict.unwrap_or(256 * MB),
+            "srep" | "maxsrep" => dict.unwrap_or(512 * MB),
+            "xtool" => 256 * MB + self.threads(cpus) * 32 * MB,
+            "ppmd" => dict.unwrap_or(192 * MB),
+            "zstd" | "zstdx" => match self.number("") {
+                Some(level) if level >= 20 => GB,
+                Some(level) if level >= 16 => 512 * MB,
+                _ => 128 * MB,
+            },
+            "razor" | "razorx" | "berserk" => dict.map_or(GB, |d| d * 6),
+            "kraken" => 256 * MB,
+            "luna" => 512 * MB,
+            "lz4" | "delta" | "exe" | "dict" | "preshark" | "mm" | "tta" | "dispack" | "storing" => 16 * MB,
+            "tornado" => dict.unwrap_or(16 * MB) * 2,
+            _ if name.starts_with("precomp") => 512 * MB,
+            _ if name.starts_with("lolz") => dict.map_or(GB, |d| d * 4),
+            _ => dict.unwrap_or(64 * MB),
+        }
+    }
+
+    /// Mémoire estimée à la décompression : le dictionnaire suffit pour les méthodes LZ,
+    /// les méthodes symétriques (ppmd, lolz, xtool...) demandent autant qu'à la compression
+    pub fn estimate_decompression_memory(&self, cpus: usize) -> u64 {
+        let name = self.name.to_lowercase();
+        let dict = self.size("d").or_else(|| self.size(""));
+        match name.as_str() {
+            "lzma" | "lzma2" => dict.unwrap_or(64 * MB) + MB,
+            "4x4" => {
+                let threads = self.threads(cpus);
+                let block = self.size("b").unwrap_or(8 * MB);
+                let inner = self.inner.as_ref().map_or(64 * MB, |s| s.estimate_decompression_memory(cpus));
+                threads * (inner.min(block) + 2 * block)
+            }
+            "zstd" | "zstdx" => match self.number("") {
+                Some(level) if level >= 20 => 256 * MB,
+                _ => 64 * MB,
+            },
+            "razor" | "razorx" | "berserk" => dict.map_or(256 * MB, |d| d + 16 * MB),
+            "kraken" => 64 * MB,
+            "luna" => 128 * MB,
+            "tornado" => dict.unwrap_or(16 * MB),
+            _ => self.estimate_memory(cpus),
+        }
+    }
+
+    /// Remplace le nombre de threads de l'étape ; xtool, 4x4 et zstd le reçoivent même s'il
+    /// n'était pas précisé, les autres méthodes seulement si elles en avaient déjà un
+    fn set_threads(&mut self, threads: usize) {
+        let name = self.name.to_lowercase();
+        let default_key = match name.as_str() {
+            "xtool" | "4x4" => Some("t"),
+            "zstd" | "zstdx" => Some("T"),
+            _ => None,
+        };
+        let mut found = false;
+        for param in &mut self.params {
+            let key = match param {
+                Param::Number { key, .. } | Param::Percent { key, .. } => key.clone(),
+                _ => continue,
+            };
+            if ["t", "mt", "T"].contains(&key.as_str()) {
+                *param = Param::Number { key, value: threads as u64 };
+                found = true;
+            }
+        }
+        if let (false, Some(key)) = (found, default_key) {
+            self.params.push(Param::Number { key: key.to_string(), value: threads as u64 });
+        }
+        if let Some(inner) = &mut self.inner {
+            inner.set_threads(threads);
+        }
+    }
+
+    /// Divise par deux les dictionnaires et blocs (1 Mo minimum) ; false si plus rien à réduire
+    fn shrink(&mut self) -> bool {
+        let mut shrunk = false;
+        for param in &mut self.params {
+            if let Param::Size { key, bytes, raw } = param {
+                if ["d", "", "b"].contains(&key.as_str()) && *bytes > MB {
+                    *bytes = (*bytes / 2).max(MB);
+                    *raw = format!("{}{}", key, size_token(*bytes));
+                    shrunk = true;
+                }
+            }
+        }
+        if let Some(inner) = &mut self.inner {
+            shrunk |= inner.shrink();
+        }
+        shrunk
+    }
+}
+
+impl Chain {
+    /// Les étapes tournent en parallèle (pipeline) : leurs besoins s'additionnent
+    pub fn estimate_memory(&self, cpus: usize) -> u64 {
+        self.stages.iter().map(|s| s.estimate_memory(cpus)).sum()
+    }
+
+    pub fn estimate_decompression_memory(&self, cpus: usize) -> u64 {
+        self.stages.iter().map(|s| s.estimate_decompression_memory(cpus)).sum()
+    }
+}
+
+/// Taille au format FreeArc, dans la plus grande unité exacte
+fn size_token(bytes: u64) -> String {
+    if bytes.is_multiple_of(GB) {
+        format!("{}gb", bytes / GB)
+    } else if bytes.is_multiple_of(MB) {
+        format!("{}mb", bytes / MB)
+    } else {
+        format!("{}kb", bytes.div_ceil(KB))
+    }
+}
+
+/// Estimation pour l'ensemble des options de méthode d'un preset
+pub fn estimate_memory(args: &[MethodArg], cpus: usize) -> u64 {
+    // Niveaux -m1 à -m9 : valeurs typiques de FreeArc en compression
+    const LEVELS: [u64; 10] = [0, 32 * MB, 64 * MB, 96 * MB, 192 * MB, 256 * MB, 512 * MB, 768 * MB, GB, 1536 * MB];
+    args.iter()
+        .map(|arg| match arg {
+            MethodArg::Level { level, .. } => LEVELS[*level as usize],
+            MethodArg::Chain(chain) => chain.estimate_memory(cpus),
+            MethodArg::Group { op: GroupOp::Add(chain) | GroupOp::Replace { to: chain, .. }, .. } => chain.estimate_memory(cpus),
+            MethodArg::Group { op: GroupOp::Remove(_), .. } => 0,
+            // Chaque groupe est compressé à son tour : le plus gourmand l'emporte
+            MethodArg::WithGroups { main, groups } => groups
+                .iter()
+                .map(|(_, chain)| chain.estimate_memory(cpus))
+                .fold(estimate_memory(std::slice::from_ref(main), cpus), u64::max),
+        })
+        .sum()
+}
+
+/// Estimation de la mémoire nécessaire pour extraire l'archive
+pub fn estimate_decompression_memory(args: &[MethodArg], cpus: usize) -> u64 {
+    const LEVELS: [u64; 10] = [0, 8 * MB, 16 * MB, 32 * MB, 64 * MB, 96 * MB, 192 * MB, 256 * MB, 384 * MB, 512 * MB];
+    args.iter()
+        .map(|arg| match arg {
+            MethodArg::Level { level, .. } => LEVELS[*level as usize],
+            MethodArg::Chain(chain) => chain.estimate_decompression_memory(cpus),
+            MethodArg::Group { op: GroupOp::Add(chain) | GroupOp::Replace { to: chain, .. }, .. } => {
+                chain.estimate_decompression_memory(cpus)
+            }
+            MethodArg::Group { op: GroupOp::Remove(_), .. } => 0,
+            MethodArg::WithGroups { main, groups } => groups
+                .iter()
+                .map(|(_, chain)| chain.estimate_decompression_memory(cpus))
+                .fold(estimate_decompression_memory(std::slice::from_ref(main), cpus), u64::max),
+        })
+        .sum()
+}
+
+/// Besoins en mémoire (compression, décompression) d'une liste d'options ;
+/// None si les options ne sont pas une méthode FreeArc valide
+pub fn estimate_flags<S: AsRef<str>>(flags: &[S], cpus: usize) -> Option<(u64, u64)> {
+    let args = parse_flags(flags).ok()?;
+    Some((estimate_memory(&args, cpus), estimate_decompression_memory(&args, cpus)))
+}
+
+/// Dictionnaire LZMA2 par défaut de 7-Zip pour un niveau `-mx`
+fn sevenzip_level_dictionary(level: u32) -> u64 {
+    match level {
+        0 => 0,
+        1 => 256 * KB,
+        2 => MB,
+        3 | 4 => 4 * MB,
+        5 | 6 => 16 * MB,
+        7 | 8 => 32 * MB,
+        _ => 64 * MB,
+    }
+}
+
+/// Taille de dictionnaire 7-Zip : `64m`, `256k`... ou `26` pour 2^26 octets
+fn parse_sevenzip_size(text: &str) -> Option<u64> {
+    if text.chars().all(|c| c.is_ascii_digit()) {
+        let bits: u32 = text.parse().ok()?;
+        return 1u64.checked_shl(bits);
+    }
+    parse_size(text)
+}
+
+/// Besoins en mémoire (compression, décompression) d'options 7-Zip (`-mx`, `-md`, `-mmt`, `-m0=lzma2:d=...`) ;
+/// None si la méthode n'est pas LZMA/LZMA2 (PPMd, BZip2... ne sont pas estimés).
+/// Même ordre de grandeur que 7-Zip : environ 11,5 fois le dictionnaire par encodeur (un encodeur
+/// pour deux threads à partir de -mx5, 7,5 fois et un par thread en dessous), le dictionnaire pour extraire.
+pub fn estimate_7z_flags<S: AsRef<str>>(flags: &[S], cpus: usize) -> Option<(u64, u64)> {
+    let mut level = 5;
+    let mut dictionary = None;
+    let mut threads = cpus.max(1);
+    for flag in flags {
+        let flag = flag.as_ref().to_ascii_lowercase();
+        if let Some(value) = flag.strip_prefix("-mx") {
+            level = value.trim_start_matches('=').parse().ok()?;
+        } else if let Some(value) = flag.strip_prefix("-mmt") {
+            threads = match value.trim_start_matches('=') {
+                "" | "on" => cpus.max(1),
+                "off" => 1,
+                count => count.parse::<usize>().ok()?.max(1),
+            };
+        } else if let Some(value) = flag.strip_prefix("-md") {
+            dictionary = Some(parse_sevenzip_size(value.trim_start_matches('='))?);
+        } else if let Some(method) = flag.strip_prefix("-m0=").or_else(|| flag.strip_prefix("-m=")) {
+            let mut params = method.split(':');
+            if !matches!(params.next(), Some("lzma" | "lzma2")) {
+                return None;
+            }
+            for param in params {
+                if let Some(size) = param.strip_prefix("d=").or_else(|| param.strip_prefix('d')) {
+                    dictionary = Some(parse_sevenzip_size(size)?);
+                }
+            }
+        }
+    }
+    if level == 0 {
+        return Some((0, 0));
+    }
+    let dictionary = dictionary.unwrap_or_else(|| sevenzip_level_dictionary(level));
+    let (encoders, factor) = if level >= 5 { (threads.div_ceil(2), 23) } else { (threads, 15) };
+    let compression = encoders as u64 * (dictionary * factor / 2 + 6 * MB);
+    Some((compression, dictionary + 2 * MB))
+}
+
+/// Mémoire (disponible, totale) de la machine, en octets
+pub fn system_memory() -> (u64, u64) {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_memory();
+    (sys.available_memory(), sys.total_memory())
+}
+
+/// Vérifie qu'une compression tient en mémoire : erreur si l'estimation dépasse la mémoire totale,
+/// sinon les avertissements à journaliser (mémoire disponible dépassée, extraction impossible
+/// sur cette machine, options dont l'outil ne permet pas d'estimer les besoins)
+pub fn check_memory(
+    backend: &dyn ArchiveBackend,
+    flags: &[String],
+    cpus: usize,
+    (available, total): (u64, u64),
+) -> Result<Vec<String>, String> {
+    let flags: Vec<&str> = flags.iter().map(String::as_str).collect();
+    let Some((compression, decompression)) = backend.estimate_memory(&flags, cpus) else {
+        return Ok(vec![format!("les besoins en mémoire des options de {} ne sont pas vérifiés", backend.name())]);
+    };
+    if compression > total {
+        return Err(format!(
+            "la compression demande environ {} de mémoire, plus que la mémoire totale ({})",
+            format_size(compression),
+            format_size(total)
+        ));
+    }
+    let mut warnings = Vec::new();
+    if compression > available {
+        warnings.push(format!(
+            "la compression demande environ {} de mémoire, plus que la mémoire disponible ({})",
+            format_size(compression),
+            format_size(available)
+        ));
+    }
+    if decompression > total {
+        warnings.push(format!(
+            "l'extraction demandera environ {} de mémoire, plus que la mémoire totale de cette machine ({})",
+            format_size(decompression),
+            format_size(total)
+        ));
+    }
+    Ok(warnings)
+}
+
+impl MethodArg {
+    /// Réduit les dictionnaires d'un cran ; false si l'option ne peut plus être allégée
+    fn shrink(&mut self) -> bool {
+        let chain = match self {
+            MethodArg::Level { level, .. } => {
+                if *level <= 1 {
+                    return false;
+                }
+                *level -= 1;
+                return true;
+            }
+            MethodArg::Chain(chain) => chain,
+            MethodArg::Group { op: GroupOp::Add(chain) | GroupOp::Replace { to: chain, .. }, .. } => chain,
+            MethodArg::Group { op: GroupOp::Remove(_), .. } => return false,
+            MethodArg::WithGroups { main, groups } => {
+                let mut shrunk = main.shrink();
+                for stage in groups.iter_mut().flat_map(|(_, chain)| chain.stages.iter_mut()) {
+                    shrunk |= stage.shrink();
+                }
+                return shrunk;
+            }
+        };
+        let mut shrunk = false;
+        for stage in &mut chain.stages {
+            shrunk |= stage.shrink();
+        }
+        shrunk
+    }
+}
+
+/// Impose le nombre de threads à toutes les étapes des chaînes de méthodes ;
+/// les niveaux prédéfinis (-m4...) et les autres options sont conservés
+pub fn set_threads<S: AsRef<str>>(flags: &[S], threads: usize) -> Vec<String> {
+    flags
+        .iter()
+        .map(|flag| {
+            let mut arg = match parse_arg(flag.as_ref()) {
+                Some(Ok(arg)) => arg,
+                _ => return flag.as_ref().to_string(),
+            };
+            match &mut arg {
+                MethodArg::Chain(chain) | MethodArg::Group { op: GroupOp::Add(chain) | GroupOp::Replace { to: chain, .. }, .. } => {
+                    for stage in &mut chain.stages {
+                        stage.set_threads(threads);
+                    }
+                    arg.to_string()
+                }
+                MethodArg::WithGroups { main, groups } => {
+                    let chains = groups.iter_mut().map(|(_, chain)| chain);
+                    let main_chain = match main.as_mut() {
+                        MethodArg::Chain(chain) => Some(chain),
+                        _ => None,
+                    };
+                    for stage in chains.chain(main_chain).flat_map(|chain| chain.stages.iter_mut()) {
+                        stage.set_threads(threads);
+                    }
+                    arg.to_string()
+                }
+                _ => flag.as_ref().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Ajoute les méthodes propres à certains groupes (`$text`, `ppmd`) à la méthode principale :
+/// `-m4` devient `-m4/$text=ppmd`. Un groupe que la méthode précise déjà garde sa chaîne ;
+/// sans méthode principale, FreeArc utiliserait -m4, qui sert alors de base.
+pub fn with_group_methods<S: AsRef<str>>(flags: &[S], methods: &[(String, Chain)]) -> Vec<String> {
+    let mut flags: Vec<String> = flags.iter().map(|f| f.as_ref().to_string()).collect();
+    if methods.is_empty() {
+        return flags;
+    }
+    let position = flags.iter().position(|flag| {
+        matches!(parse_arg(flag), Some(Ok(MethodArg::Level { .. } | MethodArg::Chain(_) | MethodArg::WithGroups { .. })))
+    });
+    let (main, mut groups) = match position.and_then(|i| parse_arg(&flags[i])).and_then(Result::ok) {
+        Some(MethodArg::WithGroups { main, groups }) => (main, groups),
+        Some(arg) => (Box::new(arg), Vec::new()),
+        None => (Box::new(MethodArg::Level { level: 4, modifiers: String::new() }), Vec::new()),
+    };
+    for (group, chain) in methods {
+        if !groups.iter().any(|(g, _)| g.eq_ignore_ascii_case(group)) {
+            groups.push((group.clone(), chain.clone()));
+        }
+    }
+    let flag = MethodArg::WithGroups { main, groups }.to_string();
+    match position {
+        Some(i) => flags[i] = flag,
+        None => flags.insert(0, flag),
+    }
+    flags
+}
+
+/// Réduit dictionnaires, blocs et niveaux jusqu'à ce que la compression tienne dans `budget` octets.
+/// Les options qui ne concernent pas la méthode sont conservées telles quelles ;
+/// None si la méthode ne peut pas descendre assez bas (xtool, threads...).
+pub fn downgrade_flags<S: AsRef<str>>(flags: &[S], budget: u64, cpus: usize) -> Option<Vec<String>> {
+    let mut parsed: Vec<Result<MethodArg, String>> = flags
+        .iter()
+        .map(|f| match parse_arg(f.as_ref()) {
+            Some(arg) => arg.map_err(|_| f.as_ref().to_string()),
+            None => Err(f.as_ref().to_string()),
+        })
+        .collect();
+    if parsed.iter().all(Result::is_err) {
+        return None;
+    }
+    loop {
+        let args: Vec<MethodArg> = parsed.iter().filter_map(|a| a.as_ref().ok().cloned()).collect();
+        if estimate_memory(&args, cpus) <= budget {
+            break;
+        }
+        let mut shrunk = false;
+        for arg in parsed.iter_mut().flatten() {
+            shrunk |= arg.shrink();
+        }
+        if !shrunk {
+            return None;
+        }
+    }
+    Some(
+        parsed
+            .into_iter()
+            .map(|a| match a {
+                Ok(arg) => arg.to_string(),
+                Err(flag) => flag,
+            })
+            .collect(),
+    )
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Param::Size { raw, .. } => write!(f, "{}", raw),
+            Param::Percent { key, value } => write!(f, "{}{}p", key, value),
+            Param::Number { key, value } => write!(f, "{}{}", key, value),
+            Param::Flag(flag) => write!(f, "{}", flag),
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for param in &self.params {
+            write!(f, ":{}", param)?;
+        }
+        if let Some(inner) = &self.inner {
+            write!(f, ":{}", inner)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let stages: Vec<String> = self.stages.iter().map(|s| s.to_string()).collect();
+        write!(f, "{}", stages.join("+"))
+    }
+}
+
+/// Option telle que FreeArc l'attend sur la ligne de commande
+impl fmt::Display for MethodArg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MethodArg::Level { level, modifiers } => write!(f, "-m{}{}", level, modifiers),
+            MethodArg::Chain(chain) => write!(f, "-m={}", chain),
+            MethodArg::WithGroups { main, groups } => {
+                write!(f, "{}", main)?;
+                for (group, chain) in groups {
+                    write!(f, "/{}={}", group, chain)?;
+                }
+                Ok(())
+            }
+            MethodArg::Group { groups, op: GroupOp::Remove(method) } if groups.is_empty() => write!(f, "-mc-{}", method),
+            MethodArg::Group { groups, op } => {
+                write!(f, "-mc{}:", groups.join(","))?;
+                match op {
+                    GroupOp::Replace { from, to } => write!(f, "{}/{}", from, to),
+                    GroupOp::Add(chain) => write!(f, "+{}", chain),
+                    GroupOp::Remove(method) => write!(f, "-{}", method),
+                }
+            }
+        }
+    }
+}
+
+impl Param {
+    /// Libellé lisible pour le schéma
+    fn describe(&self) -> String {
+        let key_label = |key: &str| match key {
+            "d" => "dictionnaire".to_string(),
+            "b" => "bloc".to_string(),
+            "t" => "threads".to_string(),
+            "c" => "chunk".to_string(),
+            "" => "taille".to_string(),
+            other => other.to_string(),
+        };
+        match self {
+            Param::Size { key, bytes, .. } => format!("{} {}", key_label(key), format_size(*bytes)),
+            Param::Percent { key, value } => format!("{} {} %", key_label(key), value),
+            Param::Number { key, value } if key.is_empty() => format!("niveau {}", value),
+            Param::Number { key, value } => format!("{} = {}", key, value),
+            Param::Flag(flag) => flag.clone(),
+        }
+    }
+}
+
+fn show_stage(ui: &mut egui::Ui, stage: &Stage, cpus: usize) {
+    egui::Frame::group(ui.style()).show(ui, |ui| {
+        ui.vertical(|ui| {
+            ui.label(RichText::new(&stage.name).strong().color(Color32::from_rgb(66, 133, 244)));
+            for param in &stage.params {
+                ui.label(RichText::new(param.describe()).small().monospace());
+            }
+            if let Some(inner) = &stage.inner {
+                ui.label(RichText::new("↳ enveloppe :").small());
+                show_stage(ui, inner, cpus);
+            }
+            ui.label(RichText::new(format!("≈ {}", format_size(stage.estimate_memory(cpus)))).small().color(Color32::GRAY));
+        });
+    });
+}
+
+fn show_chain(ui: &mut egui::Ui, chain: &Chain, cpus: usize) {
+    ui.horizontal_wrapped(|ui| {
+        for (i, stage) in chain.stages.iter().enumerate() {
+            if i > 0 {
+                ui.label(RichText::new("➜").size(18.0));
+            }
+            show_stage(ui, stage, cpus);
+        }
+    });
+}
+
+fn show_arg(ui: &mut egui::Ui, arg: &MethodArg, cpus: usize) {
+    match arg {
+        MethodArg::Level { level, modifiers } => {
+            ui.label(format!("Niveau prédéfini -m{}{}", level, modifiers));
+        }
+        MethodArg::Chain(chain) => show_chain(ui, chain, cpus),
+        MethodArg::Group { groups, op } => {
+            let target = if groups.is_empty() { "tous les groupes".to_string() } else { groups.join(", ") };
+            match op {
+                GroupOp::Add(chain) => {
+                    ui.label(format!("Ajout pour {} :", target));
+                    show_chain(ui, chain, cpus);
+                }
+                GroupOp::Replace { from, to } => {
+                    ui.label(format!("Remplacement de {} pour {} :", from, target));
+                    show_chain(ui, to, cpus);
+                }
+                GroupOp::Remove(method) => {
+                    ui.label(format!("Désactivation de {} pour {}", method, target));
+                }
+            }
+        }
+        MethodArg::WithGroups { main, groups } => {
+            show_arg(ui, main, cpus);
+            for (group, chain) in groups {
+                ui.label(format!("Pour {} :", group));
+                show_chain(ui, chain, cpus);
+            }
+        }
+    }
+}
+
+pub fn cpu_count() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Schéma des étapes, avertissements et estimation mémoire d'une liste d'options
+pub fn show_analysis<S: AsRef<str>>(ui: &mut egui::Ui, flags: &[S]) {
+    let cpus = cpu_count();
+    let args = match parse_flags(flags) {
+        Ok(args) => args,
+        Err(e) => {
+            ui.colored_label(Color32::from_rgb(234, 67, 53), format!("❌ {}", e));
+            return;
+        }
+    };
+    if args.is_empty() {
+        ui.label(RichText::new("Aucune option de méthode (-m)").color(Color32::GRAY));
+        return;
+    }
+    for arg in &args {
+        show_arg(ui, arg, cpus);
+    }
+    for warning in warnings(&args) {
+        ui.colored_label(Color32::from_rgb(251, 188, 4), format!("⚠ {}", warning));
+    }
+    ui.label(RichText::new(format!(
+        "Mémoire estimée : ≈ {} en compression, ≈ {} en décompression ({} threads)",
+        format_size(estimate_memory(&args, cpus)),
+        format_size(estimate_decompression_memory(&args, cpus)),
+        cpus
+    )).strong());
+}
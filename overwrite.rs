@@ -0,0 +1,394 @@
+// Fichiers déjà présents dans le dossier d'extraction : politique d'écrasement
+// (demander, tout remplacer, conserver, remplacer si plus récent, renommer) et
+// décision fichier par fichier. Ce que l'outil ne sait pas faire lui-même est obtenu
+// par une extraction sélective, les fichiers à renommer passant par un dossier de transit.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+
+use crate::backend::ArchiveBackend;
+use crate::listing::{self, ArchiveEntry};
+use crate::diskspace::{self, SpaceNeed};
+use crate::safety::SafetyReport;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum OverwritePolicy {
+    Ask,
+    #[default]
+    Overwrite,
+    Skip,
+    Newer, // Remplacer seulement si le fichier de l'archive est plus récent
+    Rename,
+}
+
+impl OverwritePolicy {
+    pub fn all() -> &'static [OverwritePolicy] {
+        &[
+            OverwritePolicy::Ask,
+            OverwritePolicy::Overwrite,
+            OverwritePolicy::Skip,
+            OverwritePolicy::Newer,
+            OverwritePolicy::Rename,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OverwritePolicy::Ask => "Demander pour chaque fichier",
+            OverwritePolicy::Overwrite => "Tout remplacer",
+            OverwritePolicy::Skip => "Conserver les fichiers existants",
+            OverwritePolicy::Newer => "Remplacer si plus récent",
+            OverwritePolicy::Rename => "Renommer les fichiers extraits",
+        }
+    }
+
+    /// Mot-clé de la ligne de commande (`--overwrite skip`)
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            OverwritePolicy::Ask => "ask",
+            OverwritePolicy::Overwrite => "all",
+            OverwritePolicy::Skip => "skip",
+            OverwritePolicy::Newer => "newer",
+            OverwritePolicy::Rename => "rename",
+        }
+    }
+
+    pub fn parse(keyword: &str) -> Option<OverwritePolicy> {
+        OverwritePolicy::all().iter().copied().find(|p| p.keyword().eq_ignore_ascii_case(keyword.trim()))
+    }
+}
+
+/// Décision pour un fichier déjà présent
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Resolution {
+    Overwrite,
+    Skip,
+    Rename,
+}
+
+impl Resolution {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Resolution::Overwrite => "Remplacer",
+            Resolution::Skip => "Conserver",
+            Resolution::Rename => "Renommer",
+        }
+    }
+}
+
+/// Entrée de l'archive dont la destination existe déjà
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub entry: String,
+    pub target: PathBuf,
+    pub archive_size: u64,
+    pub archive_modified: String, // "AAAA-MM-JJ HH:MM:SS", tel qu'annoncé par l'outil
+    pub existing_size: u64,
+    pub existing_modified: String,
+    /// Fichier de l'archive plus récent que l'existant ; None si une des dates est illisible
+    pub archive_newer: Option<bool>,
+    pub resolution: Resolution,
+}
+
+/// Entrée retenue par la sélection (vide = tout) : l'entrée elle-même ou un dossier parent
+fn is_selected(entry: &str, selection: &[String]) -> bool {
+    selection.is_empty()
+        || selection.iter().any(|s| entry == s || entry.strip_prefix(s.as_str()).is_some_and(|rest| rest.starts_with('/')))
+}
+
+/// Fichiers de l'archive à extraire, dans l'ordre de l'archive
+pub fn selected_files<'a>(entries: &'a [ArchiveEntry], selection: &'a [String]) -> impl Iterator<Item = &'a ArchiveEntry> {
+    entries.iter().filter(move |e| !e.is_dir && is_selected(&e.path, selection))
+}
+
+/// Compare les fichiers à extraire au contenu de la destination. La décision initiale
+/// découle de la politique ; pour `Ask`, elle reste à confirmer (remplacer par défaut).
+pub fn find_conflicts(entries: &[ArchiveEntry], selection: &[String], dest: &Path, policy: OverwritePolicy) -> Vec<Conflict> {
+    selected_files(entries, selection)
+        .filter_map(|entry| {
+            let target = dest.join(&entry.path);
+            let metadata = fs::metadata(&target).ok().filter(|m| m.is_file())?;
+            let existing_time = metadata.modified().ok();
+            let archive_newer = parse_listing_time(&entry.modified).zip(existing_time).map(|(archive, existing)| archive > existing);
+            let resolution = match policy {
+                OverwritePolicy::Ask | OverwritePolicy::Overwrite => Resolution::Overwrite,
+                OverwritePolicy::Skip => Resolution::Skip,
+                OverwritePolicy::Rename => Resolution::Rename,
+                // Date illisible d'un côté ou de l'autre : le fichier existant est conservé
+                OverwritePolicy::Newer if archive_newer == Some(true) => Resolution::Overwrite,
+                OverwritePolicy::Newer => Resolution::Skip,
+            };
+            Some(Conflict {
+                entry: entry.path.clone(),
+                target,
+                archive_size: entry.size,
+                archive_modified: entry.modified.clone(),
+                existing_size: metadata.len(),
+                existing_modified: existing_time.map(format_time).unwrap_or_default(),
+                archive_newer,
+                resolution,
+            })
+        })
+        .collect()
+}
+
+/// Format des dates des listages : "AAAA-MM-JJ HH:MM:SS", en heure locale (FreeArc et
+/// 7-Zip affichent l'heure locale)
+const LISTING_TIME: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Date d'un fichier au format des listages, pour l'affichage
+pub fn format_time(time: SystemTime) -> String {
+    DateTime::<Local>::from(time).format(LISTING_TIME).to_string()
+}
+
+/// Date d'une entrée de listage ; None si elle n'a pas le format attendu (fractions de
+/// seconde ignorées) ou tombe dans un changement d'heure inexistant
+pub fn parse_listing_time(text: &str) -> Option<SystemTime> {
+    let naive = NaiveDateTime::parse_from_str(text.get(..19)?, LISTING_TIME).ok()?;
+    Local.from_local_datetime(&naive).earliest().map(SystemTime::from)
+}
+
+/// `rapport.txt` → `rapport_1.txt`, premier nom libre (même forme que 7-Zip)
+pub fn renamed_target(target: &Path) -> PathBuf {
+    let stem = target.file_stem().and_then(|s| s.to_str()).unwrap_or("fichier");
+    let ext = target.extension().and_then(|e| e.to_str()).map(|e| format!(".{}", e)).unwrap_or_default();
+    (1..)
+        .map(|n| target.with_file_name(format!("{}_{}{}", stem, n, ext)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| target.to_path_buf())
+}
+
+/// Extraction découpée selon les décisions : entrées extraites sur place, et entrées
+/// extraites à part puis renommées. Les fichiers conservés et les entrées refusées par
+/// la vérification de sécurité ne sont pas extraits.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractPlan {
+    pub in_place: Vec<String>,
+    pub renamed: Vec<String>,
+    pub skipped: usize,
+    /// Politique passée à l'outil pour l'extraction sur place : celle demandée quand il
+    /// l'applique lui-même, sinon le remplacement (les décisions sont déjà prises)
+    pub policy: OverwritePolicy,
+    /// Toute l'archive est extraite : commande d'extraction normale, sans fichier liste
+    pub whole: bool,
+    /// Espace supplémentaire occupé dans la destination (fichiers remplacés déduits)
+    pub unpacked: u64,
+    pub safety: SafetyReport,
+}
+
+impl ExtractPlan {
+    /// Vérifie les entrées, puis compare les fichiers retenus à la destination ; quand l'outil
+    /// n'applique pas la politique lui-même, `decide` peut modifier les décisions
+    pub fn prepare(
+        backend: &dyn ArchiveBackend,
+        entries: &[ArchiveEntry],
+        selection: &[String],
+        dest: &Path,
+        policy: OverwritePolicy,
+        decide: impl FnOnce(&mut [Conflict]),
+    ) -> ExtractPlan {
+        let selected: Vec<ArchiveEntry> = entries.iter().filter(|e| is_selected(&e.path, selection)).cloned().collect();
+        let safety = SafetyReport::check(&selected);
+        let entries = safety.safe_entries(&selected);
+        let native = backend.overwrite_args(policy).is_some();
+        // Avec une politique appliquée par l'outil, les conflits servent seulement à estimer l'espace
+        let mut conflicts = find_conflicts(&entries, &[], dest, policy);
+        if !native {
+            decide(&mut conflicts);
+        }
+
+        let mut plan = ExtractPlan { policy: if native { policy } else { OverwritePolicy::Overwrite }, ..Default::default() };
+        for entry in selected_files(&entries, &[]) {
+            let conflict = conflicts.iter().find(|c| c.entry == entry.path);
+            match conflict.map(|c| c.resolution).filter(|_| !native) {
+                Some(Resolution::Skip) => plan.skipped += 1,
+                Some(Resolution::Rename) => plan.renamed.push(entry.path.clone()),
+                Some(Resolution::Overwrite) | None => plan.in_place.push(entry.path.clone()),
+            }
+            let replaced = conflict.filter(|c| c.resolution == Resolution::Overwrite).map_or(0, |c| c.existing_size);
+            if conflict.is_none_or(|c| c.resolution != Resolution::Skip) {
+                plan.unpacked += entry.size.saturating_sub(replaced);
+            }
+        }
+        plan.whole = selection.is_empty() && plan.skipped == 0 && plan.renamed.is_empty() && safety.is_clean();
+        plan.safety = safety;
+        plan
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} fichier(s) extrait(s) sur place, {} renommé(s), {} conservé(s)",
+            self.in_place.len(),
+            self.renamed.len(),
+            self.skipped
+        )
+    }
+
+    /// Extraction sur place ; None s'il n'y a rien à extraire
+    pub fn in_place_command(&self, backend: &dyn ArchiveBackend, source: &Path, dest: &Path, tag: u64) -> Result<Option<Command>, String> {
+        if self.in_place.is_empty() {
+            return Ok(None);
+        }
+        let mut cmd = if self.whole {
+            backend.extract_command(source, dest)
+        } else {
+            entries_command(backend, source, dest, &self.in_place, tag)?
+        };
+        cmd.args(backend.overwrite_args(self.policy).unwrap_or_default());
+        Ok(Some(cmd))
+    }
+
+    /// Extraction des fichiers à renommer dans le dossier de transit
+    pub fn rename_command(&self, backend: &dyn ArchiveBackend, source: &Path, staging: &Path, tag: u64) -> Result<Option<Command>, String> {
+        if self.renamed.is_empty() {
+            return Ok(None);
+        }
+        let mut cmd = entries_command(backend, source, staging, &self.renamed, tag)?;
+        cmd.args(backend.overwrite_args(OverwritePolicy::Overwrite).unwrap_or_default());
+        Ok(Some(cmd))
+    }
+}
+
+/// Extraction des entrées données (fichier liste) vers `dest`
+fn entries_command(backend: &dyn ArchiveBackend, source: &Path, dest: &Path, entries: &[String], tag: u64) -> Result<Command, String> {
+    let list_file = listing::write_list_file(entries, tag)?;
+    backend
+        .extract_files_command(source, dest, &list_file)
+        .ok_or_else(|| format!("{} ne permet pas de choisir les fichiers à extraire", backend.name()))
+}
+
+/// Dossier de transit des fichiers à renommer, dans la destination (même disque)
+pub fn staging_dir(dest: &Path, tag: u64) -> PathBuf {
+    dest.join(format!("stelarc_rename_{}", tag))
+}
+
+/// Déplace les fichiers extraits dans le dossier de transit vers un nom libre de la
+/// destination, puis supprime le dossier de transit. Retourne les lignes du journal.
+pub fn move_renamed(staging: &Path, dest: &Path, entries: &[String]) -> Result<Vec<String>, String> {
+    let mut log = Vec::new();
+    for entry in entries {
+        let staged = staging.join(entry);
+        let target = renamed_target(&dest.join(entry));
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("{} : {}", parent.display(), e))?;
+        }
+        fs::rename(&staged, &target).map_err(|e| format!("{} : {}", staged.display(), e))?;
+        log.push(format!("Renommé : {} → {}", entry, target.display()));
+    }
+    let _ = fs::remove_dir_all(staging);
+    Ok(log)
+}
+
+/// Extraction en mode console selon la politique, après vérification des entrées ; avec
+/// `ask`, chaque fichier déjà présent est confirmé au clavier
+pub fn run_extract_cli(backend: &dyn ArchiveBackend, source: &Path, dest: &Path, policy: OverwritePolicy) -> bool {
+    if !backend.capabilities().list {
+        let Some(args) = backend.overwrite_args(policy) else {
+            eprintln!("Erreur : {} remplace toujours les fichiers existants", backend.name());
+            return false;
+        };
+        println!("Attention : {} ne permet pas de vérifier le contenu de l'archive avant extraction", backend.name());
+        let mut cmd = backend.extract_command(source, dest);
+        cmd.args(args);
+        return crate::run_cli_command(cmd, "l'extraction");
+    }
+    let entries = match listing::list_archive(source) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Erreur : {}", e);
+            return false;
+        }
+    };
+    let plan = ExtractPlan::prepare(backend, &entries, &[], dest, policy, |conflicts| {
+        if policy != OverwritePolicy::Ask {
+            return;
+        }
+        let mut for_all = None;
+        for conflict in conflicts {
+            conflict.resolution = match for_all {
+                Some(resolution) => resolution,
+                None => {
+                    let (resolution, all) = ask_resolution(conflict);
+                    if all {
+                        for_all = Some(resolution);
+                    }
+                    resolution
+                }
+            };
+        }
+    });
+    for line in plan.safety.log_lines() {
+        eprintln!("{}", line);
+    }
+    println!("{}", plan.summary());
+    let space = diskspace::check(&[SpaceNeed::exact(dest, plan.unpacked, "l'extraction")]);
+    if let Err(e) = space.into_result(|warning| println!("{}", warning)) {
+        eprintln!("Erreur : {}", e);
+        return false;
+    }
+
+    let tag = std::process::id() as u64;
+    let success = match plan.in_place_command(backend, source, dest, tag) {
+        Ok(Some(cmd)) => crate::run_cli_command(cmd, "l'extraction"),
+        Ok(None) => true,
+        Err(e) => {
+            eprintln!("Erreur : {}", e);
+            false
+        }
+    };
+    let staging = staging_dir(dest, tag);
+    // Erreur vide : l'échec de la commande a déjà été affiché
+    let moved = match plan.rename_command(backend, source, &staging, tag) {
+        _ if !success => Ok(Vec::new()),
+        Ok(Some(cmd)) => match crate::run_cli_command(cmd, "l'extraction des fichiers à renommer") {
+            true => move_renamed(&staging, dest, &plan.renamed),
+            false => Err(String::new()),
+        },
+        Ok(None) => Ok(Vec::new()),
+        Err(e) => Err(e),
+    };
+    let success = success && match moved {
+        Ok(lines) => {
+            lines.iter().for_each(|line| println!("{}", line));
+            true
+        }
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            if !e.is_empty() {
+                eprintln!("Erreur : {}", e);
+            }
+            false
+        }
+    };
+    println!("{}", plan.safety.summary());
+    success
+}
+
+/// Question posée pour un fichier déjà présent : (décision, à appliquer aux suivants)
+fn ask_resolution(conflict: &Conflict) -> (Resolution, bool) {
+    println!("Le fichier {} existe déjà :", conflict.target.display());
+    println!("  archive  : {:>12}  {}", crate::format_size(conflict.archive_size), conflict.archive_modified);
+    println!("  existant : {:>12}  {}", crate::format_size(conflict.existing_size), conflict.existing_modified);
+    loop {
+        print!("[r]emplacer, [c]onserver, re[n]ommer (majuscule : pour tous les suivants) ? ");
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).unwrap_or(0) == 0 {
+            // Entrée fermée : on ne touche pas au fichier existant
+            return (Resolution::Skip, true);
+        }
+        let answer = answer.trim();
+        let all = answer.chars().next().is_some_and(char::is_uppercase);
+        match answer.to_lowercase().as_str() {
+            "r" => return (Resolution::Overwrite, all),
+            "c" => return (Resolution::Skip, all),
+            "n" => return (Resolution::Rename, all),
+            _ => {}
+        }
+    }
+}
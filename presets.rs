@@ -0,0 +1,293 @@
+// Presets utilisateur : combinaisons d'options enregistrées dans un fichier JSON,
+// pour essayer de nouvelles méthodes (xtool, lolz...) sans recompiler.
+
+use eframe::egui;
+use egui::{Color32, RichText};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::backend::BackendKind;
+use crate::filters::SourceFilter;
+use crate::method;
+
+pub const USER_PRESETS_FILE: &str = "stelarc_presets.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserPreset {
+    pub name: String,
+    pub backend: BackendKind,
+    pub flags: Vec<String>,
+    #[serde(default)]
+    pub description: String,
+    /// Extension de l'archive produite (sans le point)
+    pub extension: String,
+    /// Filtres appliqués aux sources quand le preset est choisi
+    #[serde(default)]
+    pub filter: Option<Box<SourceFilter>>,
+}
+
+impl UserPreset {
+    fn new(backend: BackendKind) -> Self {
+        Self {
+            name: String::new(),
+            backend,
+            flags: Vec::new(),
+            description: String::new(),
+            extension: backend.backend().extensions()[0].to_string(),
+            filter: None,
+        }
+    }
+}
+
+/// Fichier absent ou illisible : aucun preset utilisateur
+pub fn load_user_presets(path: &Path) -> Vec<UserPreset> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_user_presets(path: &Path, presets: &[UserPreset]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(presets).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// Découpe une ligne d'options ; les guillemets regroupent un argument contenant des espaces
+pub fn split_flags(text: &str) -> Vec<String> {
+    let mut flags = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    flags.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        flags.push(current);
+    }
+    flags
+}
+
+fn join_flags(flags: &[String]) -> String {
+    flags
+        .iter()
+        .map(|f| if f.contains(char::is_whitespace) { format!("\"{}\"", f) } else { f.clone() })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Fenêtre de gestion des presets utilisateur
+pub struct PresetEditor {
+    selected: Option<usize>, // Preset en cours de modification, None = nouveau
+    draft: UserPreset,
+    flags_text: String,
+    error: Option<String>,
+}
+
+impl Default for PresetEditor {
+    fn default() -> Self {
+        Self { selected: None, draft: UserPreset::new(BackendKind::FreeArc), flags_text: String::new(), error: None }
+    }
+}
+
+impl PresetEditor {
+    fn edit(&mut self, index: Option<usize>, preset: UserPreset) {
+        self.selected = index;
+        self.flags_text = join_flags(&preset.flags);
+        self.draft = preset;
+        self.error = None;
+    }
+
+    fn validate(&self, presets: &[UserPreset], builtin_names: &[String]) -> Result<UserPreset, String> {
+        let mut preset = self.draft.clone();
+        preset.name = preset.name.trim().to_string();
+        preset.extension = preset.extension.trim().trim_start_matches('.').to_string();
+        preset.flags = split_flags(&self.flags_text);
+
+        if preset.name.is_empty() {
+            return Err("Le nom est obligatoire".to_string());
+        }
+        let taken = presets
+            .iter()
+            .enumerate()
+            .any(|(i, p)| Some(i) != self.selected && p.name.eq_ignore_ascii_case(&preset.name));
+        if taken || builtin_names.iter().any(|n| n.eq_ignore_ascii_case(&preset.name)) {
+            return Err(format!("Un preset nommé \"{}\" existe déjà", preset.name));
+        }
+        if preset.flags.is_empty() {
+            return Err("Aucune option de compression".to_string());
+        }
+        if preset.backend == BackendKind::FreeArc {
+            method::parse_flags(&preset.flags).map_err(|e| format!("Méthode invalide : {}", e))?;
+        }
+        if preset.extension.is_empty() {
+            return Err("L'extension est obligatoire".to_string());
+        }
+        if let Some(filter) = &preset.filter {
+            filter.validate()?;
+        }
+        Ok(preset)
+    }
+
+    /// Retourne true si la liste des presets a été modifiée.
+    /// `current_filter` : filtres actifs dans la fenêtre principale, que le preset peut reprendre
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        presets: &mut Vec<UserPreset>,
+        builtin_names: &[String],
+        current_filter: Option<&SourceFilter>,
+    ) -> bool {
+        let mut changed = false;
+        egui::Window::new("✏ Presets utilisateur")
+            .open(open)
+            .resizable(true)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    // Liste des presets
+                    ui.vertical(|ui| {
+                        ui.set_width(200.0);
+                        if ui.button("➕ Nouveau").clicked() {
+                            self.edit(None, UserPreset::new(self.draft.backend));
+                        }
+                        ui.separator();
+                        if presets.is_empty() {
+                            ui.label(RichText::new("Aucun preset utilisateur").color(Color32::GRAY));
+                        }
+                        let mut action = None;
+                        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                            for (index, preset) in presets.iter().enumerate() {
+                                ui.horizontal(|ui| {
+                                    if ui.selectable_label(self.selected == Some(index), &preset.name).clicked() {
+                                        action = Some((index, false));
+                                    }
+                                    if ui.small_button("⧉").on_hover_text("Dupliquer").clicked() {
+                                        action = Some((index, true));
+                                    }
+                                });
+                            }
+                        });
+                        match action {
+                            Some((index, false)) => self.edit(Some(index), presets[index].clone()),
+                            Some((index, true)) => {
+                                let mut copy = presets[index].clone();
+                                copy.name = format!("{} (copie)", copy.name);
+                                self.edit(None, copy);
+                            }
+                            None => {}
+                        }
+                    });
+
+                    ui.separator();
+
+                    // Formulaire
+                    ui.vertical(|ui| {
+                        egui::Grid::new("preset_editor_grid").num_columns(2).show(ui, |ui| {
+                            ui.label("Nom :");
+                            ui.text_edit_singleline(&mut self.draft.name);
+                            ui.end_row();
+
+                            ui.label("Outil :");
+                            let previous = self.draft.backend;
+                            egui::ComboBox::new("preset_editor_backend", "")
+                                .selected_text(self.draft.backend.backend().name())
+                                .show_ui(ui, |ui| {
+                                    for kind in BackendKind::all() {
+                                        ui.selectable_value(&mut self.draft.backend, *kind, kind.backend().name());
+                                    }
+                                });
+                            if self.draft.backend != previous {
+                                self.draft.extension = self.draft.backend.backend().extensions()[0].to_string();
+                            }
+                            ui.end_row();
+
+                            ui.label("Options :");
+                            ui.add(egui::TextEdit::singleline(&mut self.flags_text)
+                                .hint_text("-i2 -m=xtool:...+lzma")
+                                .desired_width(320.0)
+                                .font(egui::TextStyle::Monospace));
+                            ui.end_row();
+
+                            ui.label("Extension :");
+                            ui.horizontal(|ui| {
+                                ui.add(egui::TextEdit::singleline(&mut self.draft.extension).desired_width(60.0));
+                                for ext in self.draft.backend.backend().extensions() {
+                                    if ui.small_button(*ext).clicked() {
+                                        self.draft.extension = ext.to_string();
+                                    }
+                                }
+                            });
+                            ui.end_row();
+
+                            ui.label("Description :");
+                            ui.add(egui::TextEdit::multiline(&mut self.draft.description).desired_rows(2).desired_width(320.0));
+                            ui.end_row();
+
+                            ui.label("Filtres :");
+                            ui.horizontal(|ui| {
+                                let summary = self.draft.filter.as_ref().map_or("Aucun filtre".to_string(), |f| f.summary());
+                                ui.add(egui::Label::new(RichText::new(summary).color(Color32::GRAY)).truncate());
+                                if ui
+                                    .add_enabled(current_filter.is_some(), egui::Button::new("Reprendre les filtres actuels").small())
+                                    .on_disabled_hover_text("Activez les filtres dans la fenêtre principale")
+                                    .clicked()
+                                {
+                                    self.draft.filter = current_filter.cloned().map(Box::new);
+                                }
+                                if self.draft.filter.is_some() && ui.small_button("✖").on_hover_text("Retirer les filtres").clicked() {
+                                    self.draft.filter = None;
+                                }
+                            });
+                            ui.end_row();
+                        });
+
+                        // Aperçu de la chaîne de méthodes pendant la saisie
+                        if self.draft.backend == BackendKind::FreeArc {
+                            ui.separator();
+                            method::show_analysis(ui, &split_flags(&self.flags_text));
+                        }
+
+                        if let Some(error) = &self.error {
+                            ui.colored_label(Color32::from_rgb(234, 67, 53), error);
+                        }
+
+                        ui.horizontal(|ui| {
+                            if ui.button("💾 Enregistrer").clicked() {
+                                match self.validate(presets, builtin_names) {
+                                    Ok(preset) => {
+                                        match self.selected {
+                                            Some(index) => presets[index] = preset.clone(),
+                                            None => {
+                                                presets.push(preset.clone());
+                                                self.selected = Some(presets.len() - 1);
+                                            }
+                                        }
+                                        self.edit(self.selected, preset);
+                                        changed = true;
+                                    }
+                                    Err(e) => self.error = Some(e),
+                                }
+                            }
+                            if let Some(index) = self.selected {
+                                if ui.button("🗑 Supprimer").clicked() {
+                                    presets.remove(index);
+                                    self.edit(None, UserPreset::new(self.draft.backend));
+                                    changed = true;
+                                }
+                            }
+                        });
+                    });
+                });
+            });
+        changed
+    }
+}
@@ -0,0 +1,88 @@
+// Contrôle d'un processus d'archivage et de ses descendants.
+// FreeArc lance lui-même xtool, srep, precomp... : tuer ou suspendre uniquement arc.exe
+// laisserait ces helpers tourner en arrière-plan.
+
+use std::process::Command;
+use sysinfo::{Pid, ProcessesToUpdate, Signal, System};
+
+/// Pid du processus racine suivi de tous ses descendants
+fn collect_tree(sys: &System, root: Pid) -> Vec<Pid> {
+    let mut tree = vec![root];
+    let mut i = 0;
+    while i < tree.len() {
+        let parent = tree[i];
+        for (pid, process) in sys.processes() {
+            if process.parent() == Some(parent) && !tree.contains(pid) {
+                tree.push(*pid);
+            }
+        }
+        i += 1;
+    }
+    tree
+}
+
+/// Envoie un signal à tout l'arbre, enfants d'abord. Retourne false si un envoi a échoué
+/// ou si le signal n'est pas supporté par la plateforme.
+fn signal_tree(root: u32, signal: Signal) -> bool {
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+    let tree = collect_tree(&sys, Pid::from_u32(root));
+
+    let mut ok = true;
+    for pid in tree.iter().rev() {
+        if let Some(process) = sys.process(*pid) {
+            ok &= process.kill_with(signal).unwrap_or(false);
+        }
+    }
+    ok
+}
+
+pub fn kill_tree(root: u32) -> bool {
+    signal_tree(root, Signal::Kill)
+}
+
+/// SIGSTOP : uniquement sur les systèmes Unix
+pub fn pause_tree(root: u32) -> bool {
+    signal_tree(root, Signal::Stop)
+}
+
+/// SIGCONT : uniquement sur les systèmes Unix
+pub fn resume_tree(root: u32) -> bool {
+    signal_tree(root, Signal::Continue)
+}
+
+/// Mémoire résidente cumulée de l'arbre (octets), pour mesurer le pic d'un outil
+/// et de ses helpers
+pub fn tree_memory(root: u32) -> u64 {
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+    collect_tree(&sys, Pid::from_u32(root))
+        .iter()
+        .filter_map(|pid| sys.process(*pid))
+        .map(|process| process.memory())
+        .sum()
+}
+
+pub fn supports_pause() -> bool {
+    cfg!(unix)
+}
+
+/// Limite la commande (et les helpers qu'elle lance, qui héritent de l'affinité) aux coeurs
+/// du masque, via `taskset`. Sans effet hors Linux.
+pub fn with_affinity(cmd: Command, mask: u64) -> Command {
+    if !cfg!(target_os = "linux") || mask == 0 {
+        return cmd;
+    }
+    let mut wrapped = Command::new("taskset");
+    wrapped.arg(format!("{:x}", mask)).arg(cmd.get_program()).args(cmd.get_args());
+    if let Some(dir) = cmd.get_current_dir() {
+        wrapped.current_dir(dir);
+    }
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => wrapped.env(key, value),
+            None => wrapped.env_remove(key),
+        };
+    }
+    wrapped
+}
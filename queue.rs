@@ -0,0 +1,267 @@
+// File d'attente des opérations (compression, extraction, test, hash, réparation, conversion).
+// Sauvegardée sur disque à chaque modification pour survivre à un redémarrage.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::CompressionPreset;
+use crate::encryption::EncryptionSettings;
+use crate::filters::SourceFilter;
+use crate::groups::FileGroups;
+use crate::overwrite::{OverwritePolicy, Resolution};
+use crate::recovery::RecoverySize;
+use crate::sfx::SfxOptions;
+
+pub const QUEUE_FILE: &str = "stelarc_queue.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JobKind {
+    Compress,
+    Extract,
+    Test,
+    Hash,
+    Update(UpdateOp), // Modification de l'archive `output`
+    Repair,
+    Convert, // Archive `sources[0]` recompressée avec le preset vers `output`
+}
+
+/// Modification d'une archive existante, sans la recompresser entièrement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum UpdateOp {
+    Add,     // `sources` ajoutés (ou remplacés)
+    Freshen, // Entrées de `selection` remplacées si modifiées dans le dossier `sources[0]`
+    Delete,  // Entrées de `selection` supprimées
+    Rename { from: String, to: String },
+}
+
+impl JobKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JobKind::Compress => "Compression",
+            JobKind::Extract => "Extraction",
+            JobKind::Test => "Test",
+            JobKind::Hash => "Hash",
+            JobKind::Repair => "Réparation",
+            JobKind::Convert => "Conversion",
+            JobKind::Update(UpdateOp::Add) => "Ajout",
+            JobKind::Update(UpdateOp::Freshen) => "Actualisation",
+            JobKind::Update(UpdateOp::Delete) => "Suppression",
+            JobKind::Update(UpdateOp::Rename { .. }) => "Renommage",
+        }
+    }
+
+    /// Formulation utilisée dans les messages ("Erreur lors de la compression")
+    pub fn action(&self) -> &'static str {
+        match self {
+            JobKind::Compress => "la compression",
+            JobKind::Extract => "l'extraction",
+            JobKind::Test => "le test",
+            JobKind::Hash => "le calcul du hash",
+            JobKind::Repair => "la réparation",
+            JobKind::Convert => "la conversion",
+            JobKind::Update(UpdateOp::Add) => "l'ajout de fichiers",
+            JobKind::Update(UpdateOp::Freshen) => "l'actualisation de l'archive",
+            JobKind::Update(UpdateOp::Delete) => "la suppression d'entrées",
+            JobKind::Update(UpdateOp::Rename { .. }) => "le renommage",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Done,
+    Failed(String),
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn label(&self) -> String {
+        match self {
+            JobStatus::Pending => "En attente".to_string(),
+            JobStatus::Running => "En cours".to_string(),
+            JobStatus::Done => "Terminé".to_string(),
+            JobStatus::Failed(e) => format!("Échec : {}", e),
+            JobStatus::Cancelled => "Annulé".to_string(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Done | JobStatus::Failed(_) | JobStatus::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: u64,
+    pub kind: JobKind,
+    pub preset: CompressionPreset,
+    /// Fichiers à compresser, ou l'archive / le fichier traité pour les autres opérations
+    pub sources: Vec<PathBuf>,
+    /// Archive produite (compression) ou dossier de destination (extraction)
+    pub output: PathBuf,
+    /// Extraction sélective : entrées de l'archive à extraire (vide = tout)
+    #[serde(default)]
+    pub selection: Vec<String>,
+    /// Compression chiffrée
+    #[serde(default)]
+    pub encryption: Option<EncryptionSettings>,
+    /// Jamais sauvegardé : une tâche chiffrée restaurée devra être relancée avec son mot de passe
+    #[serde(skip)]
+    pub password: Option<String>,
+    /// Nombre de threads imposé (None = celui du preset)
+    #[serde(default)]
+    pub threads: Option<usize>,
+    /// Coeurs autorisés (bit n = coeur n), Linux uniquement
+    #[serde(default)]
+    pub affinity: Option<u64>,
+    /// Taille des volumes (compression découpée)
+    #[serde(default)]
+    pub volume_size: Option<u64>,
+    /// Archive auto-extractible
+    #[serde(default)]
+    pub sfx: Option<SfxOptions>,
+    /// Enregistrement de récupération ajouté à l'archive
+    #[serde(default)]
+    pub recovery: Option<RecoverySize>,
+    /// Filtres appliqués aux sources
+    #[serde(default)]
+    pub filter: Option<SourceFilter>,
+    /// Groupes de fichiers et leurs méthodes (FreeArc)
+    #[serde(default)]
+    pub groups: Option<FileGroups>,
+    /// Extraction : fichiers déjà présents dans la destination
+    #[serde(default)]
+    pub overwrite: OverwritePolicy,
+    /// Décisions prises fichier par fichier (entrée de l'archive), prioritaires sur la politique
+    #[serde(default)]
+    pub resolutions: Vec<(String, Resolution)>,
+    /// Extraction en mode intelligent : `output` est le dossier de base, le sous-dossier est
+    /// décidé au lancement d'après le contenu de l'archive
+    #[serde(default)]
+    pub smart_destination: bool,
+    pub status: JobStatus,
+}
+
+impl Job {
+    pub fn description(&self) -> String {
+        let source = match self.sources.as_slice() {
+            [single] => single.display().to_string(),
+            many => format!("{} éléments", many.len()),
+        };
+        match &self.kind {
+            JobKind::Extract if !self.selection.is_empty() => {
+                format!("{} ({} entrée(s)) → {}", source, self.selection.len(), self.output.display())
+            }
+            JobKind::Compress | JobKind::Extract | JobKind::Convert => format!("{} → {}", source, self.output.display()),
+            JobKind::Test | JobKind::Hash | JobKind::Repair => source,
+            JobKind::Update(UpdateOp::Add) => format!("{} → {}", source, self.output.display()),
+            JobKind::Update(UpdateOp::Freshen) => format!("{} depuis {}", self.output.display(), source),
+            JobKind::Update(UpdateOp::Delete) => format!("{} ({} entrée(s))", self.output.display(), self.selection.len()),
+            JobKind::Update(UpdateOp::Rename { from, to }) => format!("{} : {} → {}", self.output.display(), from, to),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobQueue {
+    pub jobs: Vec<Job>,
+    next_id: u64,
+    pub max_concurrent: usize,
+    /// La file ne démarre de nouvelles tâches que si elle est active
+    #[serde(skip)]
+    pub active: bool,
+}
+
+impl Default for JobQueue {
+    fn default() -> Self {
+        Self { jobs: Vec::new(), next_id: 1, max_concurrent: 1, active: false }
+    }
+}
+
+impl JobQueue {
+    /// Charge la file sauvegardée ; les tâches interrompues repassent en attente
+    pub fn load(path: &Path) -> JobQueue {
+        let mut queue: JobQueue = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        for job in &mut queue.jobs {
+            if job.status == JobStatus::Running {
+                job.status = JobStatus::Pending;
+            }
+        }
+        queue.max_concurrent = queue.max_concurrent.max(1);
+        queue
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, content).map_err(|e| e.to_string())
+    }
+
+    pub fn push(&mut self, kind: JobKind, preset: CompressionPreset, sources: Vec<PathBuf>, output: PathBuf) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.jobs.push(Job {
+            id,
+            kind,
+            preset,
+            sources,
+            output,
+            selection: Vec::new(),
+            encryption: None,
+            password: None,
+            threads: None,
+            affinity: None,
+            volume_size: None,
+            sfx: None,
+            recovery: None,
+            filter: None,
+            groups: None,
+            overwrite: OverwritePolicy::default(),
+            resolutions: Vec::new(),
+            smart_destination: false,
+            status: JobStatus::Pending,
+        });
+        id
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut Job> {
+        self.jobs.iter_mut().find(|j| j.id == id)
+    }
+
+    pub fn set_status(&mut self, id: u64, status: JobStatus) {
+        if let Some(job) = self.get_mut(id) {
+            job.status = status;
+        }
+    }
+
+    pub fn next_pending(&self) -> Option<&Job> {
+        self.jobs.iter().find(|j| j.status == JobStatus::Pending)
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.jobs.iter().filter(|j| j.status == JobStatus::Pending).count()
+    }
+
+    pub fn remove(&mut self, id: u64) {
+        self.jobs.retain(|j| j.id != id || j.status == JobStatus::Running);
+    }
+
+    pub fn clear_finished(&mut self) {
+        self.jobs.retain(|j| !j.status.is_finished());
+    }
+
+    /// Déplace une tâche d'un cran (-1 vers le haut, +1 vers le bas)
+    pub fn move_job(&mut self, id: u64, offset: isize) {
+        if let Some(index) = self.jobs.iter().position(|j| j.id == id) {
+            let target = index as isize + offset;
+            if target >= 0 && (target as usize) < self.jobs.len() {
+                self.jobs.swap(index, target as usize);
+            }
+        }
+    }
+}
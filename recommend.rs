@@ -0,0 +1,133 @@
+// Recommandation de preset à partir du contenu de la sélection : répartition par extension,
+// part de données déjà compressées, de flux zlib/deflate et de doublons probables.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{format_size, CompressionPreset, FileStats};
+
+/// Formats déjà compressés : précompression et xtool n'y gagnent rien
+const COMPRESSED: &[&str] = &[
+    "mp4", "mkv", "avi", "webm", "mov", "wmv", "flv", "mp3", "aac", "ogg", "opus", "flac", "m4a", "wma",
+    "jpg", "jpeg", "png", "gif", "webp", "heic", "avif", "zip", "7z", "rar", "gz", "tgz", "bz2", "xz",
+    "zst", "lz4", "br", "arc", "cab", "stel", "iso",
+];
+
+/// Conteneurs riches en flux zlib/deflate (données de jeux, documents) : terrain de xtool[preflate]
+const ZLIB_HEAVY: &[&str] = &[
+    "pak", "pk3", "pk4", "pk7", "upk", "unity3d", "assets", "bundle", "resource", "xnb", "swf", "pdf",
+    "docx", "xlsx", "pptx", "odt", "ods", "odp", "jar", "apk", "epub",
+];
+
+/// Texte et sources : LZMA avec un grand dictionnaire
+const TEXT: &[&str] = &[
+    "txt", "log", "csv", "tsv", "json", "xml", "html", "htm", "css", "js", "ts", "md", "ini", "cfg",
+    "yaml", "yml", "toml", "sql", "rs", "c", "h", "cpp", "hpp", "cs", "java", "py", "go", "php", "sh", "bat",
+];
+
+/// Taille minimale pour qu'un fichier compte comme doublon probable
+const DUPLICATE_MIN_SIZE: u64 = 1024 * 1024;
+
+pub struct Recommendation {
+    pub preset: CompressionPreset,
+    pub reasons: Vec<String>,
+}
+
+fn scan_path(path: &Path, stats: &mut FileStats, sizes: &mut HashMap<u64, usize>) {
+    if path.is_dir() {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.filter_map(|e| e.ok()) {
+                scan_path(&entry.path(), stats, sizes);
+            }
+        }
+        return;
+    }
+    let Ok(metadata) = fs::metadata(path) else { return };
+    let size = metadata.len();
+    stats.total_size += size;
+    stats.file_count += 1;
+    if size > stats.largest_file.1 {
+        stats.largest_file = (path.to_path_buf(), size);
+    }
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let entry = stats.by_extension.entry(ext).or_insert((0, 0));
+    entry.0 += 1;
+    entry.1 += size;
+    if size >= DUPLICATE_MIN_SIZE {
+        *sizes.entry(size).or_insert(0) += 1;
+    }
+}
+
+/// Parcourt la sélection (récursivement pour les dossiers)
+pub fn scan(paths: &[PathBuf]) -> FileStats {
+    let mut stats = FileStats {
+        total_size: 0,
+        file_count: 0,
+        largest_file: (PathBuf::new(), 0),
+        by_extension: HashMap::new(),
+        duplicate_size: 0,
+    };
+    let mut sizes = HashMap::new();
+    for path in paths {
+        scan_path(path, &mut stats, &mut sizes);
+    }
+    // Fichiers volumineux de taille identique : copies probables, que srep déduplique
+    stats.duplicate_size = sizes.iter().filter(|(_, count)| **count > 1).map(|(size, count)| size * (*count as u64 - 1)).sum();
+    stats
+}
+
+fn share(stats: &FileStats, extensions: &[&str]) -> f64 {
+    let bytes: u64 = stats
+        .by_extension
+        .iter()
+        .filter(|(ext, _)| extensions.contains(&ext.as_str()))
+        .map(|(_, (_, size))| size)
+        .sum();
+    bytes as f64 / stats.total_size.max(1) as f64
+}
+
+pub fn recommend(stats: &FileStats) -> Option<Recommendation> {
+    if stats.total_size == 0 {
+        return None;
+    }
+    let compressed = share(stats, COMPRESSED);
+    let zlib = share(stats, ZLIB_HEAVY);
+    let text = share(stats, TEXT);
+    let duplicates = stats.duplicate_size as f64 / stats.total_size as f64;
+    let percent = |value: f64| format!("{:.0} %", value * 100.0);
+
+    let mut reasons = vec![format!("{} fichier(s), {}", stats.file_count, format_size(stats.total_size))];
+    let mut top: Vec<_> = stats.by_extension.iter().collect();
+    top.sort_by_key(|(_, (_, size))| std::cmp::Reverse(*size));
+    let top: Vec<String> = top
+        .iter()
+        .take(3)
+        .map(|(ext, (_, size))| {
+            let ext = if ext.is_empty() { "(sans extension)".to_string() } else { format!(".{}", ext) };
+            format!("{} {}", ext, percent(*size as f64 / stats.total_size as f64))
+        })
+        .collect();
+    reasons.push(format!("Principaux types : {}", top.join(", ")));
+
+    let preset = if compressed >= 0.7 {
+        reasons.push(format!("{} de données déjà compressées (vidéo, images, archives) : précompression et xtool inutiles, compression rapide", percent(compressed)));
+        CompressionPreset::Instant
+    } else if zlib >= 0.3 {
+        reasons.push(format!("{} de conteneurs riches en flux zlib (données de jeu, documents) : xtool[preflate] les décompresse avant LZMA", percent(zlib)));
+        CompressionPreset::Xtoolj
+    } else if duplicates >= 0.2 {
+        reasons.push(format!("{} probablement dupliqués (fichiers volumineux de même taille) : srep élimine les répétitions à longue distance", percent(duplicates)));
+        CompressionPreset::FastSrepLZ
+    } else if text >= 0.5 {
+        reasons.push(format!("{} de texte ou de code : LZMA avec grand dictionnaire", percent(text)));
+        CompressionPreset::Maximum
+    } else {
+        reasons.push("Contenu varié : compromis vitesse/taux".to_string());
+        CompressionPreset::Normal
+    };
+    if (0.2..0.7).contains(&compressed) {
+        reasons.push(format!("Note : {} des données sont déjà compressées et gagneront peu", percent(compressed)));
+    }
+    Some(Recommendation { preset, reasons })
+}
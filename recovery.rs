@@ -0,0 +1,171 @@
+// Enregistrement de récupération (FreeArc -rr) et réparation d'archives endommagées (`arc r`).
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::backend::{self, BackendKind};
+use crate::format_size;
+
+const KB: u64 = 1024;
+const MB: u64 = 1024 * KB;
+
+/// Taille de l'enregistrement de récupération ajouté à la compression
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RecoverySize {
+    Percent(f32), // Part de la taille de l'archive
+    Bytes(u64),
+}
+
+impl Default for RecoverySize {
+    fn default() -> Self {
+        RecoverySize::Percent(3.0)
+    }
+}
+
+impl RecoverySize {
+    /// `5%`, `0.5%`, ou une taille suivie de k, m ou g (`512k`, `4m`)
+    pub fn parse(text: &str) -> Option<RecoverySize> {
+        let text = text.trim().to_lowercase();
+        if let Some(percent) = text.strip_suffix('%') {
+            let value: f32 = percent.trim().replace(',', ".").parse().ok()?;
+            return (value > 0.0 && value < 100.0).then_some(RecoverySize::Percent(value));
+        }
+        let text = text.trim_end_matches(['b', 'o']);
+        let (number, unit) = match text.char_indices().last()? {
+            (i, 'k') => (&text[..i], KB),
+            (i, 'm') => (&text[..i], MB),
+            (i, 'g') => (&text[..i], 1024 * MB),
+            _ => (text, 1),
+        };
+        let value: f64 = number.trim().replace(',', ".").parse().ok()?;
+        let bytes = (value * unit as f64) as u64;
+        (bytes > 0).then_some(RecoverySize::Bytes(bytes))
+    }
+
+    /// Option FreeArc : `-rr5%`, `-rr4mb`, `-rr512kb`
+    pub fn freearc_arg(&self) -> String {
+        match *self {
+            RecoverySize::Percent(value) => format!("-rr{}%", value),
+            RecoverySize::Bytes(bytes) if bytes.is_multiple_of(MB) => format!("-rr{}mb", bytes / MB),
+            RecoverySize::Bytes(bytes) => format!("-rr{}kb", bytes.div_ceil(KB)),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match *self {
+            RecoverySize::Percent(value) => format!("{}%", value),
+            RecoverySize::Bytes(bytes) => format_size(bytes),
+        }
+    }
+}
+
+/// Résultat d'une réparation, d'après la sortie de l'outil
+#[derive(Debug, Clone)]
+pub struct RepairReport {
+    pub recovered: Vec<String>,
+    pub unrecoverable: Vec<String>,
+    /// Archive réparée écrite à part (FreeArc : `fixed.<nom>`), sinon réparation sur place
+    pub repaired: Option<PathBuf>,
+    pub success: bool,
+}
+
+/// Mots de la ligne en minuscules, sans la ponctuation qui les entoure : un nom de fichier
+/// (`fixed.txt`, `failed_tests.log`) reste un seul mot et ne passe pas pour un message
+fn words(line: &str) -> Vec<String> {
+    line.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| c.is_ascii_punctuation()).to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+impl RepairReport {
+    /// `lines` : sortie complète de la commande, sans préfixe ; `started` : lancement de la
+    /// commande, un `fixed.<nom>` plus ancien vient d'une réparation précédente
+    pub fn build(archive: &Path, lines: &[String], success: bool, started: SystemTime) -> RepairReport {
+        let mut recovered = Vec::new();
+        let mut unrecoverable = Vec::new();
+        for line in lines {
+            let words = words(line);
+            let has = |list: &[&str]| words.iter().any(|w| list.contains(&w.as_str()));
+            if has(&["can't", "cannot", "unrecoverable", "failed"]) || words.windows(2).any(|w| w == ["not", "enough"]) {
+                unrecoverable.push(line.trim().to_string());
+            } else if has(&["recovered", "repaired", "fixed", "restored"]) {
+                recovered.push(line.trim().to_string());
+            }
+        }
+        let repaired = fixed_archive(archive)
+            .filter(|p| p.metadata().and_then(|m| m.modified()).is_ok_and(|modified| modified >= started));
+        RepairReport { recovered, unrecoverable, repaired, success }
+    }
+
+    pub fn summary(&self) -> String {
+        if !self.success {
+            return format!("Réparation impossible : {} problème(s) non récupérable(s)", self.unrecoverable.len().max(1));
+        }
+        let target = match &self.repaired {
+            Some(path) => format!("archive réparée : {}", path.display()),
+            None => "archive réparée sur place".to_string(),
+        };
+        if self.recovered.is_empty() {
+            format!("Réparation terminée, aucun bloc endommagé trouvé ({})", target)
+        } else {
+            format!("Réparation terminée : {} élément(s) récupéré(s), {}", self.recovered.len(), target)
+        }
+    }
+}
+
+/// Emplacement où FreeArc écrit l'archive réparée : `fixed.<nom>` à côté de l'original
+pub fn fixed_archive(archive: &Path) -> Option<PathBuf> {
+    let name = archive.file_name()?.to_str()?;
+    Some(archive.with_file_name(format!("fixed.{}", name)))
+}
+
+/// Réparation en mode console : sortie de l'outil puis détail de ce qui a été récupéré
+pub fn run_repair_cli(archive: &Path) -> bool {
+    let Ok(archive) = &std::fs::canonicalize(archive) else {
+        eprintln!("Erreur : L'archive n'existe pas");
+        return false;
+    };
+    let backend = BackendKind::for_archive(archive).backend();
+    if !backend.is_available() {
+        eprintln!("Erreur : {} n'est pas installé correctement", backend.name());
+        return false;
+    }
+    let Some(mut cmd) = backend.repair_command(archive) else {
+        eprintln!("Erreur : {} ne sait pas réparer une archive", backend.name());
+        return false;
+    };
+    if let Some(dir) = archive.parent().filter(|p| !p.as_os_str().is_empty()) {
+        cmd.current_dir(dir);
+    }
+    let started = SystemTime::now();
+    let output = match cmd.stdin(std::process::Stdio::null()).output() {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("Erreur lors de l'exécution de la commande : {}", e);
+            return false;
+        }
+    };
+    let mut lines = Vec::new();
+    for stream in [&output.stdout, &output.stderr] {
+        backend::read_output(stream.as_slice(), |line, complete| {
+            if complete {
+                println!("{}", line);
+                lines.push(line.to_string());
+            }
+        });
+    }
+
+    let report = RepairReport::build(archive, &lines, output.status.success(), started);
+    println!("----------------------------------------");
+    for line in &report.recovered {
+        println!("[OK]     {}", line);
+    }
+    for line in &report.unrecoverable {
+        println!("[ERREUR] {}", line);
+    }
+    println!("----------------------------------------");
+    println!("{}", report.summary());
+    report.success
+}
@@ -0,0 +1,268 @@
+// Vérification des entrées d'une archive avant extraction : chemins remontant hors de la
+// destination (`../`), chemins absolus, noms de périphérique Windows, liens symboliques
+// pointant à l'extérieur et noms contenant des jokers (`*`, `?`), que les outils liraient
+// comme des motifs dans les fichiers liste. Les entrées refusées ne sont pas extraites.
+
+use crate::listing::ArchiveEntry;
+
+/// Noms réservés par Windows, quelle que soit l'extension (`nul.txt`)
+const DEVICE_NAMES: &[&str] = &["CON", "PRN", "AUX", "NUL", "CONIN$", "CONOUT$"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnsafeReason {
+    Traversal,
+    Absolute,
+    DeviceName(String),
+    Symlink(String),        // Cible du lien, vide si inconnue
+    ThroughSymlink(String), // Lien de l'archive traversé par le chemin ou la cible
+    Wildcard,        // `*` ou `?` : motif dans une liste @fichier, d'autres entrées seraient extraites
+}
+
+impl UnsafeReason {
+    pub fn label(&self) -> String {
+        match self {
+            UnsafeReason::Traversal => "chemin remontant hors du dossier d'extraction".to_string(),
+            UnsafeReason::Absolute => "chemin absolu".to_string(),
+            UnsafeReason::DeviceName(name) => format!("nom de périphérique réservé ({})", name),
+            UnsafeReason::Symlink(target) if target.is_empty() => "lien symbolique dont la cible n'est pas vérifiable".to_string(),
+            UnsafeReason::Symlink(target) => format!("lien symbolique vers {} hors du dossier d'extraction", target),
+            UnsafeReason::ThroughSymlink(link) => format!("chemin passant par le lien symbolique {}", link),
+            UnsafeReason::Wildcard => "nom contenant un caractère joker (* ou ?)".to_string(),
+        }
+    }
+}
+
+fn is_absolute(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with('/') || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+fn device_name(component: &str) -> Option<String> {
+    // Windows ignore l'extension, les espaces et les points finaux : "NUL .txt" est NUL
+    let stem = component.split('.').next().unwrap_or(component).trim_end_matches([' ', '.']).to_ascii_uppercase();
+    let numbered = ["COM", "LPT"]
+        .iter()
+        .any(|prefix| stem.strip_prefix(prefix).is_some_and(|n| n.len() == 1 && n.as_bytes()[0].is_ascii_digit() && n != "0"));
+    (numbered || DEVICE_NAMES.contains(&stem.as_str())).then_some(stem)
+}
+
+/// Composants d'un chemin d'archive, sans les vides ni les `.`
+fn components(path: &str) -> Vec<&str> {
+    path.split('/').filter(|c| !c.is_empty() && *c != ".").collect()
+}
+
+/// Le chemin, relatif à `base` (dossier, composants séparés par '/'), reste-t-il dans la destination ?
+fn stays_inside(base: &[&str], path: &str) -> bool {
+    let mut depth = base.len();
+    for component in components(path) {
+        match component {
+            ".." if depth == 0 => return false,
+            ".." => depth -= 1,
+            _ => depth += 1,
+        }
+    }
+    true
+}
+
+/// Premier lien de `links` traversé par `path`, relatif à `base`. Un lien peut désigner
+/// n'importe quel dossier (`l1 -> .`) : la profondeur lexicale d'un chemin qui passe par
+/// lui ne dit plus rien de l'endroit où il mène
+fn through_link(links: &[String], base: &[&str], path: &[&str]) -> Option<String> {
+    let mut current: Vec<&str> = base.to_vec();
+    for component in path {
+        match *component {
+            ".." => {
+                current.pop();
+            }
+            name => {
+                current.push(name);
+                let joined = current.join("/");
+                if links.contains(&joined) {
+                    return Some(joined);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Raison du refus d'une entrée, None si elle peut être extraite
+pub fn check_entry(entry: &ArchiveEntry) -> Option<UnsafeReason> {
+    let path = entry.path.as_str();
+    if is_absolute(path) {
+        return Some(UnsafeReason::Absolute);
+    }
+    if path.split('/').any(|c| c == "..") {
+        return Some(UnsafeReason::Traversal);
+    }
+    if let Some(name) = path.split('/').find_map(device_name) {
+        return Some(UnsafeReason::DeviceName(name));
+    }
+    if path.contains(['*', '?']) {
+        return Some(UnsafeReason::Wildcard);
+    }
+    if let Some(target) = &entry.symlink {
+        let parent = components(path);
+        let parent = &parent[..parent.len().saturating_sub(1)];
+        if target.is_empty() || is_absolute(target) || !stays_inside(parent, target) {
+            return Some(UnsafeReason::Symlink(target.clone()));
+        }
+    }
+    None
+}
+
+/// Chaînes de liens : `l1 -> .` puis `l1/l2 -> ..` sort de la destination alors que chaque
+/// lien, pris seul, y reste. Refuse l'entrée dont le dossier ou la cible passe par un lien
+/// de l'archive (`links`)
+fn check_links(entry: &ArchiveEntry, links: &[String]) -> Option<UnsafeReason> {
+    let parts = components(&entry.path);
+    let parent = &parts[..parts.len().saturating_sub(1)];
+    if let Some(link) = through_link(links, &[], parent) {
+        return Some(UnsafeReason::ThroughSymlink(link));
+    }
+    let target = entry.symlink.as_deref()?;
+    through_link(links, parent, &components(target)).map(UnsafeReason::ThroughSymlink)
+}
+
+/// Bilan de la vérification des entrées d'une archive
+#[derive(Debug, Clone, Default)]
+pub struct SafetyReport {
+    pub checked: usize,
+    pub rejected: Vec<(String, UnsafeReason)>,
+}
+
+impl SafetyReport {
+    pub fn check(entries: &[ArchiveEntry]) -> SafetyReport {
+        // Tous les liens, quel que soit leur rang : l'ordre d'extraction n'est pas garanti
+        let links: Vec<String> = entries.iter().filter(|e| e.symlink.is_some()).map(|e| components(&e.path).join("/")).collect();
+        let rejected = entries
+            .iter()
+            .filter_map(|e| check_entry(e).or_else(|| check_links(e, &links)).map(|reason| (e.path.clone(), reason)))
+            .collect();
+        SafetyReport { checked: entries.len(), rejected }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.rejected.is_empty()
+    }
+
+    /// Entrées sans danger ; une entrée placée sous une entrée refusée l'est aussi
+    pub fn safe_entries(&self, entries: &[ArchiveEntry]) -> Vec<ArchiveEntry> {
+        entries
+            .iter()
+            .filter(|e| {
+                !self.rejected.iter().any(|(path, _)| {
+                    e.path == *path || e.path.strip_prefix(path.as_str()).is_some_and(|rest| rest.starts_with('/'))
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Une ligne par entrée refusée, pour le journal
+    pub fn log_lines(&self) -> Vec<String> {
+        self.rejected.iter().map(|(path, reason)| format!("Entrée refusée : {} ({})", path, reason.label())).collect()
+    }
+
+    pub fn summary(&self) -> String {
+        if self.rejected.is_empty() {
+            return format!("Sécurité : {} entrée(s) vérifiée(s), aucune entrée dangereuse", self.checked);
+        }
+        let count = |f: fn(&UnsafeReason) -> bool| self.rejected.iter().filter(|(_, r)| f(r)).count();
+        let details: Vec<String> = [
+            ("chemin(s) remontant(s)", count(|r| *r == UnsafeReason::Traversal)),
+            ("chemin(s) absolu(s)", count(|r| *r == UnsafeReason::Absolute)),
+            ("nom(s) de périphérique", count(|r| matches!(r, UnsafeReason::DeviceName(_)))),
+            ("lien(s) symbolique(s)", count(|r| matches!(r, UnsafeReason::Symlink(_) | UnsafeReason::ThroughSymlink(_)))),
+            ("nom(s) avec joker", count(|r| *r == UnsafeReason::Wildcard)),
+        ]
+        .iter()
+        .filter(|(_, n)| *n > 0)
+        .map(|(label, n)| format!("{} {}", n, label))
+        .collect();
+        format!(
+            "Sécurité : {} entrée(s) refusée(s) sur {} ({})",
+            self.rejected.len(),
+            self.checked,
+            details.join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str) -> ArchiveEntry {
+        ArchiveEntry { path: path.to_string(), ..Default::default() }
+    }
+
+    fn link(path: &str, target: &str) -> ArchiveEntry {
+        ArchiveEntry { path: path.to_string(), symlink: Some(target.to_string()), ..Default::default() }
+    }
+
+    fn rejected(entries: &[ArchiveEntry]) -> Vec<(String, UnsafeReason)> {
+        SafetyReport::check(entries).rejected
+    }
+
+    #[test]
+    fn accepts_ordinary_entries() {
+        let entries = [file("dossier/fichier.txt"), file("./a/b.c"), file("COM0.txt"), link("d/l", "../autre")];
+        assert!(SafetyReport::check(&entries).is_clean());
+    }
+
+    #[test]
+    fn rejects_traversal() {
+        assert_eq!(check_entry(&file("../x")), Some(UnsafeReason::Traversal));
+        assert_eq!(check_entry(&file("a/../../x")), Some(UnsafeReason::Traversal));
+    }
+
+    #[test]
+    fn rejects_absolute_paths() {
+        assert_eq!(check_entry(&file("/etc/passwd")), Some(UnsafeReason::Absolute));
+        assert_eq!(check_entry(&file("C:/Windows/x.dll")), Some(UnsafeReason::Absolute));
+    }
+
+    #[test]
+    fn rejects_device_names() {
+        assert_eq!(check_entry(&file("nul.txt")), Some(UnsafeReason::DeviceName("NUL".to_string())));
+        assert_eq!(check_entry(&file("dossier/com1")), Some(UnsafeReason::DeviceName("COM1".to_string())));
+        assert_eq!(check_entry(&file("Aux .log")), Some(UnsafeReason::DeviceName("AUX".to_string())));
+    }
+
+    #[test]
+    fn rejects_wildcards() {
+        assert_eq!(check_entry(&file("photos/*.jpg")), Some(UnsafeReason::Wildcard));
+        assert_eq!(check_entry(&file("a?b")), Some(UnsafeReason::Wildcard));
+    }
+
+    #[test]
+    fn rejects_escaping_symlinks() {
+        assert_eq!(check_entry(&link("l", "../x")), Some(UnsafeReason::Symlink("../x".to_string())));
+        assert_eq!(check_entry(&link("l", "/etc")), Some(UnsafeReason::Symlink("/etc".to_string())));
+        assert_eq!(check_entry(&link("l", "")), Some(UnsafeReason::Symlink(String::new())));
+    }
+
+    #[test]
+    fn rejects_symlink_chains() {
+        // l1 -> . puis l1/l2 -> .. : l2 est en réalité à la racine, sa cible en sort
+        let chain = [link("l1", "."), link("l1/l2", "..")];
+        assert_eq!(rejected(&chain), vec![("l1/l2".to_string(), UnsafeReason::ThroughSymlink("l1".to_string()))]);
+
+        // Cible passant par un autre lien
+        let target = [link("l1", "."), link("l2", "l1/..")];
+        assert_eq!(rejected(&target), vec![("l2".to_string(), UnsafeReason::ThroughSymlink("l1".to_string()))]);
+
+        // Fichier écrit à travers un lien, même placé avant lui dans l'archive
+        let write = [file("l1/x.txt"), link("l1", "sous-dossier")];
+        assert_eq!(rejected(&write), vec![("l1/x.txt".to_string(), UnsafeReason::ThroughSymlink("l1".to_string()))]);
+    }
+
+    #[test]
+    fn safe_entries_drop_children_of_rejected_entries() {
+        let entries = [link("l", "../x"), file("l/a.txt"), file("la.txt")];
+        let report = SafetyReport::check(&entries);
+        let safe: Vec<String> = report.safe_entries(&entries).into_iter().map(|e| e.path).collect();
+        assert_eq!(safe, vec!["la.txt".to_string()]);
+    }
+}
@@ -0,0 +1,260 @@
+// Archives auto-extractibles : choix du module, texte de configuration (titre, message,
+// dossier, écrasement, commande finale), icône et vérification de l'exécutable produit.
+// FreeArc intègre le module lui-même (-sfx) ; pour 7-Zip, module, configuration et
+// archive sont mis bout à bout, comme le fait `copy /b 7zSD.sfx + config.txt + archive.7z`.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::backend::BackendKind;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SfxOverwrite {
+    Ask,
+    Always,
+    Never,
+}
+
+impl SfxOverwrite {
+    pub fn all() -> &'static [SfxOverwrite] {
+        &[SfxOverwrite::Ask, SfxOverwrite::Always, SfxOverwrite::Never]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SfxOverwrite::Ask => "Demander",
+            SfxOverwrite::Always => "Toujours écraser",
+            SfxOverwrite::Never => "Ne jamais écraser",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SfxOptions {
+    pub module: PathBuf,
+    pub extract_dir: String, // Dossier proposé, ex. "%ProgramFiles%\MonJeu"
+    pub title: String,
+    pub message: String, // Affiché avant l'extraction
+    pub overwrite: SfxOverwrite,
+    pub run_after: String, // Commande lancée après l'extraction
+    pub icon: Option<PathBuf>,
+}
+
+impl Default for SfxOptions {
+    fn default() -> Self {
+        Self {
+            module: PathBuf::new(),
+            extract_dir: String::new(),
+            title: String::new(),
+            message: String::new(),
+            overwrite: SfxOverwrite::Ask,
+            run_after: String::new(),
+            icon: None,
+        }
+    }
+}
+
+impl SfxOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.module.is_file() {
+            return Err(format!("Module SFX introuvable : {}", self.module.display()));
+        }
+        if let Some(icon) = &self.icon {
+            if !icon.is_file() {
+                return Err(format!("Icône introuvable : {}", icon.display()));
+            }
+            if !icon.extension().is_some_and(|e| e.eq_ignore_ascii_case("ico")) {
+                return Err("L'icône doit être un fichier .ico".to_string());
+            }
+            if !rcedit_path().exists() {
+                return Err(format!("Changer l'icône nécessite rcedit ({})", rcedit_path().display()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Bloc de configuration au format des modules SFX 7-Zip ; FreeArc le reçoit comme
+    /// commentaire d'archive, que ses modules installateurs lisent de la même façon
+    pub fn config_text(&self) -> String {
+        let quote = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+        let mut lines = vec![";!@Install@!UTF-8!".to_string()];
+        if !self.title.is_empty() {
+            lines.push(format!("Title=\"{}\"", quote(&self.title)));
+        }
+        if !self.message.is_empty() {
+            lines.push(format!("BeginPrompt=\"{}\"", quote(&self.message)));
+        }
+        if !self.extract_dir.is_empty() {
+            lines.push(format!("InstallPath=\"{}\"", quote(&self.extract_dir)));
+        }
+        match self.overwrite {
+            SfxOverwrite::Ask => {}
+            SfxOverwrite::Always => lines.push("OverwriteMode=\"0\"".to_string()),
+            SfxOverwrite::Never => lines.push("OverwriteMode=\"1\"".to_string()),
+        }
+        if !self.run_after.is_empty() {
+            lines.push(format!("RunProgram=\"{}\"", quote(&self.run_after)));
+        }
+        lines.push(";!@InstallEnd@!".to_string());
+        lines.join("\r\n") + "\r\n"
+    }
+}
+
+pub fn rcedit_path() -> PathBuf {
+    PathBuf::from(if cfg!(windows) { ".\\rcedit\\rcedit.exe" } else { "./rcedit/rcedit" })
+}
+
+/// Modules .sfx présents dans les dossiers donnés
+pub fn find_modules(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut modules: Vec<PathBuf> = dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("sfx")))
+        .collect();
+    modules.sort();
+    modules.dedup();
+    modules
+}
+
+/// Construction en cours d'une archive auto-extractible ; les fichiers temporaires
+/// (module avec icône, configuration, archive intermédiaire) sont supprimés avec elle
+pub struct SfxBuild {
+    pub backend: BackendKind,
+    pub module: PathBuf,
+    pub config: PathBuf,
+    /// Archive produite par l'outil ; différente de `output` quand il faut l'accoler au module
+    pub archive: PathBuf,
+    pub output: PathBuf,
+    temporary: Vec<PathBuf>,
+}
+
+impl SfxBuild {
+    /// Prépare module et configuration ; l'outil écrit directement l'exécutable tant que
+    /// `separate_archive` n'a pas été appelé
+    pub fn prepare(options: &SfxOptions, backend: BackendKind, output: &Path, tag: u64) -> Result<Self, String> {
+        options.validate()?;
+        let temp = std::env::temp_dir();
+        let mut temporary = Vec::new();
+
+        let config = temp.join(format!("stelarc_sfx_{}.txt", tag));
+        fs::write(&config, options.config_text()).map_err(|e| format!("Configuration SFX : {}", e))?;
+        temporary.push(config.clone());
+
+        // L'icône est changée sur une copie du module, avant d'y accoler l'archive :
+        // rcedit réécrit l'exécutable et perdrait les données ajoutées à la fin
+        let module = match &options.icon {
+            Some(icon) => {
+                let copy = temp.join(format!("stelarc_sfx_{}.sfx", tag));
+                fs::copy(&options.module, &copy).map_err(|e| format!("Copie du module SFX : {}", e))?;
+                temporary.push(copy.clone());
+                let status = Command::new(rcedit_path())
+                    .arg(&copy)
+                    .arg("--set-icon")
+                    .arg(icon)
+                    .status()
+                    .map_err(|e| format!("rcedit : {}", e))?;
+                if !status.success() {
+                    return Err(format!("rcedit n'a pas pu appliquer l'icône (code {:?})", status.code()));
+                }
+                copy
+            }
+            None => options.module.clone(),
+        };
+
+        Ok(Self { backend, module, config, archive: output.to_path_buf(), output: output.to_path_buf(), temporary })
+    }
+
+    /// L'outil produit une archive intermédiaire, accolée au module par `finish`
+    pub fn separate_archive(&mut self, tag: u64) {
+        let name = self.output.file_stem().and_then(|n| n.to_str()).unwrap_or("archive");
+        let ext = self.backend.backend().extensions()[0];
+        self.archive = self.output.with_file_name(format!("stelarc_sfx_{}_{}.{}", tag, name, ext));
+        self.temporary.push(self.archive.clone());
+    }
+
+    /// Assemble l'exécutable si besoin, puis vérifie qu'il se teste correctement.
+    /// Retourne les lignes à afficher dans le journal.
+    pub fn finish(&self) -> Result<Vec<String>, String> {
+        let mut log = Vec::new();
+        if self.archive != self.output {
+            let mut output = File::create(&self.output).map_err(|e| format!("{} : {}", self.output.display(), e))?;
+            for part in [&self.module, &self.config, &self.archive] {
+                let mut input = File::open(part).map_err(|e| format!("{} : {}", part.display(), e))?;
+                std::io::copy(&mut input, &mut output).map_err(|e| e.to_string())?;
+            }
+            output.flush().map_err(|e| e.to_string())?;
+            log.push(format!("Exécutable auto-extractible créé : {}", self.output.display()));
+        }
+        make_executable(&self.output)?;
+        log.push(self.self_test()?);
+        Ok(log)
+    }
+
+    /// Auto-test de l'exécutable, seulement si son format correspond à la plateforme
+    fn self_test(&self) -> Result<String, String> {
+        let format = executable_format(&self.output)?;
+        let host = if cfg!(windows) { "Windows" } else if cfg!(target_os = "linux") { "Linux" } else { "" };
+        if format != host {
+            return Ok(format!(
+                "Attention : exécutable {} non testable sur cette plateforme ; testez-le sur la machine cible",
+                format
+            ));
+        }
+        let output = fs::canonicalize(&self.output).map_err(|e| e.to_string())?;
+        let mut cmd = self
+            .backend
+            .backend()
+            .sfx_test_command(&output)
+            .ok_or("Cet outil ne sait pas tester une archive auto-extractible")?;
+        let result = cmd
+            .stdin(std::process::Stdio::null())
+            .output()
+            .map_err(|e| format!("Impossible de lancer l'auto-test : {}", e))?;
+        if result.status.success() {
+            Ok("Auto-test de l'exécutable réussi".to_string())
+        } else {
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            Err(format!("Échec de l'auto-test de l'exécutable (code {:?}) {}", result.status.code(), stderr.trim()))
+        }
+    }
+}
+
+impl Drop for SfxBuild {
+    fn drop(&mut self) {
+        for path in &self.temporary {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Plateforme de l'exécutable d'après son en-tête
+fn executable_format(path: &Path) -> Result<&'static str, String> {
+    let mut header = [0u8; 4];
+    File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map_err(|e| format!("{} : {}", path.display(), e))?;
+    Ok(match header {
+        [b'M', b'Z', ..] => "Windows",
+        [0x7f, b'E', b'L', b'F'] => "Linux",
+        _ => return Err("Le fichier produit n'est pas un exécutable (module SFX invalide ?)".to_string()),
+    })
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = fs::metadata(path).map_err(|e| e.to_string())?.permissions();
+    permissions.set_mode(permissions.mode() | 0o755);
+    fs::set_permissions(path, permissions).map_err(|e| e.to_string())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
@@ -0,0 +1,176 @@
+// Test d'intégrité d'une archive (`arc t`, `7z t`) et rapport fichier par fichier.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::backend::{self, BackendKind};
+use crate::listing;
+
+/// Ligne de sortie reconnue pendant un test
+#[derive(Debug, Clone)]
+pub enum TestLine {
+    Passed(String),
+    /// (chemin, message) ; chemin vide pour une erreur qui concerne toute l'archive
+    Failed(String, String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestOutcome {
+    Passed,
+    Failed(String),
+    /// Le test a échoué sans que l'outil ne désigne de fichier
+    Unverified,
+}
+
+#[derive(Debug, Clone)]
+pub struct TestReport {
+    pub archive: PathBuf,
+    pub results: Vec<(String, TestOutcome)>,
+    pub archive_errors: Vec<String>,
+    pub success: bool,
+}
+
+impl TestReport {
+    /// `listed` : fichiers de l'archive quand l'outil sait la lister, sinon seuls
+    /// les fichiers cités dans la sortie du test apparaissent dans le rapport
+    pub fn build(archive: PathBuf, listed: Option<Vec<String>>, lines: Vec<TestLine>, success: bool) -> TestReport {
+        let mut passed = HashSet::new();
+        let mut failed = HashMap::new();
+        let mut archive_errors = Vec::new();
+        let mut order = Vec::new();
+        for line in lines {
+            match line {
+                TestLine::Passed(path) => {
+                    order.push(path.clone());
+                    passed.insert(path);
+                }
+                TestLine::Failed(path, message) if path.is_empty() => archive_errors.push(message),
+                TestLine::Failed(path, message) => {
+                    order.push(path.clone());
+                    failed.insert(path, message);
+                }
+            }
+        }
+
+        let files = listed.unwrap_or_else(|| {
+            let mut seen = HashSet::new();
+            order.into_iter().filter(|p| seen.insert(p.clone())).collect()
+        });
+        let results = files
+            .into_iter()
+            .map(|path| {
+                let outcome = match failed.get(&path) {
+                    Some(message) => TestOutcome::Failed(message.clone()),
+                    None if success || passed.contains(&path) => TestOutcome::Passed,
+                    None => TestOutcome::Unverified,
+                };
+                (path, outcome)
+            })
+            .collect();
+
+        TestReport { archive, results, archive_errors, success }
+    }
+
+    pub fn count(&self, outcome: &TestOutcome) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, o)| std::mem::discriminant(o) == std::mem::discriminant(outcome))
+            .count()
+    }
+
+    pub fn summary(&self) -> String {
+        let failed = self.count(&TestOutcome::Failed(String::new()));
+        let unverified = self.count(&TestOutcome::Unverified);
+        if self.success && failed == 0 {
+            format!("Archive intègre : {} fichier(s) vérifié(s)", self.count(&TestOutcome::Passed))
+        } else {
+            format!(
+                "Archive endommagée : {} OK, {} en erreur, {} non vérifié(s)",
+                self.count(&TestOutcome::Passed),
+                failed,
+                unverified
+            )
+        }
+    }
+}
+
+/// Fichiers de l'archive (sans les dossiers), si l'outil sait la lister
+pub fn listed_files(archive: &Path) -> Option<Vec<String>> {
+    let backend = BackendKind::for_archive(archive).backend();
+    if !backend.capabilities().list {
+        return None;
+    }
+    listing::list_archive(archive)
+        .ok()
+        .map(|entries| entries.into_iter().filter(|e| !e.is_dir).map(|e| e.path).collect())
+}
+
+/// Test en mode console : sortie affichée en direct puis rapport fichier par fichier
+pub fn run_test_cli(archive: &Path) -> bool {
+    let backend = BackendKind::for_archive(archive).backend();
+    if !backend.is_available() {
+        eprintln!("Erreur : {} n'est pas installé correctement", backend.name());
+        return false;
+    }
+    let Some(mut cmd) = backend.capabilities().test.then(|| backend.test_command(archive)).flatten() else {
+        eprintln!("Erreur : {} ne sait pas tester une archive", backend.name());
+        return false;
+    };
+
+    let listed = listed_files(archive);
+    cmd.stdout(std::process::Stdio::piped()).stderr(std::process::Stdio::piped());
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("Erreur lors de l'exécution de la commande : {}", e);
+            return false;
+        }
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let readers: Vec<_> = [
+        child.stdout.take().map(|s| Box::new(s) as Box<dyn std::io::Read + Send>),
+        child.stderr.take().map(|s| Box::new(s) as Box<dyn std::io::Read + Send>),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|reader| {
+        let tx = tx.clone();
+        let kind = backend.kind();
+        thread::spawn(move || {
+            backend::read_output(reader, |line, complete| {
+                if complete {
+                    println!("{}", line);
+                    if let Some(result) = kind.backend().parse_test_line(line) {
+                        tx.send(result).ok();
+                    }
+                }
+            });
+        })
+    })
+    .collect();
+    drop(tx);
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    let success = child.wait().map(|s| s.success()).unwrap_or(false);
+    let report = TestReport::build(archive.to_path_buf(), listed, rx.into_iter().collect(), success);
+
+    println!("----------------------------------------");
+    for (path, outcome) in &report.results {
+        match outcome {
+            TestOutcome::Passed => println!("[OK]     {}", path),
+            TestOutcome::Failed(message) => println!("[ERREUR] {} : {}", path, message),
+            TestOutcome::Unverified => println!("[?]      {}", path),
+        }
+    }
+    for error in &report.archive_errors {
+        println!("[ERREUR] {}", error);
+    }
+    println!("----------------------------------------");
+    println!("{}", report.summary());
+    report.success && report.count(&TestOutcome::Failed(String::new())) == 0
+}
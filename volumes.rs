@@ -0,0 +1,277 @@
+// Archives en plusieurs volumes (.001, .002...). 7-Zip les crée et les lit lui-même ;
+// pour FreeArc et Sharky, l'archive est découpée après la compression et les volumes
+// sont réassemblés dans un fichier temporaire avant l'extraction ou le test.
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::CANCELLED;
+use crate::backend::BackendKind;
+use crate::diskspace::SpaceNeed;
+
+const MB: u64 = 1024 * 1024;
+const GB: u64 = 1024 * MB;
+
+/// Tailles proposées : (libellé, mot-clé en ligne de commande, octets)
+pub const VOLUME_PRESETS: &[(&str, &str, u64)] = &[
+    ("CD (700 Mo)", "cd", 700 * MB),
+    ("FAT32 (4 Go)", "fat32", 4 * GB - 1), // Taille maximale d'un fichier en FAT32
+    ("DVD (4,7 Go)", "dvd", 4_700_000_000),
+    ("Blu-ray (25 Go)", "bluray", 25_000_000_000),
+];
+
+/// Taille de volume : mot-clé (`fat32`, `dvd`...) ou nombre suivi de k, m ou g (`700m`, `4.5g`)
+pub fn parse_volume_size(text: &str) -> Option<u64> {
+    let text = text.trim().to_lowercase();
+    if let Some((_, _, size)) = VOLUME_PRESETS.iter().find(|(_, keyword, _)| *keyword == text) {
+        return Some(*size);
+    }
+    let text = text.trim_end_matches(['b', 'o']);
+    let (number, unit) = match text.char_indices().last()? {
+        (i, 'k') => (&text[..i], 1024),
+        (i, 'm') => (&text[..i], MB),
+        (i, 'g') => (&text[..i], GB),
+        _ => (text, 1),
+    };
+    let value: f64 = number.replace(',', ".").parse().ok()?;
+    let bytes = (value * unit as f64) as u64;
+    (bytes > 0).then_some(bytes)
+}
+
+/// `archive.arc` + 3 → `archive.arc.003`
+pub fn volume_path(archive: &Path, index: usize) -> PathBuf {
+    let mut name = archive.as_os_str().to_os_string();
+    name.push(format!(".{:03}", index));
+    PathBuf::from(name)
+}
+
+/// Numéro du volume ; il doit suivre une extension d'archive (`photos.7z.002`) :
+/// `rapport.2024` ou `scan.123` sont des fichiers ordinaires
+fn volume_index(path: &Path) -> Option<usize> {
+    let ext = path.extension()?.to_str()?;
+    if ext.len() < 3 || !ext.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let base = base_archive(path);
+    if !BackendKind::is_archive_extension(base.extension()?.to_str()?) {
+        return None;
+    }
+    ext.parse().ok()
+}
+
+/// Volume d'une archive découpée (`.001`, `.002`...)
+pub fn is_volume(path: &Path) -> bool {
+    volume_index(path).is_some()
+}
+
+pub fn is_first_volume(path: &Path) -> bool {
+    volume_index(path) == Some(1)
+}
+
+/// `archive.arc.001` → `archive.arc`
+pub fn base_archive(volume: &Path) -> PathBuf {
+    volume.with_extension("")
+}
+
+/// Refus d'ouvrir un volume autre que le premier : l'archive se lit à partir du `.001`
+pub fn not_first_volume(volume: &Path) -> String {
+    format!(
+        "{} n'est pas le premier volume de l'archive : ouvrez {}",
+        volume.display(),
+        volume_path(&base_archive(volume), 1).display()
+    )
+}
+
+/// Tous les volumes à partir du premier, après vérification qu'aucun ne manque
+/// et que tous sauf le dernier ont la même taille
+pub fn find_volumes(first: &Path) -> Result<Vec<PathBuf>, String> {
+    let base = base_archive(first);
+    let dir = base.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let base_name = base.file_name().and_then(|n| n.to_str()).ok_or("Nom d'archive invalide")?;
+
+    // Plus grand numéro présent, pour détecter un trou dans la séquence
+    let last = fs::read_dir(dir)
+        .map_err(|e| format!("Impossible de lire {} : {}", dir.display(), e))?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_str()?.to_string();
+            let suffix = name.strip_prefix(base_name)?.strip_prefix('.')?;
+            if suffix.len() < 3 || !suffix.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            suffix.parse::<usize>().ok()
+        })
+        .max()
+        .unwrap_or(0);
+
+    let mut volumes = Vec::new();
+    let mut missing = Vec::new();
+    for index in 1..=last.max(1) {
+        let path = volume_path(&base, index);
+        if path.is_file() {
+            volumes.push(path);
+        } else {
+            missing.push(format!("{:03}", index));
+        }
+    }
+    if !missing.is_empty() {
+        return Err(format!("Volume(s) manquant(s) pour {} : .{}", base_name, missing.join(", .")));
+    }
+
+    let size = |p: &Path| fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+    let expected = size(&volumes[0]);
+    if let Some(short) = volumes[..volumes.len() - 1].iter().find(|v| size(v) != expected) {
+        return Err(format!("Volume incomplet : {} (taille différente des précédents)", short.display()));
+    }
+    if volumes.len() > 1 && size(&volumes[volumes.len() - 1]) > expected {
+        return Err(format!("Dernier volume plus grand que les autres : {}", volumes[volumes.len() - 1].display()));
+    }
+    Ok(volumes)
+}
+
+/// Copie `limit` octets au plus, en vérifiant régulièrement l'annulation
+fn copy_chunk(reader: &mut impl Read, writer: &mut impl Write, limit: u64, cancel_flag: &AtomicBool) -> Result<u64, String> {
+    let mut buf = vec![0u8; MB as usize];
+    let mut copied = 0;
+    while copied < limit {
+        if cancel_flag.load(Ordering::SeqCst) {
+            return Err(CANCELLED.to_string());
+        }
+        let wanted = buf.len().min((limit - copied) as usize);
+        let read = reader.read(&mut buf[..wanted]).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        writer.write_all(&buf[..read]).map_err(|e| e.to_string())?;
+        copied += read as u64;
+    }
+    Ok(copied)
+}
+
+/// Découpe l'archive en volumes de `volume_size` octets puis supprime l'original
+pub fn split(archive: &Path, volume_size: u64, cancel_flag: &AtomicBool) -> Result<Vec<PathBuf>, String> {
+    let total = fs::metadata(archive).map_err(|e| format!("{} : {}", archive.display(), e))?.len();
+    let mut reader = File::open(archive).map_err(|e| format!("{} : {}", archive.display(), e))?;
+    let mut volumes = Vec::new();
+    let mut written = 0;
+    while written < total || volumes.is_empty() {
+        let path = volume_path(archive, volumes.len() + 1);
+        let result = File::create(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|mut file| copy_chunk(&mut reader, &mut file, volume_size, cancel_flag));
+        volumes.push(path);
+        let result = match result {
+            Ok(0) if written < total => Err("Lecture de l'archive interrompue".to_string()),
+            result => result,
+        };
+        match result {
+            Ok(copied) => written += copied,
+            Err(e) => {
+                // Volumes déjà écrits supprimés, celui en cours compris : l'archive reste entière
+                for volume in &volumes {
+                    let _ = fs::remove_file(volume);
+                }
+                return Err(e);
+            }
+        }
+    }
+    // Volumes restants d'une compression précédente plus volumineuse
+    let mut stale = volumes.len() + 1;
+    while fs::remove_file(volume_path(archive, stale)).is_ok() {
+        stale += 1;
+    }
+    fs::remove_file(archive).map_err(|e| format!("Impossible de supprimer {} : {}", archive.display(), e))?;
+    Ok(volumes)
+}
+
+/// Archive réassemblée à partir de ses volumes ; le fichier est supprimé avec la structure
+pub struct Reassembly {
+    volumes: Vec<PathBuf>,
+    pub joined: PathBuf,
+    done: bool,
+}
+
+impl Reassembly {
+    pub fn new(volumes: Vec<PathBuf>, joined: PathBuf) -> Self {
+        Self { volumes, joined, done: false }
+    }
+
+    pub fn volume_count(&self) -> usize {
+        self.volumes.len()
+    }
+
+    /// Espace occupé par le fichier réassemblé : somme des volumes
+    pub fn space_need(&self) -> SpaceNeed {
+        let size = self.volumes.iter().map(|v| fs::metadata(v).map(|m| m.len()).unwrap_or(0)).sum();
+        SpaceNeed::exact(&self.joined, size, "le réassemblage des volumes")
+    }
+
+    /// Besoins vérifiés ensemble avant le réassemblage : le fichier réassemblé, puis ce qui
+    /// sera écrit dans `dest`, estimé d'après la taille des volumes. La taille exacte n'est
+    /// connue qu'en listant le fichier réassemblé, qui occupe alors déjà sa place sur le disque
+    pub fn space_needs(&self, dest: &Path, label: &'static str) -> Vec<SpaceNeed> {
+        let joined = self.space_need();
+        let then = SpaceNeed::estimated(dest, joined.bytes, label);
+        vec![joined, then]
+    }
+
+    /// Concatène les volumes ; sans effet si c'est déjà fait
+    pub fn join(&mut self, cancel_flag: &AtomicBool) -> Result<(), String> {
+        if self.done {
+            return Ok(());
+        }
+        let mut output = File::create(&self.joined).map_err(|e| format!("{} : {}", self.joined.display(), e))?;
+        for volume in &self.volumes {
+            let mut input = File::open(volume).map_err(|e| format!("{} : {}", volume.display(), e))?;
+            copy_chunk(&mut input, &mut output, u64::MAX, cancel_flag)?;
+        }
+        self.done = true;
+        Ok(())
+    }
+}
+
+impl Drop for Reassembly {
+    fn drop(&mut self) {
+        if self.joined.exists() {
+            let _ = fs::remove_file(&self.joined);
+        }
+    }
+}
+
+/// Prépare l'extraction ou le test d'une archive découpée. None si le fichier n'est pas
+/// un premier volume ou si l'outil lit les volumes lui-même (après vérification).
+/// Le fichier réassemblé est placé à côté des volumes.
+pub fn prepare_reassembly(archive: &Path, native: bool, tag: u64) -> Result<Option<Reassembly>, String> {
+    if is_volume(archive) && !is_first_volume(archive) {
+        return Err(not_first_volume(archive));
+    }
+    if !is_first_volume(archive) {
+        return Ok(None);
+    }
+    let volumes = find_volumes(archive)?;
+    if native {
+        return Ok(None);
+    }
+    let base = base_archive(archive);
+    let name = base.file_name().and_then(|n| n.to_str()).unwrap_or("archive");
+    let joined = base.with_file_name(format!("stelarc_join_{}_{}", tag, name));
+    Ok(Some(Reassembly::new(volumes, joined)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn volumes_follow_an_archive_extension() {
+        assert!(is_first_volume(Path::new("photos.7z.001")));
+        assert!(is_volume(Path::new("dossier/sauvegarde.ARC.002")));
+        assert!(!is_first_volume(Path::new("sauvegarde.arc.002")));
+        assert!(!is_volume(Path::new("rapport.2024")));
+        assert!(!is_volume(Path::new("scan.123")));
+        assert!(!is_volume(Path::new("notes.txt.001")));
+        assert!(!is_volume(Path::new("photos.7z.01")));
+    }
+}